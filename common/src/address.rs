@@ -1,4 +1,4 @@
-use crate::lazy::{ACP_CODE_HASH, PW_LOCK_CODE_HASH, SECP256K1_CODE_HASH};
+use crate::lazy::{ACP_CODE_HASH, OMNI_LOCK_CODE_HASH, PW_LOCK_CODE_HASH, SECP256K1_CODE_HASH};
 use crate::{NetworkType, MULTISIG_TYPE_HASH};
 
 use anyhow::Result;
@@ -288,6 +288,17 @@ pub fn is_secp256k1(address: &Address) -> bool {
     }
 }
 
+pub fn is_multisig(address: &Address) -> bool {
+    match address.payload() {
+        AddressPayload::Short { index, .. } => index == &CodeHashIndex::Multisig,
+        AddressPayload::Full {
+            hash_type,
+            code_hash,
+            ..
+        } => hash_type == &ScriptHashType::Type && code_hash == &MULTISIG_TYPE_HASH.pack(),
+    }
+}
+
 pub fn is_acp(address: &Address) -> bool {
     match address.payload() {
         AddressPayload::Short { index, .. } => index == &CodeHashIndex::AnyoneCanPay,
@@ -320,6 +331,24 @@ pub fn is_pw_lock(address: &Address) -> bool {
     }
 }
 
+pub fn is_omni_lock(address: &Address) -> bool {
+    match address.payload() {
+        AddressPayload::Short { .. } => false,
+        AddressPayload::Full {
+            hash_type,
+            code_hash,
+            ..
+        } => {
+            hash_type == &ScriptHashType::Type
+                && OMNI_LOCK_CODE_HASH
+                    .get()
+                    .map_or(false, |omni_lock_code_hash| {
+                        code_hash == &omni_lock_code_hash.pack()
+                    })
+        }
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct Address {
     network: NetworkType,
@@ -23,11 +23,14 @@ pub const NETWORK_STAGING: &str = "ckb_staging";
 pub const NETWORK_DEV: &str = "ckb_dev";
 
 pub const SECP256K1: &str = "secp256k1_blake160";
+pub const MULTISIG: &str = "secp256k1_multisig";
 pub const SUDT: &str = "sudt";
+pub const XUDT: &str = "xudt";
 pub const ACP: &str = "anyone_can_pay";
 pub const CHEQUE: &str = "cheque";
 pub const DAO: &str = "dao";
 pub const PW_LOCK: &str = "pw_lock";
+pub const OMNI_LOCK: &str = "omni_lock";
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 enum ErrorKind {
@@ -81,6 +84,10 @@ impl<T: Debug + Display> MercuryError<T> {
     fn new(kind: ErrorKind, error: T) -> Self {
         MercuryError { kind, error }
     }
+
+    pub fn error(&self) -> &T {
+        &self.error
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
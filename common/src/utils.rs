@@ -5,6 +5,7 @@ use ckb_types::{packed, U256};
 use derive_more::Display;
 
 use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ScriptInfo {
@@ -89,6 +90,14 @@ pub fn u256_low_u64(u: U256) -> u64 {
     u.0[0]
 }
 
+/// The milliseconds since the unix epoch.
+pub fn unix_timestamp_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -4,7 +4,8 @@ use common::{async_trait, MercuryError, Result};
 use core_synchronization::SyncAdapter;
 
 use ckb_jsonrpc_types::{
-    BlockView, EpochView, JsonBytes, LocalNode, RawTxPool, TransactionWithStatus, Uint32, Uint64,
+    BlockView, EpochView, JsonBytes, LocalNode, OutputsValidator, RawTxPool, Transaction,
+    TransactionWithStatus, Uint32, Uint64,
 };
 use ckb_types::{core, core::BlockNumber, packed, prelude::Entity, H256};
 use jsonrpc_core::types::{
@@ -25,6 +26,7 @@ const GET_BLOCK_BY_NUMBER_REQ: &str = "get_block_by_number";
 const GET_TIP_BLOCK_NUMBER_REQ: &str = "get_tip_block_number";
 const GET_EPOCH_BY_NUMBER_REQ: &str = "get_epoch_by_number";
 const GET_CURRENT_EPOCH_REQ: &str = "get_current_epoch";
+const SEND_TRANSACTION_REQ: &str = "send_transaction";
 
 #[derive(Clone, Debug)]
 pub struct CkbRpcClient {
@@ -136,6 +138,16 @@ impl CkbRpc for CkbRpcClient {
         let resp = self.rpc_exec(&request, id).await?;
         handle_response(resp)
     }
+
+    async fn send_transaction(
+        &self,
+        tx: Transaction,
+        outputs_validator: Option<OutputsValidator>,
+    ) -> Result<H256> {
+        let (id, request) = self.build_request(SEND_TRANSACTION_REQ, (tx, outputs_validator))?;
+        let resp = self.rpc_exec(&request, id).await?;
+        handle_response(resp)
+    }
 }
 
 impl CkbRpcClient {
@@ -280,7 +292,11 @@ fn handle_output<T: DeserializeOwned>(output: Output) -> Result<T> {
     let value = match output {
         Output::Success(succ) => succ.result,
         Output::Failure(fail) => {
-            return Err(MercuryError::rpc(ClientError::DecodeJson(fail.error.to_string())).into())
+            return Err(MercuryError::rpc(ClientError::NodeError {
+                code: fail.error.code.code(),
+                message: fail.error.message,
+            })
+            .into())
         }
     };
 
@@ -1,4 +1,4 @@
-use common::derive_more::Display;
+use common::{anyhow, derive_more::Display, MercuryError};
 
 use serde::{Deserialize, Serialize};
 
@@ -12,4 +12,19 @@ pub enum ClientError {
 
     #[display(fmt = "Decode json error {}", _0)]
     DecodeJson(String),
+
+    #[display(fmt = "Ckb node error {}: {}", code, message)]
+    NodeError { code: i64, message: String },
+}
+
+/// The error code of the CKB node refusing a transaction which is already in the tx pool.
+pub const POOL_REJECTED_DUPLICATED_TRANSACTION: i64 = -1107;
+
+/// The error code of the CKB node if the request is handled and refused by the node,
+/// or `None` if the node is unreachable.
+pub fn node_error_code(error: &anyhow::Error) -> Option<i64> {
+    match error.downcast_ref::<MercuryError<ClientError>>()?.error() {
+        ClientError::NodeError { code, .. } => Some(*code),
+        _ => None,
+    }
 }
@@ -6,7 +6,8 @@ pub use client::CkbRpcClient;
 use common::{async_trait, Result};
 
 use ckb_jsonrpc_types::{
-    BlockView, EpochView, LocalNode, RawTxPool, TransactionWithStatus, Uint64,
+    BlockView, EpochView, LocalNode, OutputsValidator, RawTxPool, Transaction,
+    TransactionWithStatus, Uint64,
 };
 use ckb_types::{core, H256};
 
@@ -33,4 +34,10 @@ pub trait CkbRpc: Sync + Send + 'static {
     async fn get_current_epoch(&self) -> Result<EpochView>;
 
     async fn get_block(&self, block_hash: H256, use_hex_format: bool) -> Result<Option<BlockView>>;
+
+    async fn send_transaction(
+        &self,
+        tx: Transaction,
+        outputs_validator: Option<OutputsValidator>,
+    ) -> Result<H256>;
 }
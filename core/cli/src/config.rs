@@ -21,6 +21,9 @@ pub struct NetworkConfig {
 
     #[serde(default = "default_listen_uri")]
     pub listen_uri: String,
+
+    #[serde(default)]
+    pub ws_listen_uri: Option<String>,
 }
 
 #[derive(Deserialize, Default, Clone, Debug)]
@@ -121,6 +124,9 @@ pub struct MercuryConfig {
 
     #[serde(default = "default_is_pprof_enabled")]
     pub is_pprof_enabled: bool,
+
+    #[serde(default = "default_input_reservation_ttl")]
+    pub input_reservation_ttl: u64,
 }
 
 impl MercuryConfig {
@@ -222,6 +228,10 @@ fn default_is_pprof_enabled() -> bool {
     false
 }
 
+fn default_input_reservation_ttl() -> u64 {
+    60u64
+}
+
 fn default_file_size_limit() -> u64 {
     1073741824 // 1GiB
 }
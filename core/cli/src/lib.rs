@@ -61,6 +61,12 @@ impl<'a> Cli<'a> {
                     .help("Mercury listen uri")
                     .takes_value(true),
             )
+            .arg(
+                Arg::with_name("ws_listen_uri")
+                    .long("ws_listen_uri")
+                    .help("Mercury websocket subscription listen uri")
+                    .takes_value(true),
+            )
             .arg(
                 Arg::with_name("ckb_uri")
                     .long("ckb_uri")
@@ -121,11 +127,21 @@ impl<'a> Cli<'a> {
             self.config.cheque_since,
             self.config.pool_cache_size,
             self.config.is_pprof_enabled,
+            self.config
+                .extensions_config
+                .iter()
+                .map(|config| (config.extension_name.clone(), config.config.clone()))
+                .collect(),
+            self.config.input_reservation_ttl,
         );
 
-        let stop_handle = service
+        let (stop_handle, ws_stop_handle) = service
             .init(
                 self.parse_cmd_args("listen_uri", self.config.network_config.listen_uri.clone()),
+                self.matches
+                    .value_of("ws_listen_uri")
+                    .map(ToString::to_string)
+                    .or_else(|| self.config.network_config.ws_listen_uri.clone()),
                 self.config.db_config.db_type.clone(),
                 self.config.db_config.db_name.clone(),
                 self.parse_cmd_args("db_host", self.config.db_config.db_host.clone()),
@@ -155,6 +171,9 @@ impl<'a> Cli<'a> {
             .expect("stop server handle")
             .await
             .expect("join server handle");
+        if let Some(ws_stop_handle) = ws_stop_handle {
+            ws_stop_handle.stop().expect("stop ws server handle").await;
+        }
         info!("Closing!");
     }
 
@@ -62,6 +62,9 @@ pub enum CoreError {
     #[display(fmt = "Can not find unlocked dao withdrawing cell")]
     CannotFindUnlockedWithdrawingCell,
 
+    #[display(fmt = "Invalid dao cell {}", _0)]
+    InvalidDaoCell(String),
+
     #[display(fmt = "Cannot find transaction by hash")]
     CannotFindTransactionByHash,
 
@@ -145,6 +148,24 @@ pub enum CoreError {
 
     #[display(fmt = "When issuing udt from items must contain owner item")]
     FromNotContainOwner,
+
+    #[display(fmt = "Missing multisig config of lock args {}", _0)]
+    MissingMultisigConfig(String),
+
+    #[display(fmt = "Invalid multisig config {}", _0)]
+    InvalidMultisigConfig(String),
+
+    #[display(fmt = "Can not find cheque cell")]
+    CannotFindChequeCell,
+
+    #[display(fmt = "Invalid cheque cell {}", _0)]
+    InvalidChequeCell(String),
+
+    #[display(fmt = "Exceed the maximum transaction size {}", _0)]
+    ExceedMaxTxSize(String),
+
+    #[display(fmt = "Not enough cells to consolidate: {}", _0)]
+    NotEnoughCellsToConsolidate(String),
 }
 
 impl RpcError for CoreError {
@@ -179,6 +200,10 @@ impl RpcError for CoreError {
             CoreError::CkbIsNotEnough(_) => -11029,
             CoreError::UDTIsNotEnough(_) => -11030,
             CoreError::UnsupportTransferMode(_) => -11031,
+            CoreError::MissingMultisigConfig(_) => -11032,
+            CoreError::InvalidMultisigConfig(_) => -11033,
+            CoreError::ExceedMaxTxSize(_) => -11034,
+            CoreError::NotEnoughCellsToConsolidate(_) => -11035,
 
             CoreError::MissingConsumedInfo => -10020,
 
@@ -198,6 +223,7 @@ impl RpcError for CoreError {
 
             CoreError::NeedAtLeastOneFrom => -10070,
             CoreError::InvalidDAOCapacity => -10071,
+            CoreError::InvalidDaoCell(_) => -10072,
             CoreError::CannotFindDepositCell => -11072,
 
             CoreError::CannotFindUnlockedWithdrawingCell => -10110,
@@ -205,6 +231,9 @@ impl RpcError for CoreError {
 
             CoreError::NeedAtLeastOneTo => -10120,
             CoreError::FromNotContainOwner => -10121,
+
+            CoreError::CannotFindChequeCell => -10130,
+            CoreError::InvalidChequeCell(_) => -10131,
         }
     }
 
@@ -1,35 +1,52 @@
 mod adjust_account;
-mod build_tx;
+pub(crate) mod build_tx;
+mod consolidate;
 mod operation;
+mod payout;
 mod query;
+mod statement;
+mod sweep;
 pub(crate) mod utils;
 pub(crate) mod utils_types;
+mod verify_tx;
+pub(crate) mod webhook;
 
 use crate::r#impl::build_tx::calculate_tx_size;
+use crate::r#impl::verify_tx::build_consensus;
 use crate::{error::CoreError, MercuryRpcServer};
 
+use ckb_chain_spec::consensus::Consensus;
+use ckb_jsonrpc_types::OutPoint;
 use ckb_types::core::RationalU256;
 use ckb_types::{packed, prelude::*, H160, H256};
 use clap::crate_version;
 use common::lazy::{
-    ACP_CODE_HASH, CHEQUE_CODE_HASH, DAO_CODE_HASH, PW_LOCK_CODE_HASH, SECP256K1_CODE_HASH,
-    SUDT_CODE_HASH,
+    ACP_CODE_HASH, CHEQUE_CODE_HASH, DAO_CODE_HASH, OMNI_LOCK_CODE_HASH, PW_LOCK_CODE_HASH,
+    SECP256K1_CODE_HASH, SUDT_CODE_HASH, XUDT_CODE_HASH,
 };
 use common::utils::ScriptInfo;
 use common::{
     async_trait, hash::blake2b_160, Address, AddressPayload, NetworkType, Order, ACP, CHEQUE, DAO,
-    PW_LOCK, SECP256K1, SUDT,
+    OMNI_LOCK, PW_LOCK, SECP256K1, SUDT, XUDT,
 };
 use core_ckb_client::CkbRpc;
 use core_rpc_types::error::MercuryRpcError;
 use core_rpc_types::{
     indexer,
     uints::{Uint16, Uint64},
-    AdjustAccountPayload, BlockInfo, DaoClaimPayload, DaoDepositPayload, DaoWithdrawPayload,
-    GetAccountInfoPayload, GetAccountInfoResponse, GetBalancePayload, GetBalanceResponse,
-    GetBlockInfoPayload, GetSpentTransactionPayload, GetTransactionInfoResponse, MercuryInfo,
-    PaginationResponse, QueryTransactionsPayload, SimpleTransferPayload, SudtIssuePayload,
-    SyncState, TransactionCompletionResponse, TransferPayload, TxView,
+    AccountStatement, AddressLabel, AdjustAccountPayload, BatchPayoutPayload, BlockInfo,
+    ChequeClaimPayload, ChequeInfo, ChequeTransactionResponse, ChequeWithdrawPayload,
+    ConsolidatePayload, DaoClaimPayload, DaoDepositPayload, DaoPosition, DaoWithdrawPayload,
+    Extension, GetAccountInfoPayload, GetAccountInfoResponse, GetAccountStatementPayload,
+    GetBalancePayload, GetBalanceResponse, GetBlockInfoPayload, GetBlocksInfoPayload,
+    GetChainEventsPayload, GetChainEventsResponse, GetChequesPayload, GetDaoPositionsPayload,
+    GetSpentTransactionPayload, GetSubmittedTransactionsPayload, GetTransactionInfoResponse,
+    JsonItem, MercuryInfo, PaginationResponse, QueryTransactionsPayload, QueryTransactionsResponse,
+    RegisterWebhookPayload, ReleaseInputReservationPayload, SearchAddressLabelsPayload,
+    SendTransactionPayload, SetAddressLabelPayload, SimpleTransferPayload, SubmittedTransaction,
+    SudtIssuePayload, SweepPayload, SweepTransactionResponse, SyncState,
+    TransactionCompletionResponse, TransferPayload, TxView, VerifyTransactionPayload,
+    VerifyTransactionResponse, Webhook,
 };
 use core_storage::{DBInfo, RelationalStorage};
 use jsonrpsee_core::{Error, RpcResult};
@@ -39,6 +56,7 @@ use pprof::ProfilerGuard;
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
 lazy_static::lazy_static! {
     pub static ref PROFILER_GUARD: std::sync::Mutex<Option<ProfilerGuard<'static>>> = std::sync::Mutex::new(None);
@@ -54,6 +72,9 @@ pub struct MercuryRpcImpl<C> {
     sync_state: Arc<RwLock<SyncState>>,
     pool_cache_size: u16,
     is_pprof_enabled: bool,
+    enabled_extensions: Vec<Extension>,
+    consensus: Consensus,
+    input_reservation_ttl: Duration,
 }
 
 #[async_trait]
@@ -66,6 +87,21 @@ impl<C: CkbRpc> MercuryRpcServer for MercuryRpcImpl<C> {
         self.inner_get_block_info(payload).await.map_err(Into::into)
     }
 
+    async fn get_blocks_info(&self, payload: GetBlocksInfoPayload) -> RpcResult<Vec<BlockInfo>> {
+        self.inner_get_blocks_info(payload)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn get_chain_events(
+        &self,
+        payload: GetChainEventsPayload,
+    ) -> RpcResult<GetChainEventsResponse> {
+        self.inner_get_chain_events(payload)
+            .await
+            .map_err(Into::into)
+    }
+
     async fn get_transaction_info(&self, tx_hash: H256) -> RpcResult<GetTransactionInfoResponse> {
         self.inner_get_transaction_info(tx_hash)
             .await
@@ -75,12 +111,37 @@ impl<C: CkbRpc> MercuryRpcServer for MercuryRpcImpl<C> {
     async fn query_transactions(
         &self,
         payload: QueryTransactionsPayload,
-    ) -> RpcResult<PaginationResponse<TxView>> {
+    ) -> RpcResult<QueryTransactionsResponse> {
         self.inner_query_transactions(payload)
             .await
             .map_err(Into::into)
     }
 
+    async fn get_cheques(
+        &self,
+        payload: GetChequesPayload,
+    ) -> RpcResult<PaginationResponse<ChequeInfo>> {
+        self.inner_get_cheques(payload).await.map_err(Into::into)
+    }
+
+    async fn get_dao_positions(
+        &self,
+        payload: GetDaoPositionsPayload,
+    ) -> RpcResult<PaginationResponse<DaoPosition>> {
+        self.inner_get_dao_positions(payload)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn get_account_statement(
+        &self,
+        payload: GetAccountStatementPayload,
+    ) -> RpcResult<AccountStatement> {
+        self.inner_get_account_statement(payload)
+            .await
+            .map_err(Into::into)
+    }
+
     async fn get_account_info(
         &self,
         payload: GetAccountInfoPayload,
@@ -117,6 +178,33 @@ impl<C: CkbRpc> MercuryRpcServer for MercuryRpcImpl<C> {
             .map_err(Into::into)
     }
 
+    async fn build_batch_payout(
+        &self,
+        payload: BatchPayoutPayload,
+    ) -> RpcResult<Vec<TransactionCompletionResponse>> {
+        self.inner_build_batch_payout(payload)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn build_sweep_transaction(
+        &self,
+        payload: SweepPayload,
+    ) -> RpcResult<SweepTransactionResponse> {
+        self.inner_build_sweep_transaction(payload)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn build_consolidate_transaction(
+        &self,
+        payload: ConsolidatePayload,
+    ) -> RpcResult<TransactionCompletionResponse> {
+        self.inner_build_consolidate_transaction(payload)
+            .await
+            .map_err(Into::into)
+    }
+
     async fn register_addresses(&self, addresses: Vec<String>) -> RpcResult<Vec<H160>> {
         let mut inputs: Vec<(H160, String)> = vec![];
         for addr_str in addresses {
@@ -131,12 +219,50 @@ impl<C: CkbRpc> MercuryRpcServer for MercuryRpcImpl<C> {
             .map_err(Into::into)
     }
 
+    async fn set_address_labels(
+        &self,
+        payload: Vec<SetAddressLabelPayload>,
+    ) -> RpcResult<Vec<JsonItem>> {
+        self.inner_set_address_labels(payload)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn get_address_labels(&self, items: Vec<JsonItem>) -> RpcResult<Vec<AddressLabel>> {
+        self.inner_get_address_labels(items)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn search_address_labels(
+        &self,
+        payload: SearchAddressLabelsPayload,
+    ) -> RpcResult<PaginationResponse<AddressLabel>> {
+        self.inner_search_address_labels(payload)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn register_webhook(&self, payload: RegisterWebhookPayload) -> RpcResult<Uint64> {
+        self.inner_register_webhook(payload)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn get_webhooks(&self) -> RpcResult<Vec<Webhook>> {
+        self.inner_get_webhooks().await.map_err(Into::into)
+    }
+
+    async fn remove_webhook(&self, id: Uint64) -> RpcResult<bool> {
+        self.inner_remove_webhook(id).await.map_err(Into::into)
+    }
+
     async fn get_mercury_info(&self) -> RpcResult<MercuryInfo> {
         Ok(MercuryInfo {
             network_type: self.network_type,
             mercury_version: crate_version!().to_string(),
             ckb_node_version: self.ckb_client.local_node_info().await?.version,
-            enabled_extensions: vec![],
+            enabled_extensions: self.enabled_extensions.clone(),
         })
     }
 
@@ -171,6 +297,33 @@ impl<C: CkbRpc> MercuryRpcServer for MercuryRpcImpl<C> {
             .map_err(Into::into)
     }
 
+    async fn build_cheque_claim_transaction(
+        &self,
+        payload: ChequeClaimPayload,
+    ) -> RpcResult<ChequeTransactionResponse> {
+        self.inner_build_cheque_claim_transaction(payload)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn build_cheque_withdraw_transaction(
+        &self,
+        payload: ChequeWithdrawPayload,
+    ) -> RpcResult<ChequeTransactionResponse> {
+        self.inner_build_cheque_withdraw_transaction(payload)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn verify_transaction(
+        &self,
+        payload: VerifyTransactionPayload,
+    ) -> RpcResult<VerifyTransactionResponse> {
+        self.inner_verify_transaction(payload)
+            .await
+            .map_err(Into::into)
+    }
+
     async fn build_sudt_issue_transaction(
         &self,
         payload: SudtIssuePayload,
@@ -189,6 +342,30 @@ impl<C: CkbRpc> MercuryRpcServer for MercuryRpcImpl<C> {
             .map_err(Into::into)
     }
 
+    async fn send_transaction(&self, payload: SendTransactionPayload) -> RpcResult<H256> {
+        self.inner_send_transaction(payload)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn release_input_reservation(
+        &self,
+        payload: ReleaseInputReservationPayload,
+    ) -> RpcResult<Vec<OutPoint>> {
+        self.inner_release_input_reservation(payload)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn get_submitted_transactions(
+        &self,
+        payload: GetSubmittedTransactionsPayload,
+    ) -> RpcResult<PaginationResponse<SubmittedTransaction>> {
+        self.inner_get_submitted_transactions(payload)
+            .await
+            .map_err(Into::into)
+    }
+
     async fn get_tip(&self) -> RpcResult<Option<indexer::Tip>> {
         self.inner_get_tip().await.map_err(Into::into)
     }
@@ -341,6 +518,8 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         sync_state: Arc<RwLock<SyncState>>,
         pool_cache_size: u16,
         is_pprof_enabled: bool,
+        enabled_extensions: Vec<Extension>,
+        input_reservation_ttl: u64,
     ) -> Self {
         load_code_hash(&builtin_scripts);
         MercuryRpcImpl {
@@ -353,6 +532,9 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             sync_state,
             pool_cache_size,
             is_pprof_enabled,
+            enabled_extensions,
+            consensus: build_consensus(network_type),
+            input_reservation_ttl: Duration::from_secs(input_reservation_ttl),
         }
     }
 }
@@ -380,6 +562,10 @@ pub fn load_code_hash(builtin_scripts: &HashMap<String, ScriptInfo>) {
             .code_hash()
             .unpack(),
     );
+    // xUDT is optional so that configs without the script keep working
+    if let Some(xudt) = builtin_scripts.get(XUDT) {
+        let _ = XUDT_CODE_HASH.set(xudt.script.code_hash().unpack());
+    }
     let _ = ACP_CODE_HASH.set(
         builtin_scripts
             .get(ACP)
@@ -416,4 +602,8 @@ pub fn load_code_hash(builtin_scripts: &HashMap<String, ScriptInfo>) {
             .code_hash()
             .unpack(),
     );
+    // omni lock is optional as well
+    if let Some(omni_lock) = builtin_scripts.get(OMNI_LOCK) {
+        let _ = OMNI_LOCK_CODE_HASH.set(omni_lock.script.code_hash().unpack());
+    }
 }
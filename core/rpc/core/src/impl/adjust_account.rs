@@ -2,15 +2,15 @@ use crate::r#impl::utils::map_json_items;
 use crate::r#impl::{calculate_tx_size, utils, utils_types::TransferComponents};
 use crate::{error::CoreError, InnerResult, MercuryRpcImpl};
 
-use ckb_types::core::TransactionView;
+use ckb_types::core::{Capacity, TransactionView};
 use ckb_types::{bytes::Bytes, packed, prelude::*, H256};
 use common::address::{is_acp, is_pw_lock};
 use common::hash::blake2b_256_to_160;
 use common::lazy::{ACP_CODE_HASH, PW_LOCK_CODE_HASH, SECP256K1_CODE_HASH};
 use common::utils::decode_udt_amount;
-use common::{DetailedCell, PaginationRequest, ACP, PW_LOCK, SECP256K1, SUDT};
+use common::{DetailedCell, PaginationRequest, ACP, PW_LOCK, SECP256K1};
 use core_ckb_client::CkbRpc;
-use core_rpc_types::consts::{ckb, DEFAULT_FEE_RATE, STANDARD_SUDT_CAPACITY};
+use core_rpc_types::consts::{ckb, DEFAULT_FEE_RATE};
 use core_rpc_types::{
     AccountType, AdjustAccountPayload, AssetType, GetAccountInfoPayload, GetAccountInfoResponse,
     Item, ScriptGroup, TransactionCompletionResponse,
@@ -74,6 +74,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                 },
                 payload.clone(),
                 payload.fee_rate.map(Into::into),
+                payload.verify.unwrap_or(false),
             )
             .await
             .map(Some)
@@ -86,7 +87,13 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                 )
                 .await?;
 
-            Ok(Some(TransactionCompletionResponse::new(res.0, res.1)))
+            self.build_transaction_completion_response(
+                res.0,
+                res.1,
+                payload.verify.unwrap_or(false),
+            )
+            .await
+            .map(Some)
         }
     }
 
@@ -103,21 +110,27 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         let extra_ckb = payload.extra_ckb.map(Into::into).unwrap_or_else(|| ckb(1));
         let lock_script = self.get_acp_lock_by_item(&item).await?;
 
-        transfer_components.script_deps.insert(SUDT.to_string());
-
         let sudt_type_script = self
             .build_sudt_type_script(blake2b_256_to_160(&payload.asset_info.udt_hash))
             .await?;
+        let acp_capacity = utils::calculate_cell_capacity(
+            &lock_script,
+            &Some(sudt_type_script.clone()).pack(),
+            Capacity::bytes(16).expect("generate capacity"),
+        );
 
         for _i in 0..acp_need_count {
-            utils::build_cell_for_output(
-                STANDARD_SUDT_CAPACITY + extra_ckb,
+            let output_index = utils::build_cell_for_output(
+                acp_capacity + extra_ckb,
                 lock_script.clone(),
                 Some(sudt_type_script.clone()),
                 Some(0),
                 &mut transfer_components.outputs,
                 &mut transfer_components.outputs_data,
             )?;
+            transfer_components.script_deps.insert(
+                utils::get_udt_script_name(&transfer_components.outputs[output_index]).to_string(),
+            );
         }
 
         // balance capacity
@@ -202,8 +215,14 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             }
             Bytes::new()
         } else {
-            Bytes::from(input_udt_sum.to_le_bytes().to_vec())
+            // keep the extension data of the output cell, e.g. xUDT data
+            let mut data = input_udt_sum.to_le_bytes().to_vec();
+            if output.cell_data.len() > 16 {
+                data.extend_from_slice(&output.cell_data[16..]);
+            }
+            Bytes::from(data)
         };
+        let udt_script_name = utils::get_udt_script_name(&inputs[0].cell_output);
         let output = output
             .cell_output
             .as_builder()
@@ -211,7 +230,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             .build();
 
         let mut script_deps = BTreeSet::new();
-        script_deps.insert(SUDT.to_string());
+        script_deps.insert(udt_script_name.to_string());
         let lock_code_hash: H256 = inputs[0].cell_output.lock().code_hash().unpack();
         if lock_code_hash == *ACP_CODE_HASH.get().expect("get secp code hash") {
             script_deps.insert(ACP.to_string());
@@ -1,32 +1,35 @@
 use crate::r#impl::utils::{
     build_cell_for_output, build_cheque_args, calculate_cell_capacity,
-    calculate_unlock_epoch_number, dedup_json_items, is_dao_withdraw_unlock, map_json_items,
-    to_since,
+    calculate_unlock_epoch_number, dedup_json_items, get_udt_script_name, has_cheque_lock_args,
+    is_dao_withdraw_unlock, is_omni_lock_script, is_udt_code_hash, map_json_items, new_build_id,
+    rebuild_udt_cell_for_output, to_since, udt_asset_info, BUILD_ID,
 };
 use crate::r#impl::{address_to_script, utils_types, utils_types::TransferComponents};
 use crate::{error::CoreError, InnerResult, MercuryRpcImpl};
 
-use ckb_jsonrpc_types::TransactionView as JsonTransactionView;
+use ckb_jsonrpc_types::{OutPoint, TransactionView as JsonTransactionView};
 use ckb_types::core::{
     Capacity, EpochNumberWithFraction, ScriptHashType, TransactionBuilder, TransactionView,
 };
 use ckb_types::{bytes::Bytes, constants::TX_VERSION, packed, prelude::*, H160, H256, U256};
-use common::address::{is_acp, is_pw_lock, is_secp256k1};
+use common::address::{is_acp, is_multisig, is_pw_lock, is_secp256k1};
 use common::hash::blake2b_256_to_160;
 use common::lazy::{
-    ACP_CODE_HASH, DAO_CODE_HASH, PW_LOCK_CODE_HASH, SECP256K1_CODE_HASH, SUDT_CODE_HASH,
+    ACP_CODE_HASH, CHEQUE_CODE_HASH, DAO_CODE_HASH, PW_LOCK_CODE_HASH, SECP256K1_CODE_HASH,
 };
-use common::utils::{decode_udt_amount, encode_udt_amount};
+use common::utils::{decode_udt_amount, encode_udt_amount, u256_low_u64};
 use common::{
-    Address, DetailedCell, PaginationRequest, ACP, CHEQUE, DAO, PW_LOCK, SECP256K1, SUDT,
+    Address, DetailedCell, PaginationRequest, ACP, CHEQUE, DAO, MULTISIG_TYPE_HASH, PW_LOCK,
+    SECP256K1, SUDT,
 };
 use core_ckb_client::CkbRpc;
 use core_rpc_types::consts::{
     BYTE_SHANNONS, DEFAULT_FEE_RATE, INIT_ESTIMATE_FEE, MAX_ITEM_NUM, MIN_DAO_CAPACITY,
 };
-use core_rpc_types::lazy::CURRENT_EPOCH_NUMBER;
+use core_rpc_types::lazy::{CURRENT_EPOCH_NUMBER, INPUT_RESERVATIONS};
 use core_rpc_types::{
-    AssetInfo, AssetType, DaoClaimPayload, DaoDepositPayload, DaoWithdrawPayload, ExtraType, Item,
+    AssetInfo, AssetType, ChequeClaimPayload, ChequeClaimTarget, ChequeTransactionResponse,
+    ChequeWithdrawPayload, DaoClaimPayload, DaoDepositPayload, DaoWithdrawPayload, ExtraType, Item,
     JsonItem, OutputCapacityProvider, PayFee, ScriptGroup, ScriptGroupType, SimpleTransferPayload,
     SinceConfig, SinceFlag, SinceType, SudtIssuePayload, ToInfo, TransactionCompletionResponse,
     TransferPayload,
@@ -64,6 +67,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             Self::prebuild_dao_deposit_transaction,
             payload.clone(),
             payload.fee_rate.map(Into::into),
+            payload.verify.unwrap_or(false),
         )
         .await
     }
@@ -114,11 +118,15 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         if payload.from.len() > MAX_ITEM_NUM {
             return Err(CoreError::ExceedMaxItemNum.into());
         }
+        if payload.amount.map(u64::from) == Some(0) {
+            return Err(CoreError::AmountMustPositive.into());
+        }
         dedup_json_items(&mut payload.from);
         self.build_transaction_with_adjusted_fee(
             Self::prebuild_dao_withdraw_transaction,
             payload.clone(),
             payload.fee_rate.map(Into::into),
+            payload.verify.unwrap_or(false),
         )
         .await
     }
@@ -165,15 +173,21 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         deposit_cells.retain(|i| set.insert(i.clone()));
 
         let tip_epoch_number = (**CURRENT_EPOCH_NUMBER.load()).clone();
-        let deposit_cells = deposit_cells
-            .into_iter()
-            .filter(|cell| cell.cell_data == Box::new([0u8; 8]).to_vec())
-            .filter(|cell| {
-                (EpochNumberWithFraction::from_full_value(cell.epoch_number).to_rational()
-                    + U256::from(4u64))
-                    < tip_epoch_number
-            })
-            .collect::<Vec<_>>();
+        let deposit_cells = select_dao_cells(
+            deposit_cells,
+            payload.out_points,
+            |cell| {
+                cell.cell_data == Box::new([0u8; 8]).to_vec()
+                    && (EpochNumberWithFraction::from_full_value(cell.epoch_number).to_rational()
+                        + U256::from(4u64))
+                        < tip_epoch_number
+            },
+            "is not a deposit cell which can be withdrawn",
+        )?;
+        let deposit_cells = match payload.amount {
+            Some(amount) => take_dao_cells_for_amount(deposit_cells, amount.into())?,
+            None => deposit_cells,
+        };
         if deposit_cells.is_empty() {
             return Err(CoreError::CannotFindDepositCell.into());
         }
@@ -250,11 +264,15 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         if payload.from.len() > MAX_ITEM_NUM {
             return Err(CoreError::ExceedMaxItemNum.into());
         }
+        if payload.amount.map(u64::from) == Some(0) {
+            return Err(CoreError::AmountMustPositive.into());
+        }
         dedup_json_items(&mut payload.from);
         self.build_transaction_with_adjusted_fee(
             Self::prebuild_dao_claim_transaction,
             payload.clone(),
             payload.fee_rate.map(Into::into),
+            payload.verify.unwrap_or(false),
         )
         .await
     }
@@ -307,20 +325,27 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         withdrawing_cells.retain(|i| set.insert(i.clone()));
 
         let tip_epoch_number = (**CURRENT_EPOCH_NUMBER.load()).clone();
-        let withdrawing_cells = withdrawing_cells
-            .into_iter()
-            .filter(|cell| {
-                cell.cell_data != Box::new([0u8; 8]).to_vec() && cell.cell_data.len() == 8
-            })
-            .filter(|cell| {
-                EpochNumberWithFraction::from_full_value(cell.epoch_number).to_rational()
-                    + U256::from(4u64)
-                    < tip_epoch_number
-            })
-            .collect::<Vec<_>>();
+        let is_selected = payload.out_points.is_some();
+        let mut withdrawing_cells = select_dao_cells(
+            withdrawing_cells,
+            payload.out_points,
+            |cell| {
+                cell.cell_data != Box::new([0u8; 8]).to_vec()
+                    && cell.cell_data.len() == 8
+                    && EpochNumberWithFraction::from_full_value(cell.epoch_number).to_rational()
+                        + U256::from(4u64)
+                        < tip_epoch_number
+            },
+            "is not a withdrawing cell which can be claimed",
+        )?;
         if withdrawing_cells.is_empty() {
             return Err(CoreError::CannotFindUnlockedWithdrawingCell.into());
         }
+        let amount: Option<u64> = payload.amount.map(Into::into);
+        if amount.is_some() {
+            withdrawing_cells.sort_by_key(|cell| (cell.block_number, cell.tx_index));
+        }
+        let mut claimed_capacity = 0u64;
 
         // init transfer components: build the outputs
         let mut transfer_components = TransferComponents::new();
@@ -335,20 +360,27 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             let withdrawing_tx_input_index: u32 = withdrawing_cell.out_point.index().unpack(); // input deposite cell has the same index
             let deposit_cell = &withdrawing_tx.input_cells[withdrawing_tx_input_index as usize];
 
+            // calculate input since
+            let unlock_epoch = calculate_unlock_epoch_number(
+                deposit_cell.epoch_number,
+                withdrawing_cell.epoch_number,
+            );
             if !is_dao_withdraw_unlock(
                 EpochNumberWithFraction::from_full_value(deposit_cell.epoch_number).to_rational(),
                 EpochNumberWithFraction::from_full_value(withdrawing_cell.epoch_number)
                     .to_rational(),
                 Some((**CURRENT_EPOCH_NUMBER.load()).clone()),
             ) {
+                if is_selected {
+                    return Err(CoreError::InvalidDaoCell(format!(
+                        "{} is locked until epoch {}",
+                        display_out_point(&withdrawing_cell.out_point),
+                        EpochNumberWithFraction::from_full_value(unlock_epoch)
+                    ))
+                    .into());
+                }
                 continue;
             }
-
-            // calculate input since
-            let unlock_epoch = calculate_unlock_epoch_number(
-                deposit_cell.epoch_number,
-                withdrawing_cell.epoch_number,
-            );
             let since = to_since(SinceConfig {
                 type_: SinceType::EpochNumber,
                 flag: SinceFlag::Absolute,
@@ -357,7 +389,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
 
             // build input
             transfer_components
-                .dao_since_map
+                .since_map
                 .insert(transfer_components.inputs.len(), since);
 
             // build header deps
@@ -410,12 +442,26 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                 )
                 .await?;
 
+            let capacity: u64 = withdrawing_cell.cell_output.capacity().unpack();
+            claimed_capacity += capacity;
             transfer_components.inputs.push(withdrawing_cell);
+            if amount.map_or(false, |amount| claimed_capacity >= amount) {
+                break;
+            }
         }
 
         if transfer_components.inputs.is_empty() {
             return Err(CoreError::CannotFindUnlockedWithdrawingCell.into());
         }
+        if let Some(amount) = amount {
+            if claimed_capacity < amount {
+                return Err(CoreError::CkbIsNotEnough(format!(
+                    "the unlocked withdrawing cells hold {}, less than the amount {}",
+                    claimed_capacity, amount
+                ))
+                .into());
+            }
+        }
 
         // build output cell
         let output_cell_capacity = maximum_withdraw_capacity - fixed_fee;
@@ -438,6 +484,318 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             .map(|(tx_view, script_groups)| (tx_view, script_groups, change_cell_index))
     }
 
+    pub(crate) async fn inner_build_cheque_claim_transaction(
+        &self,
+        mut payload: ChequeClaimPayload,
+    ) -> InnerResult<ChequeTransactionResponse> {
+        let item = Item::try_from(payload.from.clone())?;
+        let receiver_lock_hash = self.get_cheque_lock_hash_by_item(&item).await?;
+        let cheque_cells = self
+            .get_cheque_cells(&item, &payload.out_points, |cell| {
+                cell.cell_output.lock().args().raw_data()[0..20] == receiver_lock_hash.0
+            })
+            .await?;
+        payload.out_points = cheque_cells
+            .into_iter()
+            .map(|cell| cell.out_point.into())
+            .collect();
+
+        let response = self
+            .build_transaction_with_adjusted_fee(
+                Self::prebuild_cheque_claim_transaction,
+                payload.clone(),
+                payload.fee_rate.map(Into::into),
+                payload.verify.unwrap_or(false),
+            )
+            .await?;
+        Ok(ChequeTransactionResponse::new(response, payload.out_points))
+    }
+
+    async fn prebuild_cheque_claim_transaction(
+        &self,
+        payload: ChequeClaimPayload,
+        fixed_fee: u64,
+    ) -> InnerResult<(TransactionView, Vec<ScriptGroup>, usize)> {
+        let item = Item::try_from(payload.from)?;
+        let receiver_address = self.get_secp_address_by_item(&item).await?;
+        let receiver_lock: packed::Script = receiver_address.payload().into();
+
+        // init transfer components: return the capacity of cheque cells to the senders
+        let mut transfer_components = TransferComponents::new();
+        let mut claimed_udt_amounts: Vec<(packed::Script, u128)> = vec![];
+        for out_point in payload.out_points {
+            let cheque_cell = self.get_live_cheque_cell(out_point).await?;
+            let type_script = cheque_cell.cell_output.type_().to_opt().ok_or_else(|| {
+                CoreError::InvalidChequeCell("cheque cell without udt".to_string())
+            })?;
+            let sender_address = self.get_cheque_sender_address(&cheque_cell).await?;
+            build_cell_for_output(
+                cheque_cell.cell_output.capacity().unpack(),
+                sender_address.payload().into(),
+                None,
+                None,
+                &mut transfer_components.outputs,
+                &mut transfer_components.outputs_data,
+            )?;
+
+            let udt_amount = decode_udt_amount(&cheque_cell.cell_data).unwrap_or(0);
+            match claimed_udt_amounts
+                .iter_mut()
+                .find(|(script, _)| script == &type_script)
+            {
+                Some((_, amount)) => *amount += udt_amount,
+                None => claimed_udt_amounts.push((type_script, udt_amount)),
+            }
+
+            transfer_components.script_deps.insert(CHEQUE.to_string());
+            transfer_components
+                .script_deps
+                .insert(get_udt_script_name(&cheque_cell.cell_output).to_string());
+            transfer_components.inputs.push(cheque_cell);
+        }
+
+        // build the outputs of claimed udt
+        for (type_script, udt_amount) in claimed_udt_amounts {
+            match payload.claim_to.unwrap_or(ChequeClaimTarget::Secp) {
+                ChequeClaimTarget::Secp => {
+                    let capacity = calculate_cell_capacity(
+                        &receiver_lock,
+                        &Some(type_script.clone()).pack(),
+                        Capacity::bytes(Bytes::from(encode_udt_amount(udt_amount)).len())
+                            .expect("generate capacity"),
+                    );
+                    build_cell_for_output(
+                        capacity,
+                        receiver_lock.clone(),
+                        Some(type_script),
+                        Some(udt_amount),
+                        &mut transfer_components.outputs,
+                        &mut transfer_components.outputs_data,
+                    )?;
+                }
+                ChequeClaimTarget::Acp => {
+                    let asset_info = udt_asset_info(&type_script).ok_or_else(|| {
+                        CoreError::InvalidChequeCell("unsupported udt".to_string())
+                    })?;
+                    let mut asset_set = HashSet::new();
+                    asset_set.insert(asset_info);
+                    let live_acps = self
+                        .get_live_cells_by_item(
+                            item.clone(),
+                            asset_set,
+                            None,
+                            None,
+                            ACP_CODE_HASH.get(),
+                            None,
+                            &mut PaginationRequest::default().limit(Some(1)),
+                        )
+                        .await?;
+                    let live_acp = live_acps
+                        .get(0)
+                        .cloned()
+                        .ok_or(CoreError::CannotFindACPCell)?;
+                    let existing_udt_amount = decode_udt_amount(&live_acp.cell_data).unwrap_or(0);
+                    rebuild_udt_cell_for_output(
+                        &live_acp,
+                        live_acp.cell_output.capacity().unpack(),
+                        live_acp.cell_output.lock(),
+                        Some(existing_udt_amount + udt_amount),
+                        &mut transfer_components.outputs,
+                        &mut transfer_components.outputs_data,
+                    )?;
+                    transfer_components.script_deps.insert(ACP.to_string());
+                    transfer_components.inputs.push(live_acp);
+                    transfer_components
+                        .inputs_not_require_signature
+                        .insert(transfer_components.inputs.len() - 1);
+                }
+            }
+        }
+
+        // balance capacity, the receiver pays the capacity of new cells and the fee
+        self.balance_transfer_tx_capacity(vec![item], &mut transfer_components, Some(fixed_fee))
+            .await?;
+        let fee_change_cell_index = transfer_components
+            .fee_change_cell_index
+            .ok_or(CoreError::InvalidFeeChange)?;
+        self.complete_prebuild_transaction(transfer_components, None)
+            .map(|(tx_view, script_groups)| (tx_view, script_groups, fee_change_cell_index))
+    }
+
+    pub(crate) async fn inner_build_cheque_withdraw_transaction(
+        &self,
+        mut payload: ChequeWithdrawPayload,
+    ) -> InnerResult<ChequeTransactionResponse> {
+        let item = Item::try_from(payload.from.clone())?;
+        let sender_lock_hash = self.get_cheque_lock_hash_by_item(&item).await?;
+        let tip_epoch_number = (**CURRENT_EPOCH_NUMBER.load()).clone();
+        let cheque_cells = self
+            .get_cheque_cells(&item, &payload.out_points, |cell| {
+                cell.cell_output.lock().args().raw_data()[20..40] == sender_lock_hash.0
+                    && self.is_unlock(
+                        EpochNumberWithFraction::from_full_value(cell.epoch_number).to_rational(),
+                        Some(tip_epoch_number.clone()),
+                        self.cheque_timeout.clone(),
+                    )
+            })
+            .await?;
+        payload.out_points = cheque_cells
+            .into_iter()
+            .map(|cell| cell.out_point.into())
+            .collect();
+
+        let response = self
+            .build_transaction_with_adjusted_fee(
+                Self::prebuild_cheque_withdraw_transaction,
+                payload.clone(),
+                payload.fee_rate.map(Into::into),
+                payload.verify.unwrap_or(false),
+            )
+            .await?;
+        Ok(ChequeTransactionResponse::new(response, payload.out_points))
+    }
+
+    async fn prebuild_cheque_withdraw_transaction(
+        &self,
+        payload: ChequeWithdrawPayload,
+        fixed_fee: u64,
+    ) -> InnerResult<(TransactionView, Vec<ScriptGroup>, usize)> {
+        let item = Item::try_from(payload.from)?;
+        let sender_address = self.get_secp_address_by_item(&item).await?;
+        let sender_lock: packed::Script = sender_address.payload().into();
+
+        // the cheque lock only allows the sender to withdraw after the cheque since
+        let cheque_since = to_since(SinceConfig {
+            type_: SinceType::EpochNumber,
+            flag: SinceFlag::Relative,
+            value: EpochNumberWithFraction::new(
+                u256_low_u64(self.cheque_timeout.clone().into_u256()),
+                0,
+                1,
+            )
+            .full_value()
+            .into(),
+        })?;
+
+        // init transfer components: return the cheque cells to the sender
+        let mut transfer_components = TransferComponents::new();
+        for out_point in payload.out_points {
+            let cheque_cell = self.get_live_cheque_cell(out_point).await?;
+            let udt_amount = decode_udt_amount(&cheque_cell.cell_data);
+            rebuild_udt_cell_for_output(
+                &cheque_cell,
+                cheque_cell.cell_output.capacity().unpack(),
+                sender_lock.clone(),
+                udt_amount,
+                &mut transfer_components.outputs,
+                &mut transfer_components.outputs_data,
+            )?;
+
+            transfer_components.script_deps.insert(CHEQUE.to_string());
+            transfer_components
+                .script_deps
+                .insert(get_udt_script_name(&cheque_cell.cell_output).to_string());
+            transfer_components
+                .since_map
+                .insert(transfer_components.inputs.len(), cheque_since);
+            transfer_components.inputs.push(cheque_cell);
+        }
+
+        // balance capacity, the sender pays the fee
+        self.balance_transfer_tx_capacity(vec![item], &mut transfer_components, Some(fixed_fee))
+            .await?;
+        let fee_change_cell_index = transfer_components
+            .fee_change_cell_index
+            .ok_or(CoreError::InvalidFeeChange)?;
+        self.complete_prebuild_transaction(transfer_components, None)
+            .map(|(tx_view, script_groups)| (tx_view, script_groups, fee_change_cell_index))
+    }
+
+    async fn get_cheque_lock_hash_by_item(&self, item: &Item) -> InnerResult<H160> {
+        let address = self.get_secp_address_by_item(item).await?;
+        let lock_hash: H256 = address_to_script(address.payload())
+            .calc_script_hash()
+            .unpack();
+        Ok(blake2b_256_to_160(&lock_hash))
+    }
+
+    async fn get_cheque_cells<F>(
+        &self,
+        item: &Item,
+        out_points: &[OutPoint],
+        is_available: F,
+    ) -> InnerResult<Vec<DetailedCell>>
+    where
+        F: Fn(&DetailedCell) -> bool,
+    {
+        if out_points.is_empty() {
+            let cheque_cells = self
+                .get_live_cells_by_item(
+                    item.clone(),
+                    HashSet::new(),
+                    None,
+                    None,
+                    CHEQUE_CODE_HASH.get(),
+                    None,
+                    &mut PaginationRequest::default(),
+                )
+                .await?
+                .into_iter()
+                .filter(|cell| has_cheque_lock_args(cell) && is_available(cell))
+                .collect::<Vec<_>>();
+            if cheque_cells.is_empty() {
+                return Err(CoreError::CannotFindChequeCell.into());
+            }
+            return Ok(cheque_cells);
+        }
+
+        let mut set = HashSet::new();
+        let mut cheque_cells = vec![];
+        for out_point in out_points.iter().filter(|out_point| set.insert(*out_point)) {
+            let cheque_cell = self.get_live_cheque_cell(out_point.clone()).await?;
+            if !is_available(&cheque_cell) {
+                return Err(CoreError::InvalidChequeCell(format!(
+                    "{:#x}-{}",
+                    out_point.tx_hash,
+                    out_point.index.value()
+                ))
+                .into());
+            }
+            cheque_cells.push(cheque_cell);
+        }
+        Ok(cheque_cells)
+    }
+
+    async fn get_live_cheque_cell(&self, out_point: OutPoint) -> InnerResult<DetailedCell> {
+        let cell = self
+            .storage
+            .get_live_cells(
+                Some(out_point.into()),
+                vec![],
+                vec![],
+                None,
+                None,
+                None,
+                PaginationRequest::default(),
+            )
+            .await
+            .map_err(|e| CoreError::DBError(e.to_string()))?
+            .response
+            .pop()
+            .ok_or(CoreError::CannotFindChequeCell)?;
+        let code_hash: H256 = cell.cell_output.lock().code_hash().unpack();
+        if Some(&code_hash) != CHEQUE_CODE_HASH.get() {
+            return Err(CoreError::InvalidChequeCell("not a cheque cell".to_string()).into());
+        }
+        if !has_cheque_lock_args(&cell) {
+            return Err(CoreError::InvalidChequeCell(
+                "the cheque lock args are shorter than 40 bytes".to_string(),
+            )
+            .into());
+        }
+        Ok(cell)
+    }
+
     pub(crate) async fn inner_build_transfer_transaction(
         &self,
         mut payload: TransferPayload,
@@ -469,6 +827,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             Self::prebuild_transfer_transaction,
             payload.clone(),
             payload.fee_rate.map(Into::into),
+            payload.verify.unwrap_or(false),
         )
         .await
     }
@@ -490,11 +849,11 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                 self.prebuild_ckb_transfer_transaction_to_provide_capacity(payload, fixed_fee)
                     .await
             }
-            (AssetType::UDT, Some(OutputCapacityProvider::From)) => {
+            (AssetType::UDT | AssetType::XUDT, Some(OutputCapacityProvider::From)) => {
                 self.prebuild_udt_transfer_transaction_from_provide_capacity(payload, fixed_fee)
                     .await
             }
-            (AssetType::UDT, None | Some(OutputCapacityProvider::To)) => {
+            (AssetType::UDT | AssetType::XUDT, None | Some(OutputCapacityProvider::To)) => {
                 self.prebuild_udt_transfer_transaction_to_provide_capacity(payload, fixed_fee)
                     .await
             }
@@ -508,6 +867,10 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
     ) -> InnerResult<(TransactionView, Vec<ScriptGroup>, usize)> {
         // init transfer components: build the outputs
         let mut transfer_components = utils_types::TransferComponents::new();
+        transfer_components.multisig_scripts = self
+            .get_multisig_scripts(&payload.from, &payload.multisig_configs)
+            .await?;
+        transfer_components.coin_selection = payload.coin_selection.unwrap_or_default();
 
         for to in &payload.to {
             let to_capacity: u128 = to.amount.into();
@@ -551,6 +914,10 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
     ) -> InnerResult<(TransactionView, Vec<ScriptGroup>, usize)> {
         // init transfer components: build acp inputs and outputs
         let mut transfer_components = TransferComponents::new();
+        transfer_components.multisig_scripts = self
+            .get_multisig_scripts(&payload.from, &payload.multisig_configs)
+            .await?;
+        transfer_components.coin_selection = payload.coin_selection.unwrap_or_default();
 
         for to in &payload.to {
             let to_address = Address::from_str(&to.address).map_err(CoreError::InvalidRpcParams)?;
@@ -598,17 +965,19 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             }
             if let Some(type_script) = live_acp.cell_output.type_().to_opt() {
                 let type_code_hash: H256 = type_script.code_hash().unpack();
-                if type_code_hash == *SUDT_CODE_HASH.get().expect("get sudt code hash") {
-                    transfer_components.script_deps.insert(SUDT.to_string());
+                if is_udt_code_hash(&type_code_hash) {
+                    transfer_components
+                        .script_deps
+                        .insert(get_udt_script_name(&live_acp.cell_output).to_string());
                 }
             }
 
             // build acp output
             let required_capacity: u128 = to.amount.into();
-            build_cell_for_output(
+            rebuild_udt_cell_for_output(
+                &live_acp,
                 current_capacity + required_capacity as u64,
                 live_acp.cell_output.lock(),
-                live_acp.cell_output.type_().to_opt(),
                 current_udt_amount,
                 &mut transfer_components.outputs,
                 &mut transfer_components.outputs_data,
@@ -634,6 +1003,10 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
     ) -> InnerResult<(TransactionView, Vec<ScriptGroup>, usize)> {
         // init transfer components: build acp inputs and outputs
         let mut transfer_components = utils_types::TransferComponents::new();
+        transfer_components.multisig_scripts = self
+            .get_multisig_scripts(&payload.from, &payload.multisig_configs)
+            .await?;
+        transfer_components.coin_selection = payload.coin_selection.unwrap_or_default();
         for to in &payload.to {
             let to_address = Address::from_str(&to.address).map_err(CoreError::InvalidRpcParams)?;
             let to_lock = address_to_script(to_address.payload());
@@ -649,7 +1022,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                     .expect("generate capacity"),
             );
 
-            build_cell_for_output(
+            let output_index = build_cell_for_output(
                 capacity,
                 to_lock,
                 sudt_type_script.to_opt(),
@@ -657,7 +1030,9 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                 &mut transfer_components.outputs,
                 &mut transfer_components.outputs_data,
             )?;
-            transfer_components.script_deps.insert(SUDT.to_string());
+            transfer_components.script_deps.insert(
+                get_udt_script_name(&transfer_components.outputs[output_index]).to_string(),
+            );
         }
 
         // balance udt
@@ -692,6 +1067,10 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
     ) -> InnerResult<(TransactionView, Vec<ScriptGroup>, usize)> {
         // init transfer components: build acp inputs and outputs
         let mut transfer_components = utils_types::TransferComponents::new();
+        transfer_components.multisig_scripts = self
+            .get_multisig_scripts(&payload.from, &payload.multisig_configs)
+            .await?;
+        transfer_components.coin_selection = payload.coin_selection.unwrap_or_default();
         let mut asset_set = HashSet::new();
         asset_set.insert(payload.asset_info.clone());
 
@@ -729,14 +1108,16 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                     .insert(SECP256K1.to_string());
                 transfer_components.script_deps.insert(PW_LOCK.to_string());
             }
-            transfer_components.script_deps.insert(SUDT.to_string());
+            transfer_components
+                .script_deps
+                .insert(get_udt_script_name(&live_acp.cell_output).to_string());
 
             // build acp output
             let to_udt_amount: u128 = to.amount.into();
-            build_cell_for_output(
+            rebuild_udt_cell_for_output(
+                &live_acp,
                 live_acp.cell_output.capacity().unpack(),
                 live_acp.cell_output.lock(),
-                live_acp.cell_output.type_().to_opt(),
                 Some(existing_udt_amount + to_udt_amount),
                 &mut transfer_components.outputs,
                 &mut transfer_components.outputs_data,
@@ -776,6 +1157,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             Self::prebuild_simple_transfer_transaction,
             payload.clone(),
             payload.fee_rate.map(Into::into),
+            payload.verify.unwrap_or(false),
         )
         .await
     }
@@ -795,10 +1177,15 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             .from
             .iter()
             .map(|address| {
+                // multisig cells are not owned by an identity, keep the address as is
+                let parsed = Address::from_str(address).map_err(CoreError::ParseAddressError)?;
+                if is_multisig(&parsed) {
+                    return Ok(JsonItem::Address(address.to_owned()));
+                }
                 self.address_to_identity(address)
                     .map(|identity| JsonItem::Identity(identity.encode()))
             })
-            .collect::<Result<Vec<JsonItem>, _>>()?;
+            .collect::<InnerResult<Vec<JsonItem>>>()?;
         dedup_json_items(&mut from_items);
         let addresses: Vec<String> = payload
             .to
@@ -832,6 +1219,9 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                     pay_fee: None,
                     fee_rate: payload.fee_rate,
                     since: payload.since,
+                    multisig_configs: payload.multisig_configs,
+                    coin_selection: payload.coin_selection,
+                    verify: payload.verify,
                 };
                 self.prebuild_ckb_transfer_transaction_from_provide_capacity(
                     transfer_payload,
@@ -840,7 +1230,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                 .await
             }
 
-            AssetType::UDT => {
+            AssetType::UDT | AssetType::XUDT => {
                 let mut asset_infos = HashSet::new();
                 asset_infos.insert(payload.asset_info.clone());
                 let output_capacity_provider = self
@@ -854,6 +1244,9 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                     pay_fee: None,
                     fee_rate: payload.fee_rate,
                     since: payload.since,
+                    multisig_configs: payload.multisig_configs,
+                    coin_selection: payload.coin_selection,
+                    verify: payload.verify,
                 };
                 match output_capacity_provider {
                     OutputCapacityProvider::From => {
@@ -918,6 +1311,32 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         prebuild: F,
         payload: T,
         fee_rate: Option<u64>,
+        verify: bool,
+    ) -> InnerResult<TransactionCompletionResponse>
+    where
+        F: Fn(&'a MercuryRpcImpl<C>, T, u64) -> Fut + Copy,
+        Fut: std::future::Future<Output = InnerResult<(TransactionView, Vec<ScriptGroup>, usize)>>,
+        T: Clone,
+    {
+        // the cells taken by the build are released unless they are the inputs of the
+        // built transaction, which are reserved by the transaction hash instead
+        let build_id = new_build_id();
+        let res = BUILD_ID
+            .scope(
+                build_id.clone(),
+                self.build_transaction_with_estimated_fee(prebuild, payload, fee_rate, verify),
+            )
+            .await;
+        INPUT_RESERVATIONS.write().release_transaction(&build_id);
+        res
+    }
+
+    async fn build_transaction_with_estimated_fee<'a, F, Fut, T>(
+        &'a self,
+        prebuild: F,
+        payload: T,
+        fee_rate: Option<u64>,
+        verify: bool,
     ) -> InnerResult<TransactionCompletionResponse>
     where
         F: Fn(&'a MercuryRpcImpl<C>, T, u64) -> Fut + Copy,
@@ -947,8 +1366,9 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                     estimate_fee,
                     actual_fee,
                 )?;
-                let adjust_response = TransactionCompletionResponse::new(tx_view, script_groups);
-                return Ok(adjust_response);
+                return self
+                    .build_transaction_completion_response(tx_view, script_groups, verify)
+                    .await;
             }
         }
     }
@@ -1052,7 +1472,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         let inputs = self.build_transfer_tx_cell_inputs(
             &components.inputs,
             payload_since,
-            components.dao_since_map,
+            components.since_map,
         )?;
         let script_groups =
             build_script_groups(components.inputs.iter(), components.outputs.iter());
@@ -1061,6 +1481,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             &script_groups,
             &components.inputs_not_require_signature,
             &components.type_witness_args,
+            &components.multisig_scripts,
         );
         let tx_view = TransactionBuilder::default()
             .version(TX_VERSION.pack())
@@ -1124,7 +1545,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         &self,
         inputs: &[DetailedCell],
         payload_since: Option<SinceConfig>,
-        dao_since_map: HashMap<usize, u64>,
+        since_map: HashMap<usize, u64>,
     ) -> InnerResult<Vec<packed::CellInput>> {
         let payload_since = if let Some(config) = payload_since {
             to_since(config)?
@@ -1135,7 +1556,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             .iter()
             .enumerate()
             .map(|(index, cell)| {
-                let since = if let Some(since) = dao_since_map.get(&index) {
+                let since = if let Some(since) = since_map.get(&index) {
                     *since
                 } else {
                     payload_since
@@ -1181,6 +1602,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             Self::prebuild_sudt_issue_transaction,
             payload.clone(),
             payload.fee_rate.map(Into::into),
+            payload.verify.unwrap_or(false),
         )
         .await
     }
@@ -1333,6 +1755,81 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
     }
 }
 
+/// Keep the DAO cells of the out points if they are specified, and fail if any of them is
+/// not a cell of the items or is not eligible. Otherwise keep all the eligible cells.
+pub(crate) fn select_dao_cells(
+    cells: Vec<DetailedCell>,
+    out_points: Option<Vec<OutPoint>>,
+    is_eligible: impl Fn(&DetailedCell) -> bool,
+    ineligible_reason: &str,
+) -> InnerResult<Vec<DetailedCell>> {
+    let out_points = match out_points {
+        Some(out_points) => out_points,
+        None => return Ok(cells.into_iter().filter(|cell| is_eligible(cell)).collect()),
+    };
+
+    let mut selected: Vec<DetailedCell> = vec![];
+    for out_point in out_points {
+        let out_point: packed::OutPoint = out_point.into();
+        let cell = cells
+            .iter()
+            .find(|cell| cell.out_point == out_point)
+            .ok_or_else(|| {
+                CoreError::InvalidDaoCell(format!(
+                    "{} is not a live dao cell of the items",
+                    display_out_point(&out_point)
+                ))
+            })?;
+        if !is_eligible(cell) {
+            return Err(CoreError::InvalidDaoCell(format!(
+                "{} {}",
+                display_out_point(&out_point),
+                ineligible_reason
+            ))
+            .into());
+        }
+        if !selected
+            .iter()
+            .any(|selected| selected.out_point == out_point)
+        {
+            selected.push(cell.clone());
+        }
+    }
+    Ok(selected)
+}
+
+/// Take the earliest DAO cells until their capacity reaches the amount.
+pub(crate) fn take_dao_cells_for_amount(
+    mut cells: Vec<DetailedCell>,
+    amount: u64,
+) -> InnerResult<Vec<DetailedCell>> {
+    cells.sort_by_key(|cell| (cell.block_number, cell.tx_index));
+    let mut total_capacity = 0u64;
+    let mut taken = vec![];
+    for cell in cells {
+        if total_capacity >= amount {
+            break;
+        }
+        let capacity: u64 = cell.cell_output.capacity().unpack();
+        total_capacity += capacity;
+        taken.push(cell);
+    }
+    if total_capacity < amount {
+        return Err(CoreError::CkbIsNotEnough(format!(
+            "the dao cells hold {}, less than the amount {}",
+            total_capacity, amount
+        ))
+        .into());
+    }
+    Ok(taken)
+}
+
+fn display_out_point(out_point: &packed::OutPoint) -> String {
+    let tx_hash: H256 = out_point.tx_hash().unpack();
+    let index: u32 = out_point.index().unpack();
+    format!("{:#x}#{}", tx_hash, index)
+}
+
 pub(crate) fn calculate_tx_size(tx_view: &TransactionView) -> usize {
     let tx_size = tx_view.data().total_size();
     // tx offset bytesize
@@ -1389,6 +1886,7 @@ fn build_witnesses(
     script_groups: &[ScriptGroup],
     inputs_not_require_signature: &HashSet<usize>,
     type_witness_args: &HashMap<usize, (packed::BytesOpt, packed::BytesOpt)>,
+    multisig_scripts: &HashMap<H160, Bytes>,
 ) -> Vec<packed::Bytes> {
     let mut witnesses = vec![packed::Bytes::default(); inputs_len];
     for script_group in script_groups {
@@ -1402,10 +1900,9 @@ fn build_witnesses(
         {
             continue;
         }
-        // Currently, the length of the placeholder is hard-coded to 65, which is just enough to support lock scripts such as secp, anyone can pay, cheque, and pw lock.
-        // In the future, a more general approach will be supported, and placeholder len will be set according to what is in the script group.
+        let lock_script: packed::Script = script_group.script.clone().into();
         let mut placeholder = packed::WitnessArgs::new_builder()
-            .lock(Some(Bytes::from(vec![0u8; 65])).pack())
+            .lock(Some(build_lock_placeholder(&lock_script, multisig_scripts)).pack())
             .build();
         if let Some((input_type, output_type)) = type_witness_args.get(&(input_index as usize)) {
             placeholder = placeholder
@@ -1429,3 +1926,52 @@ fn build_witnesses(
     }
     witnesses
 }
+
+// The placeholder of secp, anyone can pay, cheque and pw lock is a 65 bytes signature.
+// Omni lock expects an `OmniLockWitnessLock` table instead, whose signature field is 65 bytes
+// and whose `omni_identity` and `preimage` fields are absent.
+// Multisig expects the multisig script followed by `threshold` 65 bytes signatures.
+pub(crate) fn build_lock_placeholder(
+    lock_script: &packed::Script,
+    multisig_scripts: &HashMap<H160, Bytes>,
+) -> Bytes {
+    if let Some(multisig_script) = get_multisig_script_of_lock(lock_script, multisig_scripts) {
+        let threshold = multisig_script[2] as usize;
+        let mut placeholder = multisig_script.to_vec();
+        placeholder.extend_from_slice(&vec![0u8; 65 * threshold]);
+        return Bytes::from(placeholder);
+    }
+    if !is_omni_lock_script(lock_script) {
+        return Bytes::from(vec![0u8; 65]);
+    }
+
+    let signature_len = 65u32;
+    let header_len = 4 * 4u32;
+    let total_len = header_len + 4 + signature_len;
+    let mut placeholder = Vec::with_capacity(total_len as usize);
+    placeholder.extend_from_slice(&total_len.to_le_bytes());
+    // offsets of signature, omni_identity and preimage
+    placeholder.extend_from_slice(&header_len.to_le_bytes());
+    placeholder.extend_from_slice(&total_len.to_le_bytes());
+    placeholder.extend_from_slice(&total_len.to_le_bytes());
+    placeholder.extend_from_slice(&signature_len.to_le_bytes());
+    placeholder.extend_from_slice(&[0u8; 65]);
+    Bytes::from(placeholder)
+}
+
+fn get_multisig_script_of_lock<'a>(
+    lock_script: &packed::Script,
+    multisig_scripts: &'a HashMap<H160, Bytes>,
+) -> Option<&'a Bytes> {
+    let args = lock_script.args().raw_data();
+    if args.len() < 20 {
+        return None;
+    }
+    let code_hash: H256 = lock_script.code_hash().unpack();
+    if code_hash != MULTISIG_TYPE_HASH {
+        return None;
+    }
+    H160::from_slice(&args[0..20])
+        .ok()
+        .and_then(|hash| multisig_scripts.get(&hash))
+}
@@ -0,0 +1,330 @@
+use crate::r#impl::sweep::{add_swept_input, pooled_cell_or_none};
+use crate::r#impl::utils::{build_cell_for_output, calculate_cell_capacity, get_udt_script_name};
+use crate::r#impl::{address_to_script, utils_types::*};
+use crate::{error::CoreError, InnerResult, MercuryRpcImpl};
+
+use ckb_types::core::{Capacity, TransactionView};
+use ckb_types::{packed, prelude::*};
+use common::hash::blake2b_256_to_160;
+use common::utils::decode_udt_amount;
+use common::DetailedCell;
+use core_ckb_client::CkbRpc;
+use core_rpc_types::consts::{DEFAULT_CONSOLIDATE_INPUT_NUM, MAX_SWEEP_INPUT_NUM};
+use core_rpc_types::{
+    AssetType, ConsolidatePayload, Item, PayFee, ScriptGroup, TransactionCompletionResponse,
+};
+use num_bigint::BigInt;
+
+use std::convert::TryFrom;
+
+impl<C: CkbRpc> MercuryRpcImpl<C> {
+    pub(crate) async fn inner_build_consolidate_transaction(
+        &self,
+        payload: ConsolidatePayload,
+    ) -> InnerResult<TransactionCompletionResponse> {
+        let max_inputs = payload
+            .max_inputs
+            .map(Into::into)
+            .unwrap_or(DEFAULT_CONSOLIDATE_INPUT_NUM) as usize;
+        if max_inputs < 2 {
+            return Err(
+                CoreError::InvalidRpcParams("max_inputs should be at least 2".to_string()).into(),
+            );
+        }
+        if max_inputs > MAX_SWEEP_INPUT_NUM {
+            return Err(CoreError::InvalidRpcParams(format!(
+                "max_inputs should not exceed {}",
+                MAX_SWEEP_INPUT_NUM
+            ))
+            .into());
+        }
+        Item::try_from(payload.item.clone())?;
+
+        self.build_transaction_with_adjusted_fee(
+            Self::prebuild_consolidate_transaction,
+            payload.clone(),
+            payload.fee_rate.map(Into::into),
+            payload.verify.unwrap_or(false),
+        )
+        .await
+    }
+
+    async fn prebuild_consolidate_transaction(
+        &self,
+        payload: ConsolidatePayload,
+        fixed_fee: u64,
+    ) -> InnerResult<(TransactionView, Vec<ScriptGroup>, usize)> {
+        match payload.asset_info.asset_type {
+            AssetType::CKB => {
+                self.prebuild_ckb_consolidate_transaction(payload, fixed_fee)
+                    .await
+            }
+            AssetType::UDT | AssetType::XUDT => {
+                self.prebuild_udt_consolidate_transaction(payload, fixed_fee)
+                    .await
+            }
+        }
+    }
+
+    /// Merge the plain CKB cells of the item whose capacity is below the threshold into
+    /// one cell of the default owner lock. The cells holding a UDT or any other type,
+    /// including the DAO cells, are left untouched.
+    async fn prebuild_ckb_consolidate_transaction(
+        &self,
+        payload: ConsolidatePayload,
+        fixed_fee: u64,
+    ) -> InnerResult<(TransactionView, Vec<ScriptGroup>, usize)> {
+        let max_inputs = payload
+            .max_inputs
+            .map(Into::into)
+            .unwrap_or(DEFAULT_CONSOLIDATE_INPUT_NUM) as usize;
+        let threshold: Option<u128> = payload.threshold.map(Into::into);
+        let item = Item::try_from(payload.item.clone())?;
+
+        let mut transfer_components = TransferComponents::new();
+        transfer_components.multisig_scripts = self
+            .get_multisig_scripts(&[payload.item], &payload.multisig_configs)
+            .await?;
+
+        let mut ckb_cells_cache = CkbCellsCache::new(vec![item.clone()]);
+        ckb_cells_cache.item_category_array.retain(|(_, category)| {
+            !matches!(
+                category,
+                PoolCkbCategory::DaoClaim | PoolCkbCategory::CkbSecpUdt
+            )
+        });
+        ckb_cells_cache
+            .pagination
+            .set_limit(Some(self.pool_cache_size));
+        let mut merged_capacity = 0u64;
+
+        while transfer_components.inputs.len() < max_inputs {
+            let next = self
+                .pool_next_live_cell_for_capacity(
+                    &mut ckb_cells_cache,
+                    i128::MAX,
+                    &transfer_components.inputs,
+                )
+                .await;
+            let (cell, asset_script_type) =
+                match pooled_cell_or_none(next, ckb_cells_cache.is_pooled_all())? {
+                    Some(next) => next,
+                    None => break,
+                };
+
+            let capacity: u64 = cell.cell_output.capacity().unpack();
+            if cell.cell_output.type_().is_some()
+                || !cell.cell_data.is_empty()
+                || !self.is_consolidatable(&cell, capacity as u128, threshold)
+            {
+                continue;
+            }
+            merged_capacity = merged_capacity
+                .checked_add(add_swept_input(
+                    cell,
+                    &asset_script_type,
+                    &mut transfer_components,
+                ))
+                .ok_or(CoreError::Overflow)?;
+        }
+        check_consolidated_inputs(&transfer_components, &payload.asset_info.to_string())?;
+
+        let to_address = self.get_default_owner_address_by_item(&item).await?;
+        let to_lock = address_to_script(to_address.payload());
+        let min_capacity = calculate_cell_capacity(
+            &to_lock,
+            &packed::ScriptOpt::default(),
+            Capacity::bytes(0).expect("generate capacity"),
+        );
+        if merged_capacity < min_capacity + fixed_fee {
+            return Err(CoreError::CkbIsNotEnough(format!(
+                "shortage: {}, item: {:?}",
+                min_capacity + fixed_fee - merged_capacity,
+                item
+            ))
+            .into());
+        }
+
+        let output_index = build_cell_for_output(
+            merged_capacity - fixed_fee,
+            to_lock,
+            None,
+            None,
+            &mut transfer_components.outputs,
+            &mut transfer_components.outputs_data,
+        )?;
+        self.complete_prebuild_transaction(transfer_components, None)
+            .map(|(tx_view, script_groups)| (tx_view, script_groups, output_index))
+    }
+
+    /// Merge the cells of the UDT owned by the item whose amount is below the threshold
+    /// into one UDT cell of the default owner lock. The capacity of the merged cells
+    /// goes back to the item as the change, which also pays the fee. The ACP cells of the
+    /// same lock as the first one are merged into one ACP cell of that lock, which keeps
+    /// the account with their capacity. The pw lock cells are not merged.
+    async fn prebuild_udt_consolidate_transaction(
+        &self,
+        payload: ConsolidatePayload,
+        fixed_fee: u64,
+    ) -> InnerResult<(TransactionView, Vec<ScriptGroup>, usize)> {
+        let max_inputs = payload
+            .max_inputs
+            .map(Into::into)
+            .unwrap_or(DEFAULT_CONSOLIDATE_INPUT_NUM) as usize;
+        let threshold: Option<u128> = payload.threshold.map(Into::into);
+        let item = Item::try_from(payload.item.clone())?;
+
+        let mut transfer_components = TransferComponents::new();
+        transfer_components.multisig_scripts = self
+            .get_multisig_scripts(&[payload.item], &payload.multisig_configs)
+            .await?;
+
+        let mut udt_cells_cache =
+            UdtCellsCache::new(vec![item.clone()], payload.asset_info.clone());
+        udt_cells_cache.item_category_array.retain(|(_, category)| {
+            !matches!(
+                category,
+                PoolUdtCategory::CkbCheque | PoolUdtCategory::PwLockEthereum
+            )
+        });
+        udt_cells_cache
+            .pagination
+            .set_limit(Some(self.pool_cache_size));
+        let mut owner_cells = vec![];
+        let mut acp_cells = vec![];
+        let mut pooled_cells = vec![];
+
+        while owner_cells.len() + acp_cells.len() < max_inputs {
+            let next = self
+                .pool_next_live_cell_for_udt(
+                    &mut udt_cells_cache,
+                    BigInt::from(u128::MAX),
+                    &pooled_cells,
+                )
+                .await;
+            let (cell, asset_script_type) =
+                match pooled_cell_or_none(next, udt_cells_cache.is_pooled_all())? {
+                    Some(next) => next,
+                    None => break,
+                };
+            pooled_cells.push(cell.clone());
+
+            let amount = decode_udt_amount(&cell.cell_data).unwrap_or(0);
+            if !self.is_consolidatable(&cell, amount, threshold) {
+                continue;
+            }
+            match asset_script_type {
+                AssetScriptType::ACP => {
+                    let first_lock = acp_cells
+                        .first()
+                        .map(|(cell, _, _): &(DetailedCell, _, _)| cell.cell_output.lock());
+                    if first_lock.map_or(true, |lock| lock == cell.cell_output.lock()) {
+                        acp_cells.push((cell, asset_script_type, amount));
+                    }
+                }
+                _ => owner_cells.push((cell, asset_script_type, amount)),
+            }
+        }
+
+        // a single cell of its kind has nothing to be merged with
+        let mut merged_amount = 0u128;
+        let merge_owner_cells = owner_cells.len() > 1;
+        if merge_owner_cells {
+            for (cell, asset_script_type, amount) in owner_cells.into_iter() {
+                add_swept_input(cell, &asset_script_type, &mut transfer_components);
+                merged_amount = merged_amount
+                    .checked_add(amount)
+                    .ok_or(CoreError::Overflow)?;
+            }
+        }
+        let acp_lock = match acp_cells.first() {
+            Some((cell, _, _)) if acp_cells.len() > 1 => Some(cell.cell_output.lock()),
+            _ => None,
+        };
+        let (mut acp_capacity, mut acp_amount) = (0u64, 0u128);
+        if acp_lock.is_some() {
+            for (cell, asset_script_type, amount) in acp_cells.into_iter() {
+                acp_capacity = acp_capacity
+                    .checked_add(add_swept_input(
+                        cell,
+                        &asset_script_type,
+                        &mut transfer_components,
+                    ))
+                    .ok_or(CoreError::Overflow)?;
+                acp_amount = acp_amount.checked_add(amount).ok_or(CoreError::Overflow)?;
+            }
+        }
+        check_consolidated_inputs(&transfer_components, &payload.asset_info.to_string())?;
+
+        // build the outputs holding the merged amounts
+        let to_address = self.get_default_owner_address_by_item(&item).await?;
+        let to_lock = address_to_script(to_address.payload());
+        let udt_type_script = self
+            .build_sudt_type_script(blake2b_256_to_160(&payload.asset_info.udt_hash))
+            .await?;
+        let udt_type_script = Some(udt_type_script).pack();
+        if merge_owner_cells {
+            let capacity = calculate_cell_capacity(
+                &to_lock,
+                &udt_type_script,
+                Capacity::bytes(16).expect("generate capacity"),
+            );
+            build_cell_for_output(
+                capacity,
+                to_lock,
+                udt_type_script.to_opt(),
+                Some(merged_amount),
+                &mut transfer_components.outputs,
+                &mut transfer_components.outputs_data,
+            )?;
+        }
+        if let Some(acp_lock) = acp_lock {
+            build_cell_for_output(
+                acp_capacity,
+                acp_lock,
+                udt_type_script.to_opt(),
+                Some(acp_amount),
+                &mut transfer_components.outputs,
+                &mut transfer_components.outputs_data,
+            )?;
+        }
+        transfer_components
+            .script_deps
+            .insert(get_udt_script_name(&transfer_components.outputs[0]).to_string());
+
+        // balance capacity
+        self.prebuild_capacity_balance_tx(
+            vec![item],
+            vec![to_address.to_string()],
+            None,
+            Some(PayFee::From),
+            fixed_fee,
+            transfer_components,
+        )
+        .await
+    }
+
+    /// An immature cellbase cannot be spent, and a cell at or above the threshold is
+    /// not small enough to be merged.
+    fn is_consolidatable(&self, cell: &DetailedCell, value: u128, threshold: Option<u128>) -> bool {
+        if cell.tx_index == 0 && !self.is_cellbase_mature(cell) {
+            return false;
+        }
+        threshold.map_or(true, |threshold| value < threshold)
+    }
+}
+
+fn check_consolidated_inputs(
+    transfer_components: &TransferComponents,
+    asset: &str,
+) -> InnerResult<()> {
+    if transfer_components.inputs.len() < 2 {
+        return Err(CoreError::NotEnoughCellsToConsolidate(format!(
+            "found {} cell of {} to merge",
+            transfer_components.inputs.len(),
+            asset
+        ))
+        .into());
+    }
+    Ok(())
+}
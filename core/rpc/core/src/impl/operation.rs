@@ -1,9 +1,20 @@
 use crate::{error::CoreError, InnerResult, MercuryRpcImpl};
 
+use common::utils::unix_timestamp_millis;
+use core_ckb_client::error::{node_error_code, POOL_REJECTED_DUPLICATED_TRANSACTION};
 use core_ckb_client::CkbRpc;
+use core_rpc_types::consts::MAX_ITEM_NUM;
+use core_rpc_types::lazy::INPUT_RESERVATIONS;
+use core_rpc_types::{
+    AddressLabel, JsonItem, ReleaseInputReservationPayload, SendTransactionPayload,
+    SetAddressLabelPayload, TransactionStatus,
+};
 use core_storage::Storage;
 
-use ckb_types::H160;
+use ckb_jsonrpc_types::OutPoint;
+use ckb_types::{packed, prelude::*, H160, H256};
+
+use std::collections::HashSet;
 
 impl<C: CkbRpc> MercuryRpcImpl<C> {
     pub(crate) async fn inner_register_addresses(
@@ -15,4 +26,109 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             .await
             .map_err(|error| CoreError::DBError(error.to_string()).into())
     }
+
+    /// Set or remove the labels, and return the items in the form the labels are stored by.
+    pub(crate) async fn inner_set_address_labels(
+        &self,
+        payload: Vec<SetAddressLabelPayload>,
+    ) -> InnerResult<Vec<JsonItem>> {
+        if payload.len() > MAX_ITEM_NUM {
+            return Err(CoreError::ExceedMaxItemNum.into());
+        }
+
+        let updated_at = unix_timestamp_millis();
+        let (mut items, mut seen) = (vec![], HashSet::new());
+        let (mut labels, mut removed) = (vec![], vec![]);
+        for label_payload in payload.into_iter() {
+            let item = self.to_label_item(label_payload.item)?;
+            if !seen.insert(item.clone()) {
+                return Err(
+                    CoreError::InvalidRpcParams(format!("duplicate item {:?}", item)).into(),
+                );
+            }
+            items.push(item.clone());
+
+            match label_payload.label {
+                Some(label) if label.is_empty() => {
+                    return Err(
+                        CoreError::InvalidRpcParams("the label is empty".to_string()).into(),
+                    )
+                }
+                Some(label) => labels.push(AddressLabel {
+                    item,
+                    label,
+                    metadata: label_payload.metadata,
+                    updated_at: updated_at.into(),
+                }),
+                None if label_payload.metadata.is_some() => {
+                    return Err(CoreError::InvalidRpcParams(
+                        "the metadata needs a label".to_string(),
+                    )
+                    .into())
+                }
+                None => removed.push(item),
+            }
+        }
+
+        self.storage
+            .set_address_labels(labels, removed)
+            .await
+            .map_err(|error| CoreError::DBError(error.to_string()))?;
+        Ok(items)
+    }
+
+    /// Relay the transaction to the CKB node and track it. A transaction refused by the
+    /// node is tracked as rejected with the reason. If the node is unreachable or the
+    /// transaction is already in the tx pool, it is tracked as pending, and the tx pool
+    /// poller corrects the status later.
+    pub(crate) async fn inner_send_transaction(
+        &self,
+        payload: SendTransactionPayload,
+    ) -> InnerResult<H256> {
+        let tx_hash: H256 = packed::Transaction::from(payload.transaction.clone())
+            .calc_tx_hash()
+            .unpack();
+        let submitted_at = unix_timestamp_millis();
+
+        let (status, reason, result) = match self
+            .ckb_client
+            .send_transaction(payload.transaction, payload.outputs_validator)
+            .await
+        {
+            Ok(hash) => (TransactionStatus::Pending, None, Ok(hash)),
+            // the transaction is already in the tx pool
+            Err(error) if node_error_code(&error) == Some(POOL_REJECTED_DUPLICATED_TRANSACTION) => {
+                (TransactionStatus::Pending, None, Ok(tx_hash.clone()))
+            }
+            Err(error) => {
+                // the node may be unreachable, so whether it received the transaction is
+                // unknown until the poll of the tx pool
+                let status = match node_error_code(&error) {
+                    Some(_) => TransactionStatus::Rejected,
+                    None => TransactionStatus::Unknown,
+                };
+                (
+                    status,
+                    Some(error.to_string()),
+                    Err(CoreError::CkbClientError(error.to_string()).into()),
+                )
+            }
+        };
+
+        self.storage
+            .insert_submitted_transaction(tx_hash, status, reason, submitted_at)
+            .await
+            .map_err(|error| CoreError::DBError(error.to_string()))?;
+        result
+    }
+
+    pub(crate) async fn inner_release_input_reservation(
+        &self,
+        payload: ReleaseInputReservationPayload,
+    ) -> InnerResult<Vec<OutPoint>> {
+        let out_points = INPUT_RESERVATIONS
+            .write()
+            .release_transaction(&payload.tx_hash);
+        Ok(out_points.into_iter().map(Into::into).collect())
+    }
 }
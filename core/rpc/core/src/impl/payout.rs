@@ -0,0 +1,127 @@
+use crate::r#impl::build_tx::calculate_tx_size;
+use crate::{error::CoreError, InnerResult, MercuryRpcImpl};
+
+use ckb_types::{packed, H256};
+use core_ckb_client::CkbRpc;
+use core_rpc_types::consts::{
+    DEFAULT_BATCH_PAYOUT_OUTPUT_NUM, DEFAULT_BATCH_PAYOUT_TX_SIZE, MAX_ITEM_NUM,
+};
+use core_rpc_types::lazy::INPUT_RESERVATIONS;
+use core_rpc_types::{BatchPayoutPayload, TransactionCompletionResponse, TransferPayload};
+
+use std::time::Duration;
+
+/// The inputs of the built transactions are reserved while the rest of the batch is
+/// being built, even if the input reservation is disabled.
+const BATCH_INPUT_RESERVATION_TTL: Duration = Duration::from_secs(60);
+
+impl<C: CkbRpc> MercuryRpcImpl<C> {
+    pub(crate) async fn inner_build_batch_payout(
+        &self,
+        payload: BatchPayoutPayload,
+    ) -> InnerResult<Vec<TransactionCompletionResponse>> {
+        if payload.from.is_empty() || payload.to.is_empty() {
+            return Err(CoreError::NeedAtLeastOneFromAndOneTo.into());
+        }
+        let max_outputs = payload
+            .max_outputs_per_tx
+            .map(Into::into)
+            .unwrap_or(DEFAULT_BATCH_PAYOUT_OUTPUT_NUM) as usize;
+        if max_outputs == 0 {
+            return Err(CoreError::InvalidRpcParams(
+                "max_outputs_per_tx should be positive".to_string(),
+            )
+            .into());
+        }
+        if max_outputs > MAX_ITEM_NUM {
+            return Err(CoreError::ExceedMaxItemNum.into());
+        }
+        let max_tx_size = payload
+            .max_tx_size
+            .map(Into::into)
+            .unwrap_or(DEFAULT_BATCH_PAYOUT_TX_SIZE) as usize;
+
+        let mut tx_hashes = vec![];
+        let res = self
+            .build_batch_payout_transactions(payload, max_outputs, max_tx_size, &mut tx_hashes)
+            .await;
+
+        // a failed batch releases all its inputs, and a built batch keeps them
+        // reserved only if the input reservation is enabled
+        if res.is_err() || self.input_reservation_ttl.is_zero() {
+            let mut reservations = INPUT_RESERVATIONS.write();
+            for tx_hash in tx_hashes.iter() {
+                reservations.release_transaction(tx_hash);
+            }
+        }
+        res
+    }
+
+    /// Build the transfer transactions in the order of the recipients. A transaction
+    /// exceeding the size limit is rebuilt with half of its recipients, and the
+    /// following transactions keep the reduced number of recipients.
+    async fn build_batch_payout_transactions(
+        &self,
+        payload: BatchPayoutPayload,
+        max_outputs: usize,
+        max_tx_size: usize,
+        tx_hashes: &mut Vec<H256>,
+    ) -> InnerResult<Vec<TransactionCompletionResponse>> {
+        let mut responses = vec![];
+        let mut outputs_num = max_outputs;
+        let mut start = 0;
+
+        while start < payload.to.len() {
+            let end = payload.to.len().min(start + outputs_num);
+            let response = self
+                .inner_build_transfer_transaction(TransferPayload {
+                    asset_info: payload.asset_info.clone(),
+                    from: payload.from.clone(),
+                    to: payload.to[start..end].to_vec(),
+                    output_capacity_provider: payload.output_capacity_provider,
+                    pay_fee: payload.pay_fee.clone(),
+                    fee_rate: payload.fee_rate,
+                    since: payload.since.clone(),
+                    multisig_configs: payload.multisig_configs.clone(),
+                    coin_selection: payload.coin_selection,
+                    verify: payload.verify,
+                })
+                .await?;
+            let tx_hash = response.tx_view.hash.clone();
+            let tx_size = calculate_tx_size(
+                &packed::Transaction::from(response.tx_view.inner.clone()).into_view(),
+            );
+
+            if tx_size > max_tx_size {
+                INPUT_RESERVATIONS.write().release_transaction(&tx_hash);
+                if end - start == 1 {
+                    return Err(CoreError::ExceedMaxTxSize(format!(
+                        "{}, the transaction paying {} is {} bytes",
+                        max_tx_size, payload.to[start].address, tx_size
+                    ))
+                    .into());
+                }
+                outputs_num = (end - start) / 2;
+                continue;
+            }
+
+            if self.input_reservation_ttl.is_zero() {
+                INPUT_RESERVATIONS.write().reserve(
+                    tx_hash.clone(),
+                    response
+                        .tx_view
+                        .inner
+                        .inputs
+                        .iter()
+                        .map(|input| input.previous_output.clone().into()),
+                    BATCH_INPUT_RESERVATION_TTL,
+                );
+            }
+            tx_hashes.push(tx_hash);
+            responses.push(response);
+            start = end;
+        }
+
+        Ok(responses)
+    }
+}
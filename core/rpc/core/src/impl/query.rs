@@ -1,27 +1,45 @@
 use crate::r#impl::utils;
+use crate::subscription::ItemMatcher;
 use crate::{error::CoreError, InnerResult, MercuryRpcImpl};
 
-use common::{DetailedCell, Order, PaginationRequest, Range};
+use common::address::is_secp256k1;
+use common::lazy::CHEQUE_CODE_HASH;
+use common::utils::{decode_dao_block_number, decode_udt_amount, u256_low_u64};
+use common::{Address, DetailedCell, Order, PaginationRequest, Range};
 use core_ckb_client::CkbRpc;
-use core_rpc_types::lazy::CURRENT_BLOCK_NUMBER;
+use core_rpc_types::consts::{
+    DEFAULT_BLOCKS_INFO_LIMIT, DEFAULT_CHAIN_EVENTS_LIMIT, MAX_BLOCKS_INFO_LIMIT,
+    MAX_CHAIN_EVENTS_LIMIT, MAX_ITEM_NUM, MIN_DAO_LOCK_PERIOD,
+};
+use core_rpc_types::lazy::{CURRENT_BLOCK_NUMBER, CURRENT_EPOCH_NUMBER, TX_POOL};
+use core_rpc_types::tx_pool::PoolCell;
 use core_rpc_types::{
-    indexer, AssetInfo, Balance, BlockInfo, BurnInfo, GetBalancePayload, GetBalanceResponse,
-    GetBlockInfoPayload, GetSpentTransactionPayload, GetTransactionInfoResponse, IOType, Item,
-    PaginationResponse, QueryTransactionsPayload, Record, StructureType, SyncProgress, SyncState,
-    TransactionInfo, TransactionStatus, TxView,
+    indexer, AddressLabel, AssetInfo, AssetType, Balance, BlockInfo, BurnInfo, ChequeInfo,
+    DaoPosition, ExtraType, GetBalancePayload, GetBalanceResponse, GetBlockInfoPayload,
+    GetBlocksInfoPayload, GetChainEventsPayload, GetChainEventsResponse, GetChequesPayload,
+    GetDaoPositionsPayload, GetSpentTransactionPayload, GetSubmittedTransactionsPayload,
+    GetTransactionInfoResponse, IOType, Item, JsonItem, PaginationResponse,
+    QueryTransactionsPayload, QueryTransactionsResponse, Record, SearchAddressLabelsPayload,
+    StructureType, SubmittedTransaction, SyncProgress, SyncState, TransactionDirection,
+    TransactionInfo, TransactionStatus, TransactionWithRichStatus, TxRichStatus, TxView,
 };
-use core_storage::{DBInfo, Storage, TransactionWrapper};
+use core_storage::{DBInfo, SimpleBlock, Storage, TransactionFilter, TransactionWrapper};
 
 use ckb_jsonrpc_types::{self, Capacity, Script};
-use ckb_types::{packed, prelude::*, H256};
+use ckb_types::core::{EpochNumberWithFraction, HeaderView, RationalU256};
+use ckb_types::{packed, prelude::*, H256, U256};
 use num_bigint::{BigInt, Sign};
 use num_traits::{ToPrimitive, Zero};
 
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::From;
 use std::ops::Neg;
+use std::str::FromStr;
 use std::{convert::TryInto, iter::Iterator};
 
+/// The number of transactions loaded by one query when building the block infos.
+const BLOCK_TRANSACTIONS_BATCH_SIZE: usize = 500;
+
 impl<C: CkbRpc> MercuryRpcImpl<C> {
     pub(crate) fn inner_get_db_info(&self) -> InnerResult<DBInfo> {
         self.storage
@@ -55,11 +73,18 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                 payload.tip_block_number.map(Into::into),
                 tip_epoch_number.clone(),
                 None,
-                payload.extra,
+                payload.extra.clone(),
                 &mut PaginationRequest::default(),
             )
             .await?;
 
+        let tip_block_number = payload
+            .tip_block_number
+            .map(Into::into)
+            .unwrap_or(**CURRENT_BLOCK_NUMBER.load());
+        let min_confirmations = payload.min_confirmations.map(u64::from).unwrap_or(0);
+        // the tx pool only applies to the latest blockchain
+        let with_pending = payload.tip_block_number.is_none();
         let mut balances_map: BTreeMap<(String, AssetInfo), Balance> = BTreeMap::new();
 
         for cell in live_cells {
@@ -80,12 +105,36 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                     self.filter_cheque_record(record, &item, &cell, tip_epoch_number.clone())
                 })
                 .collect();
+            let pending_records = if with_pending && self.is_in_cache(&cell.out_point) {
+                records.clone()
+            } else {
+                vec![]
+            };
             self.accumulate_balance_from_records(
                 &mut balances_map,
                 records,
                 tip_epoch_number.clone(),
+                &cell,
+                payload.detailed.unwrap_or(false),
+                utils::is_confirmed(cell.block_number, tip_block_number, min_confirmations),
             )
             .await?;
+            for record in pending_records {
+                if let Some(balance) = balances_map.get_mut(&(record.ownership, record.asset_info))
+                {
+                    balance.pending_outgoing =
+                        (balance.pending_outgoing.value() + record.amount.value()).into();
+                }
+            }
+        }
+
+        // the pending incoming amounts of dao or cellbase cells are not supported
+        if with_pending && payload.extra.is_none() {
+            self.accumulate_pending_incoming(
+                &mut balances_map,
+                payload.item.clone(),
+                &payload.asset_infos,
+            );
         }
 
         let balances = balances_map
@@ -95,12 +144,57 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
 
         Ok(GetBalanceResponse {
             balances,
-            tip_block_number: payload
-                .tip_block_number
-                .unwrap_or_else(|| (**CURRENT_BLOCK_NUMBER.load()).into()),
+            tip_block_number: tip_block_number.into(),
         })
     }
 
+    /// Accumulate the outputs of the pool transactions owned by the item, except the
+    /// ones consumed by other pool transactions.
+    fn accumulate_pending_incoming(
+        &self,
+        balances_map: &mut BTreeMap<(String, AssetInfo), Balance>,
+        item: JsonItem,
+        asset_infos: &HashSet<AssetInfo>,
+    ) {
+        let matcher = match ItemMatcher::new(item) {
+            Ok(matcher) => matcher,
+            Err(_) => return,
+        };
+        let output_cells: Vec<PoolCell> = TX_POOL
+            .read()
+            .transactions()
+            .flat_map(|pool_tx| pool_tx.output_cells())
+            .filter(|cell| matcher.is_cell_match(&cell.output.lock(), &cell.out_point))
+            .collect();
+
+        for cell in output_cells {
+            if self.is_in_cache(&cell.out_point) {
+                continue;
+            }
+            let ownership = self.script_to_address(&cell.output.lock()).to_string();
+            let capacity: u64 = cell.output.capacity().unpack();
+            let mut amounts = vec![(AssetInfo::new_ckb(), capacity as u128)];
+            if let Some(asset_info) = cell
+                .output
+                .type_()
+                .to_opt()
+                .and_then(|type_script| utils::udt_asset_info(&type_script))
+            {
+                amounts.push((asset_info, decode_udt_amount(&cell.data).unwrap_or(0)));
+            }
+
+            for (asset_info, amount) in amounts {
+                if !asset_infos.is_empty() && !asset_infos.contains(&asset_info) {
+                    continue;
+                }
+                let balance = balances_map
+                    .entry((ownership.clone(), asset_info.clone()))
+                    .or_insert_with(|| Balance::new(ownership.clone(), asset_info));
+                balance.pending_incoming = (balance.pending_incoming.value() + amount).into();
+            }
+        }
+    }
+
     pub(crate) async fn inner_get_block_info(
         &self,
         payload: GetBlockInfoPayload,
@@ -114,39 +208,227 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             Err(error) => return Err(CoreError::DBError(error.to_string()).into()),
         };
 
-        let mut transactions = vec![];
-        for tx_hash in block_info.transactions {
-            let tx_info = self
-                .inner_get_transaction_info(tx_hash)
-                .await
-                .map(|res| res.transaction.expect("impossible: cannot find the tx"))?;
-            transactions.push(tx_info);
+        self.to_block_infos(vec![block_info])
+            .await
+            .map(|mut block_infos| block_infos.remove(0))
+    }
+
+    pub(crate) async fn inner_get_blocks_info(
+        &self,
+        payload: GetBlocksInfoPayload,
+    ) -> InnerResult<Vec<BlockInfo>> {
+        let block_range: Range = payload.block_range.into();
+        if block_range.from > block_range.to {
+            return Err(CoreError::InvalidRpcParams(format!(
+                "invalid block range from {} to {}",
+                block_range.from, block_range.to
+            ))
+            .into());
+        }
+        let limit = payload
+            .limit
+            .map(Into::into)
+            .unwrap_or(DEFAULT_BLOCKS_INFO_LIMIT);
+        if limit == 0 || limit > MAX_BLOCKS_INFO_LIMIT {
+            return Err(CoreError::InvalidRpcParams(format!(
+                "limit should be between 1 and {}",
+                MAX_BLOCKS_INFO_LIMIT
+            ))
+            .into());
+        }
+
+        let blocks = self
+            .storage
+            .get_simple_blocks(block_range, limit)
+            .await
+            .map_err(|e| CoreError::DBError(e.to_string()))?;
+        self.to_block_infos(blocks).await
+    }
+
+    pub(crate) async fn inner_get_chain_events(
+        &self,
+        payload: GetChainEventsPayload,
+    ) -> InnerResult<GetChainEventsResponse> {
+        let cursor = payload.cursor.map(Into::into).unwrap_or(0u64);
+        let limit = payload
+            .limit
+            .map(Into::into)
+            .unwrap_or(DEFAULT_CHAIN_EVENTS_LIMIT);
+        if limit == 0 || limit > MAX_CHAIN_EVENTS_LIMIT {
+            return Err(CoreError::InvalidRpcParams(format!(
+                "limit should be between 1 and {}",
+                MAX_CHAIN_EVENTS_LIMIT
+            ))
+            .into());
         }
 
-        Ok(BlockInfo {
-            block_number: block_info.block_number.into(),
-            block_hash: block_info.block_hash,
-            parent_hash: block_info.parent_hash,
-            timestamp: block_info.timestamp.into(),
-            transactions,
+        let events = self
+            .storage
+            .get_chain_events(cursor, limit)
+            .await
+            .map_err(|e| CoreError::DBError(e.to_string()))?;
+        let next_cursor = events
+            .last()
+            .map(|event| event.id.value())
+            .unwrap_or(cursor);
+        Ok(GetChainEventsResponse {
+            events,
+            next_cursor: next_cursor.into(),
         })
     }
 
+    /// Load the transactions of the blocks together with their input and output cells
+    /// in batches, instead of querying them one transaction after another. Only the
+    /// transactions and cells are batched, while the records of the DAO and cellbase
+    /// cells still query the headers and epochs they need cell by cell.
+    async fn to_block_infos(&self, blocks: Vec<SimpleBlock>) -> InnerResult<Vec<BlockInfo>> {
+        let tx_hashes: Vec<H256> = blocks
+            .iter()
+            .flat_map(|block| block.transactions.iter().cloned())
+            .collect();
+        let mut tx_wrappers = HashMap::new();
+        for tx_hashes in tx_hashes.chunks(BLOCK_TRANSACTIONS_BATCH_SIZE) {
+            let txs = self
+                .storage
+                .get_transactions_by_hashes(tx_hashes.to_vec(), None, PaginationRequest::default())
+                .await
+                .map_err(|e| CoreError::DBError(e.to_string()))?
+                .response;
+            for tx_wrapper in txs {
+                tx_wrappers.insert(tx_wrapper.transaction_view.hash().unpack(), tx_wrapper);
+            }
+        }
+
+        let mut block_infos = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            let mut transactions = Vec::with_capacity(block.transactions.len());
+            for tx_hash in block.transactions.iter() {
+                let tx_wrapper: &TransactionWrapper = tx_wrappers
+                    .get(tx_hash)
+                    .ok_or(CoreError::CannotFindTransactionByHash)?;
+                transactions.push(self.query_transaction_info(tx_wrapper).await?);
+            }
+            block_infos.push(BlockInfo {
+                block_number: block.block_number.into(),
+                block_hash: block.block_hash,
+                parent_hash: block.parent_hash,
+                timestamp: block.timestamp.into(),
+                transactions,
+            });
+        }
+        Ok(block_infos)
+    }
+
     pub(crate) async fn inner_query_transactions(
         &self,
         payload: QueryTransactionsPayload,
-    ) -> InnerResult<PaginationResponse<TxView>> {
+    ) -> InnerResult<QueryTransactionsResponse> {
+        let include_pending = payload.include_pending.unwrap_or(false);
+        if include_pending && payload.structure_type == StructureType::DoubleEntry {
+            return Err(CoreError::InvalidRpcParams(
+                "pending transactions are only returned in the Native structure".to_string(),
+            )
+            .into());
+        }
+        // the amount range filters the change of the CKB capacity or of the UDT amount,
+        // which are not comparable to each other
+        if payload.amount_range.is_some() && payload.asset_infos.len() != 1 {
+            return Err(CoreError::InvalidRpcParams(
+                "amount_range is only supported if asset_infos has one asset".to_string(),
+            )
+            .into());
+        }
+        // the pending transactions are not in any block, so they are only returned with the
+        // first page when no block related filter is specified
+        let pending = if include_pending
+            && payload.pagination.cursor.is_none()
+            && payload.block_range.is_none()
+            && payload.time_range.is_none()
+            && payload.counterparty.is_none()
+            && payload.amount_range.is_none()
+            && payload.min_confirmations.map_or(true, |n| n.value() == 0)
+        {
+            self.query_pending_transactions(
+                payload.item.clone(),
+                &payload.asset_infos,
+                payload.direction,
+            )?
+        } else {
+            vec![]
+        };
+
+        let empty_response = QueryTransactionsResponse {
+            response: vec![],
+            next_cursor: None,
+            count: if payload.pagination.return_count {
+                Some(0u64.into())
+            } else {
+                None
+            },
+            pending: vec![],
+        };
+        let block_range: Option<Range> = payload.block_range.map(Into::into);
+        let block_range = match payload.min_confirmations.map(u64::from) {
+            Some(min_confirmations) if min_confirmations > 0 => {
+                // the transactions in the blocks after it have less confirmations
+                let tip_block_number = **CURRENT_BLOCK_NUMBER.load();
+                let max_block_number = match (tip_block_number + 1).checked_sub(min_confirmations) {
+                    Some(max_block_number) => max_block_number,
+                    None => return Ok(empty_response),
+                };
+                let block_range = match block_range {
+                    Some(range) => Range::new(range.from, range.to.min(max_block_number)),
+                    None => Range::new(0, max_block_number),
+                };
+                if block_range.from > block_range.to {
+                    return Ok(empty_response);
+                }
+                Some(block_range)
+            }
+            _ => block_range,
+        };
+        let counterparty_lock_hashes = match payload.counterparty {
+            Some(counterparty) => {
+                let lock_hashes = self.get_counterparty_lock_hashes(counterparty).await?;
+                if lock_hashes.is_empty() {
+                    return Ok(empty_response);
+                }
+                lock_hashes
+            }
+            None => vec![],
+        };
+        let (capacity_change_range, udt_amount_change_range) =
+            match (payload.amount_range, payload.asset_infos.iter().next()) {
+                (Some(amount_range), Some(asset_info))
+                    if asset_info.asset_type == AssetType::UDT =>
+                {
+                    (
+                        None,
+                        Some((asset_info.udt_hash.clone(), amount_range.into())),
+                    )
+                }
+                (amount_range, _) => (amount_range.map(Into::into), None),
+            };
+        let filter = TransactionFilter {
+            counterparty_lock_hashes,
+            direction: payload.direction.map(Into::into),
+            capacity_change_range,
+            udt_amount_change_range,
+            time_range: payload.time_range.map(Into::into),
+        };
+
         let pagination_ret = self
             .get_transactions_by_item(
                 payload.item.try_into()?,
                 payload.asset_infos,
                 payload.extra,
-                payload.block_range.map(Into::into),
+                block_range,
+                filter,
                 payload.pagination.into(),
             )
             .await?;
         match &payload.structure_type {
-            StructureType::Native => Ok(PaginationResponse {
+            StructureType::Native => Ok(QueryTransactionsResponse {
                 response: pagination_ret
                     .response
                     .into_iter()
@@ -154,24 +436,297 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                     .collect(),
                 next_cursor: pagination_ret.next_cursor.map(Into::into),
                 count: pagination_ret.count.map(Into::into),
+                pending,
             }),
 
             StructureType::DoubleEntry => {
                 let mut tx_infos = vec![];
                 for tx_view in pagination_ret.response.into_iter() {
-                    let tx_info =
-                        TxView::TransactionInfo(self.query_transaction_info(&tx_view).await?);
-                    tx_infos.push(tx_info);
+                    tx_infos.push(self.query_transaction_info(&tx_view).await?);
                 }
-                Ok(PaginationResponse {
+                if payload.with_labels.unwrap_or(false) {
+                    self.fill_record_labels(&mut tx_infos).await?;
+                }
+                let tx_infos = tx_infos.into_iter().map(TxView::TransactionInfo).collect();
+                Ok(QueryTransactionsResponse {
                     response: tx_infos,
                     next_cursor: pagination_ret.next_cursor.map(Into::into),
                     count: pagination_ret.count.map(Into::into),
+                    pending,
                 })
             }
         }
     }
 
+    /// The pool transactions which consume or create the cells of the item, ordered by
+    /// the transaction hash.
+    fn query_pending_transactions(
+        &self,
+        item: JsonItem,
+        asset_infos: &HashSet<AssetInfo>,
+        direction: Option<TransactionDirection>,
+    ) -> InnerResult<Vec<TxView>> {
+        let matcher = ItemMatcher::new(item)?;
+        let is_asset_cell = |cell: &PoolCell| {
+            asset_infos.is_empty()
+                || asset_infos
+                    .iter()
+                    .any(|asset_info| match asset_info.asset_type {
+                        AssetType::CKB => true,
+                        _ => {
+                            cell.output
+                                .type_()
+                                .to_opt()
+                                .and_then(|type_script| utils::udt_asset_info(&type_script))
+                                .as_ref()
+                                == Some(asset_info)
+                        }
+                    })
+        };
+
+        let pool = TX_POOL.read();
+        let mut pending_txs = vec![];
+        for pool_tx in pool.transactions() {
+            let output_cells = pool_tx.output_cells();
+            let is_outgoing = pool_tx
+                .input_cells
+                .iter()
+                .any(|cell| matcher.is_cell_match(&cell.output.lock(), &cell.out_point));
+            let is_involved = pool_tx
+                .input_cells
+                .iter()
+                .chain(output_cells.iter())
+                .filter(|cell| matcher.is_cell_match(&cell.output.lock(), &cell.out_point))
+                .any(is_asset_cell);
+            if !is_involved {
+                continue;
+            }
+            match direction {
+                Some(TransactionDirection::Incoming) if is_outgoing => continue,
+                Some(TransactionDirection::Outgoing) if !is_outgoing => continue,
+                _ => (),
+            }
+            pending_txs.push(pool_tx);
+        }
+        pending_txs.sort_by_key(|pool_tx| -> H256 { pool_tx.transaction.hash().unpack() });
+
+        Ok(pending_txs
+            .into_iter()
+            .map(|pool_tx| {
+                TxView::TransactionWithRichStatus(TransactionWithRichStatus {
+                    transaction: Some(pool_tx.transaction.clone().into()),
+                    tx_status: TxRichStatus {
+                        status: pool_tx.status.clone(),
+                        block_hash: None,
+                        reason: None,
+                        timestamp: None,
+                    },
+                })
+            })
+            .collect())
+    }
+
+    pub(crate) async fn inner_get_cheques(
+        &self,
+        payload: GetChequesPayload,
+    ) -> InnerResult<PaginationResponse<ChequeInfo>> {
+        let item: Item = payload.item.try_into()?;
+        let item = match item {
+            // the cheques of a secp address are indexed by its identity
+            Item::Address(address) => {
+                let parsed = Address::from_str(&address).map_err(CoreError::ParseAddressError)?;
+                if is_secp256k1(&parsed) {
+                    Item::Identity(self.address_to_identity(&address)?)
+                } else {
+                    Item::Address(address)
+                }
+            }
+            item => item,
+        };
+
+        let cell_page = self
+            .get_live_cells_page_by_item(
+                item,
+                payload.asset_infos,
+                None,
+                CHEQUE_CODE_HASH.get(),
+                None,
+                payload.pagination.into(),
+            )
+            .await?;
+
+        let tip_epoch_number = (**CURRENT_EPOCH_NUMBER.load()).clone();
+        let mut cheques = vec![];
+        for cell in cell_page.response {
+            if !utils::has_cheque_lock_args(&cell) {
+                continue;
+            }
+            let asset_info = match cell
+                .cell_output
+                .type_()
+                .to_opt()
+                .and_then(|type_script| utils::udt_asset_info(&type_script))
+            {
+                Some(asset_info) => asset_info,
+                None => continue,
+            };
+            let sender = self.get_cheque_sender_address(&cell).await?;
+            let receiver = self.get_cheque_receiver_address(&cell).await?;
+            let capacity: u64 = cell.cell_output.capacity().unpack();
+            let sender_withdrawable = self.is_unlock(
+                EpochNumberWithFraction::from_full_value(cell.epoch_number).to_rational(),
+                Some(tip_epoch_number.clone()),
+                self.cheque_timeout.clone(),
+            );
+
+            cheques.push(ChequeInfo {
+                out_point: cell.out_point.into(),
+                sender: sender.to_string(),
+                receiver: receiver.to_string(),
+                asset_info,
+                amount: decode_udt_amount(&cell.cell_data).unwrap_or(0).into(),
+                capacity: capacity.into(),
+                block_number: cell.block_number.into(),
+                epoch_number: cell.epoch_number.into(),
+                sender_withdrawable,
+            });
+        }
+
+        Ok(PaginationResponse {
+            response: cheques,
+            next_cursor: cell_page.next_cursor.map(Into::into),
+            count: cell_page.count.map(Into::into),
+        })
+    }
+
+    pub(crate) async fn inner_get_dao_positions(
+        &self,
+        payload: GetDaoPositionsPayload,
+    ) -> InnerResult<PaginationResponse<DaoPosition>> {
+        let item: Item = payload.item.try_into()?;
+        let cell_page = self
+            .get_live_cells_page_by_item(
+                item,
+                HashSet::new(),
+                None,
+                None,
+                Some(ExtraType::Dao),
+                payload.pagination.into(),
+            )
+            .await?;
+
+        let tip_header = self
+            .storage
+            .get_block_header(None, None)
+            .await
+            .map_err(|e| CoreError::DBError(e.to_string()))?;
+        let mut positions = vec![];
+        for cell in cell_page.response {
+            positions.push(self.to_dao_position(cell, &tip_header).await?);
+        }
+
+        Ok(PaginationResponse {
+            response: positions,
+            next_cursor: cell_page.next_cursor.map(Into::into),
+            count: cell_page.count.map(Into::into),
+        })
+    }
+
+    /// The compensation of a deposit cell accrues until the tip block, and its unlock
+    /// epoch is the earliest one if it is withdrawn in the next block.
+    async fn to_dao_position(
+        &self,
+        cell: DetailedCell,
+        tip_header: &HeaderView,
+    ) -> InnerResult<DaoPosition> {
+        let capacity: u64 = cell.cell_output.capacity().unpack();
+        let tip_epoch = tip_header.epoch();
+
+        let (deposit_header, withdraw_header) = if cell.cell_data[..] == [0u8; 8] {
+            let deposit_header = self
+                .storage
+                .get_block_header(Some(cell.block_hash.clone()), None)
+                .await
+                .map_err(|e| CoreError::DBError(e.to_string()))?;
+            (deposit_header, None)
+        } else {
+            let deposit_header = self
+                .storage
+                .get_block_header(None, Some(decode_dao_block_number(&cell.cell_data)))
+                .await
+                .map_err(|e| CoreError::DBError(e.to_string()))?;
+            let withdraw_header = self
+                .storage
+                .get_block_header(Some(cell.block_hash.clone()), None)
+                .await
+                .map_err(|e| CoreError::DBError(e.to_string()))?;
+            (deposit_header, Some(withdraw_header))
+        };
+
+        let end_header = withdraw_header.as_ref().unwrap_or(tip_header);
+        let compensation = self
+            .calculate_maximum_withdraw(
+                &cell,
+                deposit_header.hash().unpack(),
+                end_header.hash().unpack(),
+            )
+            .await?
+            .saturating_sub(capacity);
+
+        let deposit_epoch = deposit_header.epoch();
+        let mut unlock_epoch =
+            EpochNumberWithFraction::from_full_value(utils::calculate_unlock_epoch_number(
+                deposit_epoch.full_value(),
+                end_header.epoch().full_value(),
+            ));
+        if withdraw_header.is_none() && unlock_epoch.to_rational() <= tip_epoch.to_rational() {
+            unlock_epoch = EpochNumberWithFraction::new(
+                unlock_epoch.number() + MIN_DAO_LOCK_PERIOD,
+                unlock_epoch.index(),
+                unlock_epoch.length(),
+            );
+        }
+
+        Ok(DaoPosition {
+            out_point: cell.out_point.into(),
+            capacity: capacity.into(),
+            deposit_block_number: deposit_header.number().into(),
+            deposit_epoch_number: deposit_epoch.full_value().into(),
+            withdraw_block_number: withdraw_header
+                .as_ref()
+                .map(|header| header.number().into()),
+            withdraw_epoch_number: withdraw_header
+                .as_ref()
+                .map(|header| header.epoch().full_value().into()),
+            compensation: compensation.into(),
+            unlock_epoch_number: unlock_epoch.full_value().into(),
+            estimated_unlock_timestamp: self
+                .estimate_epoch_timestamp(&unlock_epoch, tip_header)
+                .into(),
+            claimable: withdraw_header.is_some()
+                && unlock_epoch.to_rational() <= tip_epoch.to_rational(),
+        })
+    }
+
+    /// Estimate the timestamp of an epoch from the tip block and the target epoch
+    /// duration. A reached epoch is estimated as the timestamp of the tip block.
+    fn estimate_epoch_timestamp(
+        &self,
+        epoch: &EpochNumberWithFraction,
+        tip_header: &HeaderView,
+    ) -> u64 {
+        let (epoch, tip_epoch) = (epoch.to_rational(), tip_header.epoch().to_rational());
+        if epoch <= tip_epoch {
+            return tip_header.timestamp();
+        }
+        let epoch_duration =
+            RationalU256::from_u256(U256::from(self.consensus.epoch_duration_target() * 1000));
+        let remaining = (epoch - tip_epoch) * epoch_duration;
+        tip_header
+            .timestamp()
+            .saturating_add(u256_low_u64(remaining.into_u256()))
+    }
+
     pub(crate) async fn inner_get_tip(&self) -> InnerResult<Option<indexer::Tip>> {
         let block = self
             .storage
@@ -240,6 +795,107 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         }
     }
 
+    pub(crate) async fn inner_get_submitted_transactions(
+        &self,
+        payload: GetSubmittedTransactionsPayload,
+    ) -> InnerResult<PaginationResponse<SubmittedTransaction>> {
+        let page = self
+            .storage
+            .get_submitted_transactions(
+                payload.tx_hashes,
+                payload.statuses,
+                payload.pagination.into(),
+            )
+            .await
+            .map_err(|error| CoreError::DBError(error.to_string()))?;
+
+        Ok(PaginationResponse {
+            response: page.response,
+            next_cursor: page.next_cursor.map(Into::into),
+            count: page.count.map(Into::into),
+        })
+    }
+
+    /// Return the labels in the order of the items, the items without a label are skipped.
+    pub(crate) async fn inner_get_address_labels(
+        &self,
+        items: Vec<JsonItem>,
+    ) -> InnerResult<Vec<AddressLabel>> {
+        if items.len() > MAX_ITEM_NUM {
+            return Err(CoreError::ExceedMaxItemNum.into());
+        }
+        let items = items
+            .into_iter()
+            .map(|item| self.to_label_item(item))
+            .collect::<InnerResult<Vec<JsonItem>>>()?;
+
+        let mut labels: HashMap<JsonItem, AddressLabel> = self
+            .storage
+            .get_address_labels(items.clone())
+            .await
+            .map_err(|error| CoreError::DBError(error.to_string()))?
+            .into_iter()
+            .map(|label| (label.item.clone(), label))
+            .collect();
+        Ok(items
+            .iter()
+            .filter_map(|item| labels.remove(item))
+            .collect())
+    }
+
+    pub(crate) async fn inner_search_address_labels(
+        &self,
+        payload: SearchAddressLabelsPayload,
+    ) -> InnerResult<PaginationResponse<AddressLabel>> {
+        let page = self
+            .storage
+            .search_address_labels(payload.keyword, payload.pagination.into())
+            .await
+            .map_err(|error| CoreError::DBError(error.to_string()))?;
+
+        Ok(PaginationResponse {
+            response: page.response,
+            next_cursor: page.next_cursor.map(Into::into),
+            count: page.count.map(Into::into),
+        })
+    }
+
+    /// Attach the label of the ownership to every record. The label of the address takes
+    /// precedence over the label of its identity.
+    async fn fill_record_labels(&self, tx_infos: &mut [TransactionInfo]) -> InnerResult<()> {
+        let ownerships: HashSet<String> = tx_infos
+            .iter()
+            .flat_map(|tx_info| tx_info.records.iter())
+            .map(|record| record.ownership.clone())
+            .collect();
+        let mut label_items: HashMap<String, Vec<JsonItem>> = HashMap::new();
+        for ownership in ownerships.into_iter() {
+            let mut items = vec![JsonItem::Address(ownership.clone())];
+            if let Ok(identity) = self.address_to_identity(&ownership) {
+                items.push(JsonItem::Identity(identity.encode()));
+            }
+            label_items.insert(ownership, items);
+        }
+
+        let labels: HashMap<JsonItem, String> = self
+            .storage
+            .get_address_labels(label_items.values().flatten().cloned().collect())
+            .await
+            .map_err(|error| CoreError::DBError(error.to_string()))?
+            .into_iter()
+            .map(|label| (label.item, label.label))
+            .collect();
+        for record in tx_infos
+            .iter_mut()
+            .flat_map(|tx_info| tx_info.records.iter_mut())
+        {
+            record.label = label_items[&record.ownership]
+                .iter()
+                .find_map(|item| labels.get(item).cloned());
+        }
+        Ok(())
+    }
+
     pub(crate) async fn inner_get_cells_capacity(
         &self,
         payload: indexer::SearchKey,
@@ -498,7 +1154,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         })
     }
 
-    async fn query_transaction_info(
+    pub(crate) async fn query_transaction_info(
         &self,
         tx_wrapper: &TransactionWrapper,
     ) -> InnerResult<TransactionInfo> {
@@ -576,6 +1232,26 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         })
     }
 
+    async fn get_counterparty_lock_hashes(&self, counterparty: JsonItem) -> InnerResult<Vec<H256>> {
+        let scripts = match Item::try_from(counterparty)? {
+            Item::Identity(ident) => self.get_scripts_by_identity(ident, None).await?,
+            Item::Address(address) => {
+                let address = Address::from_str(&address).map_err(CoreError::ParseAddressError)?;
+                self.get_scripts_by_address(&address, None).await?
+            }
+            Item::OutPoint(_) => {
+                return Err(CoreError::InvalidRpcParams(
+                    "the counterparty should be an identity or an address".to_string(),
+                )
+                .into())
+            }
+        };
+        Ok(scripts
+            .iter()
+            .map(|script| script.calc_script_hash().unpack())
+            .collect())
+    }
+
     async fn get_live_cells_by_search_key(
         &self,
         search_key: indexer::SearchKey,
@@ -0,0 +1,350 @@
+use crate::{error::CoreError, InnerResult, MercuryRpcImpl};
+
+use common::{Address, Order, PaginationRequest, Range};
+use core_ckb_client::CkbRpc;
+use core_rpc_types::consts::MAX_STATEMENT_ENTRY_NUM;
+use core_rpc_types::lazy::CURRENT_BLOCK_NUMBER;
+use core_rpc_types::{
+    AccountStatement, AssetInfo, GetAccountStatementPayload, GetBalancePayload, IOType, Item,
+    StatementEntry, StatementFormat,
+};
+use core_storage::{Storage, TransactionFilter, TransactionWrapper};
+
+use ckb_types::core::BlockNumber;
+
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+const STATEMENT_PAGE_SIZE: u16 = 500;
+
+impl<C: CkbRpc> MercuryRpcImpl<C> {
+    pub(crate) async fn inner_get_account_statement(
+        &self,
+        payload: GetAccountStatementPayload,
+    ) -> InnerResult<AccountStatement> {
+        let item = Item::try_from(payload.item.clone())?;
+        let scripts = match &item {
+            Item::Identity(ident) => self.get_scripts_by_identity(ident.clone(), None).await?,
+            Item::Address(address) => {
+                let address = Address::from_str(address).map_err(CoreError::ParseAddressError)?;
+                self.get_scripts_by_address(&address, None).await?
+            }
+            Item::OutPoint(_) => {
+                return Err(CoreError::InvalidRpcParams(
+                    "the statement of an out point is not supported".to_string(),
+                )
+                .into())
+            }
+        };
+        let ownerships: HashSet<String> = scripts
+            .iter()
+            .map(|script| self.script_to_address(script).to_string())
+            .collect();
+
+        let mut statement = AccountStatement {
+            asset_info: payload.asset_info.clone(),
+            opening_balance: 0u128.into(),
+            closing_balance: 0u128.into(),
+            entries: vec![],
+            export: None,
+            next_cursor: None,
+        };
+        let mut block_range = match self.get_statement_block_range(&payload).await? {
+            Some(block_range) => block_range,
+            None => return Ok(export_statement(statement, payload.format)),
+        };
+        if let Some(cursor) = payload.cursor {
+            block_range.from = block_range.from.max(cursor.into());
+        }
+
+        let mut balance = if block_range.from > 0 {
+            self.get_statement_balance(&payload, block_range.from - 1)
+                .await?
+        } else {
+            0
+        };
+        statement.opening_balance = balance.into();
+
+        let page_range = self
+            .get_statement_page_range(item.clone(), &payload, block_range.clone())
+            .await?;
+        if page_range.to < block_range.to {
+            statement.next_cursor = Some((page_range.to + 1).into());
+        }
+
+        for tx_wrapper in self
+            .get_statement_transactions(item, &payload, page_range)
+            .await?
+        {
+            let tx_info = self.query_transaction_info(&tx_wrapper).await?;
+            let (mut received, mut sent) = (0u128, 0u128);
+            let (mut ckb_input, mut owned_ckb_input) = (0u128, 0u128);
+            for record in tx_info.records.iter() {
+                let amount: u128 = record.amount.into();
+                let is_owned = ownerships.contains(&record.ownership);
+                if record.io_type == IOType::Input && record.asset_info == AssetInfo::new_ckb() {
+                    ckb_input += amount;
+                    if is_owned {
+                        owned_ckb_input += amount;
+                    }
+                }
+                if !is_owned || record.asset_info != payload.asset_info {
+                    continue;
+                }
+                match record.io_type {
+                    IOType::Input => sent += amount,
+                    IOType::Output => received += amount,
+                }
+            }
+
+            // the fee is shared by the owners of the inputs in proportion to their capacity
+            let fee: u64 = tx_info.fee.into();
+            let fee = if ckb_input == 0 {
+                0
+            } else {
+                (fee as u128)
+                    .checked_mul(owned_ckb_input)
+                    .ok_or(CoreError::Overflow)?
+                    / ckb_input
+            };
+            if received == 0 && sent == 0 && fee == 0 {
+                continue;
+            }
+
+            balance = balance
+                .checked_add(received)
+                .and_then(|balance| balance.checked_sub(sent))
+                .ok_or(CoreError::Overflow)?;
+            statement.entries.push(StatementEntry {
+                tx_hash: tx_info.tx_hash,
+                block_number: tx_position(&tx_wrapper).0.into(),
+                timestamp: tx_info.timestamp,
+                received: received.into(),
+                sent: sent.into(),
+                fee: (fee as u64).into(),
+                balance: balance.into(),
+            });
+        }
+        statement.closing_balance = balance.into();
+
+        Ok(export_statement(statement, payload.format))
+    }
+
+    /// Return the block range of the statement, which may be empty if there is no block
+    /// in the time range, or `None` if the time range is earlier than the genesis block.
+    async fn get_statement_block_range(
+        &self,
+        payload: &GetAccountStatementPayload,
+    ) -> InnerResult<Option<Range>> {
+        let tip_block_number = **CURRENT_BLOCK_NUMBER.load();
+        let block_range: Range = match (&payload.block_range, &payload.time_range) {
+            (Some(block_range), None) => block_range.clone().into(),
+            (None, Some(time_range)) => {
+                let time_range: Range = time_range.clone().into();
+                if time_range.from > time_range.to {
+                    return Err(CoreError::InvalidRpcParams(format!(
+                        "invalid time range from {} to {}",
+                        time_range.from, time_range.to
+                    ))
+                    .into());
+                }
+                let to = match self.get_block_number_by_timestamp(time_range.to).await? {
+                    Some(to) => to,
+                    None => return Ok(None),
+                };
+                let from = match time_range.from.checked_sub(1) {
+                    Some(timestamp) => self
+                        .get_block_number_by_timestamp(timestamp)
+                        .await?
+                        .map_or(0, |block_number| block_number + 1),
+                    None => 0,
+                };
+                return Ok(Some(Range::new(
+                    from.min(tip_block_number + 1),
+                    to.min(tip_block_number),
+                )));
+            }
+            _ => {
+                return Err(CoreError::InvalidRpcParams(
+                    "need either block_range or time_range".to_string(),
+                )
+                .into())
+            }
+        };
+
+        if block_range.from > block_range.to {
+            return Err(CoreError::InvalidRpcParams(format!(
+                "invalid block range from {} to {}",
+                block_range.from, block_range.to
+            ))
+            .into());
+        }
+        Ok(Some(Range::new(
+            block_range.from.min(tip_block_number + 1),
+            block_range.to.min(tip_block_number),
+        )))
+    }
+
+    async fn get_block_number_by_timestamp(
+        &self,
+        timestamp: u64,
+    ) -> InnerResult<Option<BlockNumber>> {
+        self.storage
+            .get_block_number_by_timestamp(timestamp)
+            .await
+            .map_err(|e| CoreError::DBError(e.to_string()).into())
+    }
+
+    /// The total amount of the asset owned by the item at the end of the block.
+    async fn get_statement_balance(
+        &self,
+        payload: &GetAccountStatementPayload,
+        block_number: BlockNumber,
+    ) -> InnerResult<u128> {
+        let response = self
+            .inner_get_balance(GetBalancePayload {
+                item: payload.item.clone(),
+                asset_infos: vec![payload.asset_info.clone()].into_iter().collect(),
+                extra: None,
+                tip_block_number: Some(block_number.into()),
+                detailed: None,
+                min_confirmations: None,
+            })
+            .await?;
+
+        let mut balance = 0u128;
+        for item_balance in response.balances.iter() {
+            if item_balance.asset_info != payload.asset_info {
+                continue;
+            }
+            balance = balance
+                .checked_add(item_balance.free.into())
+                .and_then(|balance| balance.checked_add(item_balance.occupied.into()))
+                .and_then(|balance| balance.checked_add(item_balance.frozen.into()))
+                .ok_or(CoreError::Overflow)?;
+        }
+        Ok(balance)
+    }
+
+    /// Narrow the block range from its start until it covers at most
+    /// `MAX_STATEMENT_ENTRY_NUM` transactions, by halving the end of the range. A single
+    /// block is never split, so its transactions are all covered even if there are more.
+    async fn get_statement_page_range(
+        &self,
+        item: Item,
+        payload: &GetAccountStatementPayload,
+        mut block_range: Range,
+    ) -> InnerResult<Range> {
+        while block_range.from < block_range.to {
+            let count = self
+                .get_transactions_by_item(
+                    item.clone(),
+                    vec![payload.asset_info.clone()].into_iter().collect(),
+                    None,
+                    Some(block_range.clone()),
+                    TransactionFilter::default(),
+                    PaginationRequest::new(None, Order::Asc, Some(1), None, true),
+                )
+                .await?
+                .count
+                .unwrap_or_default();
+            if count <= MAX_STATEMENT_ENTRY_NUM as u64 {
+                break;
+            }
+            block_range.to = block_range.from + (block_range.to - block_range.from) / 2;
+        }
+        Ok(block_range)
+    }
+
+    async fn get_statement_transactions(
+        &self,
+        item: Item,
+        payload: &GetAccountStatementPayload,
+        block_range: Range,
+    ) -> InnerResult<Vec<TransactionWrapper>> {
+        if block_range.from > block_range.to {
+            return Ok(vec![]);
+        }
+
+        let mut pagination =
+            PaginationRequest::new(None, Order::Asc, Some(STATEMENT_PAGE_SIZE), None, false);
+        let mut txs = vec![];
+        loop {
+            let page = self
+                .get_transactions_by_item(
+                    item.clone(),
+                    vec![payload.asset_info.clone()].into_iter().collect(),
+                    None,
+                    Some(block_range.clone()),
+                    TransactionFilter::default(),
+                    pagination.clone(),
+                )
+                .await?;
+            txs.extend(page.response);
+            if page.next_cursor.is_none() {
+                break;
+            }
+            pagination.update_by_response(page.next_cursor);
+        }
+
+        txs.sort_by_key(tx_position);
+        Ok(txs)
+    }
+}
+
+/// The block number and the index in the block of a committed transaction, which is
+/// taken from the input cells if the transaction has no output cell.
+fn tx_position(tx_wrapper: &TransactionWrapper) -> (BlockNumber, u32) {
+    if let Some(cell) = tx_wrapper.output_cells.first() {
+        return (cell.block_number, cell.tx_index);
+    }
+    tx_wrapper
+        .input_cells
+        .first()
+        .and_then(|cell| cell.consumed_block_number.zip(cell.consumed_tx_index))
+        .unwrap_or_default()
+}
+
+/// Move the entries into the export text if the statement is required as CSV or JSON Lines.
+fn export_statement(
+    mut statement: AccountStatement,
+    format: Option<StatementFormat>,
+) -> AccountStatement {
+    let export = match format.unwrap_or(StatementFormat::Json) {
+        StatementFormat::Json => return statement,
+        StatementFormat::Csv => {
+            let mut export =
+                "tx_hash,block_number,timestamp,received,sent,delta,fee,balance\n".to_string();
+            for entry in statement.entries.iter() {
+                let received: u128 = entry.received.into();
+                let sent: u128 = entry.sent.into();
+                let delta = if received >= sent {
+                    (received - sent).to_string()
+                } else {
+                    format!("-{}", sent - received)
+                };
+                export.push_str(&format!(
+                    "{:#x},{},{},{},{},{},{},{}\n",
+                    entry.tx_hash,
+                    u64::from(entry.block_number),
+                    u64::from(entry.timestamp),
+                    received,
+                    sent,
+                    delta,
+                    u64::from(entry.fee),
+                    u128::from(entry.balance),
+                ));
+            }
+            export
+        }
+        StatementFormat::JsonLines => statement
+            .entries
+            .iter()
+            .map(|entry| serde_json::to_string(entry).expect("serialize statement entry") + "\n")
+            .collect(),
+    };
+    statement.entries.clear();
+    statement.export = Some(export);
+    statement
+}
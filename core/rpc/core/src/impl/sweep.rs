@@ -0,0 +1,322 @@
+use crate::r#impl::utils::{
+    build_cell_for_output, calculate_cell_capacity, dedup_json_items, get_multisig_since,
+    get_udt_script_name, insert_owner_lock_script_deps, map_json_items,
+};
+use crate::r#impl::{address_to_script, utils_types::*};
+use crate::{error::CoreError, InnerResult, MercuryRpcImpl};
+
+use ckb_types::core::{Capacity, TransactionView};
+use ckb_types::{packed, prelude::*};
+use common::hash::blake2b_256_to_160;
+use common::utils::decode_udt_amount;
+use common::{Address, DetailedCell, ACP, PW_LOCK, SECP256K1};
+use core_ckb_client::CkbRpc;
+use core_rpc_types::consts::{MAX_ITEM_NUM, MAX_SWEEP_INPUT_NUM};
+use core_rpc_types::{AssetType, PayFee, ScriptGroup, SweepPayload, SweepTransactionResponse};
+use num_bigint::BigInt;
+use num_traits::{ToPrimitive, Zero};
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+impl<C: CkbRpc> MercuryRpcImpl<C> {
+    pub(crate) async fn inner_build_sweep_transaction(
+        &self,
+        mut payload: SweepPayload,
+    ) -> InnerResult<SweepTransactionResponse> {
+        if payload.from.is_empty() {
+            return Err(CoreError::NeedAtLeastOneFromAndOneTo.into());
+        }
+        if payload.from.len() > MAX_ITEM_NUM {
+            return Err(CoreError::ExceedMaxItemNum.into());
+        }
+        dedup_json_items(&mut payload.from);
+        Address::from_str(&payload.to).map_err(CoreError::InvalidRpcParams)?;
+        if self
+            .is_items_contain_addresses(&payload.from, &[payload.to.clone()])
+            .await?
+        {
+            return Err(CoreError::FromContainTo.into());
+        }
+
+        let partial = AtomicBool::new(false);
+        let response = self
+            .build_transaction_with_adjusted_fee(
+                |rpc, payload, fixed_fee| {
+                    rpc.prebuild_sweep_transaction(payload, fixed_fee, &partial)
+                },
+                payload.clone(),
+                payload.fee_rate.map(Into::into),
+                payload.verify.unwrap_or(false),
+            )
+            .await?;
+        Ok(SweepTransactionResponse::new(
+            response,
+            partial.load(Ordering::SeqCst),
+        ))
+    }
+
+    /// Set `partial` if the cells of the items are more than `MAX_SWEEP_INPUT_NUM`.
+    async fn prebuild_sweep_transaction(
+        &self,
+        payload: SweepPayload,
+        fixed_fee: u64,
+        partial: &AtomicBool,
+    ) -> InnerResult<(TransactionView, Vec<ScriptGroup>, usize)> {
+        match payload.asset_info.asset_type {
+            AssetType::CKB => {
+                self.prebuild_ckb_sweep_transaction(payload, fixed_fee, partial)
+                    .await
+            }
+            AssetType::UDT | AssetType::XUDT => {
+                self.prebuild_udt_sweep_transaction(payload, fixed_fee, partial)
+                    .await
+            }
+        }
+    }
+
+    /// Spend all the capacity of the items and send it to the destination after the fee.
+    /// The UDT cells with a positive amount keep their occupied capacity, while the empty
+    /// ACP and UDT cells are reclaimed.
+    async fn prebuild_ckb_sweep_transaction(
+        &self,
+        payload: SweepPayload,
+        fixed_fee: u64,
+        partial: &AtomicBool,
+    ) -> InnerResult<(TransactionView, Vec<ScriptGroup>, usize)> {
+        let mut transfer_components = TransferComponents::new();
+        transfer_components.multisig_scripts = self
+            .get_multisig_scripts(&payload.from, &payload.multisig_configs)
+            .await?;
+
+        let mut ckb_cells_cache = CkbCellsCache::new(map_json_items(payload.from)?);
+        ckb_cells_cache
+            .pagination
+            .set_limit(Some(self.pool_cache_size));
+        let mut header_dep_map: HashMap<packed::Byte32, usize> = HashMap::new();
+        let mut swept_capacity = 0i128;
+
+        partial.store(false, Ordering::SeqCst);
+        loop {
+            let next = self
+                .pool_next_live_cell_for_capacity(
+                    &mut ckb_cells_cache,
+                    i128::MAX,
+                    &transfer_components.inputs,
+                )
+                .await;
+            let (cell, asset_script_type) =
+                match pooled_cell_or_none(next, ckb_cells_cache.is_pooled_all())? {
+                    Some(next) => next,
+                    None => break,
+                };
+            if transfer_components.inputs.len() >= MAX_SWEEP_INPUT_NUM {
+                partial.store(true, Ordering::SeqCst);
+                break;
+            }
+
+            swept_capacity += if is_reclaimable_account(&cell, &asset_script_type) {
+                add_swept_input(cell, &asset_script_type, &mut transfer_components) as i128
+            } else {
+                self.add_live_cell_for_balance_capacity(
+                    cell,
+                    asset_script_type,
+                    i128::MAX,
+                    &mut transfer_components,
+                    &mut header_dep_map,
+                )
+                .await
+            };
+        }
+
+        let to_address = Address::from_str(&payload.to).map_err(CoreError::InvalidRpcParams)?;
+        let to_lock = address_to_script(to_address.payload());
+        let min_capacity = calculate_cell_capacity(
+            &to_lock,
+            &packed::ScriptOpt::default(),
+            Capacity::bytes(0).expect("generate capacity"),
+        );
+        let required_capacity = (min_capacity + fixed_fee) as i128;
+        if swept_capacity < required_capacity {
+            return Err(CoreError::CkbIsNotEnough(format!(
+                "shortage: {}, items: {:?}",
+                required_capacity - swept_capacity,
+                ckb_cells_cache.items
+            ))
+            .into());
+        }
+
+        let output_index = build_cell_for_output(
+            u64::try_from(swept_capacity - fixed_fee as i128).map_err(|_| CoreError::Overflow)?,
+            to_lock,
+            None,
+            None,
+            &mut transfer_components.outputs,
+            &mut transfer_components.outputs_data,
+        )?;
+        self.complete_prebuild_transaction(transfer_components, payload.since)
+            .map(|(tx_view, script_groups)| (tx_view, script_groups, output_index))
+    }
+
+    /// Spend all the cells of the UDT owned by the items, including the cheque cells
+    /// which can be claimed or withdrawn, and send the whole amount to the destination.
+    /// The capacity of the consumed cells goes back to the items as the change, which
+    /// also pays the fee.
+    async fn prebuild_udt_sweep_transaction(
+        &self,
+        payload: SweepPayload,
+        fixed_fee: u64,
+        partial: &AtomicBool,
+    ) -> InnerResult<(TransactionView, Vec<ScriptGroup>, usize)> {
+        let mut transfer_components = TransferComponents::new();
+        transfer_components.multisig_scripts = self
+            .get_multisig_scripts(&payload.from, &payload.multisig_configs)
+            .await?;
+
+        let from_items = map_json_items(payload.from)?;
+        let mut udt_cells_cache =
+            UdtCellsCache::new(from_items.clone(), payload.asset_info.clone());
+        udt_cells_cache
+            .pagination
+            .set_limit(Some(self.pool_cache_size));
+        let mut swept_amount = 0u128;
+
+        partial.store(false, Ordering::SeqCst);
+        loop {
+            let next = self
+                .pool_next_live_cell_for_udt(
+                    &mut udt_cells_cache,
+                    BigInt::from(u128::MAX),
+                    &transfer_components.inputs,
+                )
+                .await;
+            let (cell, asset_script_type) =
+                match pooled_cell_or_none(next, udt_cells_cache.is_pooled_all())? {
+                    Some(next) => next,
+                    None => break,
+                };
+            if transfer_components.inputs.len() >= MAX_SWEEP_INPUT_NUM {
+                partial.store(true, Ordering::SeqCst);
+                break;
+            }
+
+            let amount = if let AssetScriptType::Cheque(_) = asset_script_type {
+                self.add_live_cell_for_balance_udt(
+                    cell,
+                    asset_script_type,
+                    BigInt::from(u128::MAX),
+                    &mut transfer_components,
+                )
+                .await?
+                .to_u128()
+                .ok_or(CoreError::Overflow)?
+            } else {
+                let amount = decode_udt_amount(&cell.cell_data).unwrap_or(0);
+                add_swept_input(cell, &asset_script_type, &mut transfer_components);
+                amount
+            };
+            swept_amount = swept_amount
+                .checked_add(amount)
+                .ok_or(CoreError::Overflow)?;
+        }
+
+        if swept_amount.is_zero() {
+            return Err(
+                CoreError::UDTIsNotEnough(format!("no {} to sweep", payload.asset_info)).into(),
+            );
+        }
+
+        // build the output holding the whole amount
+        let to_address = Address::from_str(&payload.to).map_err(CoreError::InvalidRpcParams)?;
+        let to_lock = address_to_script(to_address.payload());
+        let udt_type_script = self
+            .build_sudt_type_script(blake2b_256_to_160(&payload.asset_info.udt_hash))
+            .await?;
+        let udt_type_script = Some(udt_type_script).pack();
+        let capacity = calculate_cell_capacity(
+            &to_lock,
+            &udt_type_script,
+            Capacity::bytes(16).expect("generate capacity"),
+        );
+        let output_index = build_cell_for_output(
+            capacity,
+            to_lock,
+            udt_type_script.to_opt(),
+            Some(swept_amount),
+            &mut transfer_components.outputs,
+            &mut transfer_components.outputs_data,
+        )?;
+        transfer_components
+            .script_deps
+            .insert(get_udt_script_name(&transfer_components.outputs[output_index]).to_string());
+
+        // balance capacity
+        self.prebuild_capacity_balance_tx(
+            from_items,
+            vec![payload.to],
+            payload.since,
+            Some(PayFee::From),
+            fixed_fee,
+            transfer_components,
+        )
+        .await
+    }
+}
+
+/// The pooled cell, or `None` if the pooler fails because all the cells of the items
+/// have been pooled.
+pub(crate) fn pooled_cell_or_none<T>(
+    pooled: InnerResult<T>,
+    is_pooled_all: bool,
+) -> InnerResult<Option<T>> {
+    match pooled {
+        Ok(next) => Ok(Some(next)),
+        Err(_) if is_pooled_all => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// An ACP or pw lock cell holding none of its UDT can be destroyed by the owner.
+fn is_reclaimable_account(cell: &DetailedCell, asset_script_type: &AssetScriptType) -> bool {
+    matches!(
+        asset_script_type,
+        AssetScriptType::ACP | AssetScriptType::PwLock
+    ) && cell.cell_output.type_().is_some()
+        && decode_udt_amount(&cell.cell_data).unwrap_or(0).is_zero()
+}
+
+/// Consume the whole cell without rebuilding it in the outputs, and return its capacity.
+pub(crate) fn add_swept_input(
+    cell: DetailedCell,
+    asset_script_type: &AssetScriptType,
+    transfer_components: &mut TransferComponents,
+) -> u64 {
+    match asset_script_type {
+        AssetScriptType::ACP => {
+            transfer_components.script_deps.insert(ACP.to_string());
+        }
+        AssetScriptType::PwLock => {
+            transfer_components
+                .script_deps
+                .insert(SECP256K1.to_string());
+            transfer_components.script_deps.insert(PW_LOCK.to_string());
+        }
+        _ => insert_owner_lock_script_deps(asset_script_type, transfer_components),
+    }
+    if cell.cell_output.type_().is_some() {
+        transfer_components
+            .script_deps
+            .insert(get_udt_script_name(&cell.cell_output).to_string());
+    }
+    if let Some(since) = get_multisig_since(&cell.cell_output.lock()) {
+        transfer_components
+            .since_map
+            .insert(transfer_components.inputs.len(), since);
+    }
+
+    let capacity: u64 = cell.cell_output.capacity().unpack();
+    transfer_components.inputs.push(cell);
+    capacity
+}
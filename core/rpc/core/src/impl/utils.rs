@@ -2,37 +2,56 @@ use crate::r#impl::{address_to_script, utils_types::*};
 use crate::{error::CoreError, InnerResult, MercuryRpcImpl};
 
 use ckb_dao_utils::extract_dao_data;
-use ckb_types::core::{BlockNumber, Capacity, EpochNumberWithFraction, RationalU256};
+use ckb_jsonrpc_types::TransactionView as JsonTransactionView;
+use ckb_types::core::{
+    BlockNumber, Capacity, EpochNumberWithFraction, RationalU256, ScriptHashType,
+};
 use ckb_types::{bytes::Bytes, packed, prelude::*, H160, H256, U256};
-use common::address::{is_acp, is_pw_lock, is_secp256k1};
+use common::address::{is_acp, is_multisig, is_omni_lock, is_pw_lock, is_secp256k1};
 use common::hash::{blake2b_160, blake2b_256_to_160};
 use common::lazy::{
-    ACP_CODE_HASH, CHEQUE_CODE_HASH, DAO_CODE_HASH, PW_LOCK_CODE_HASH, SECP256K1_CODE_HASH,
-    SUDT_CODE_HASH,
+    ACP_CODE_HASH, CHEQUE_CODE_HASH, DAO_CODE_HASH, OMNI_LOCK_CODE_HASH, PW_LOCK_CODE_HASH,
+    SECP256K1_CODE_HASH, SUDT_CODE_HASH, XUDT_CODE_HASH,
 };
 use common::utils::{decode_dao_block_number, decode_udt_amount, encode_udt_amount, u256_low_u64};
 use common::{
     Address, AddressPayload, DetailedCell, PaginationRequest, PaginationResponse, Range, ACP,
-    CHEQUE, DAO, PW_LOCK, SECP256K1, SUDT,
+    CHEQUE, DAO, MULTISIG, MULTISIG_TYPE_HASH, OMNI_LOCK, PW_LOCK, SECP256K1, SUDT, XUDT,
 };
 use core_ckb_client::CkbRpc;
 use core_rpc_types::consts::{
-    MIN_CKB_CAPACITY, MIN_DAO_LOCK_PERIOD, STANDARD_SUDT_CAPACITY,
-    WITHDRAWING_DAO_CELL_OCCUPIED_CAPACITY,
+    MIN_CKB_CAPACITY, MIN_DAO_LOCK_PERIOD, WITHDRAWING_DAO_CELL_OCCUPIED_CAPACITY,
 };
-use core_rpc_types::lazy::{CURRENT_EPOCH_NUMBER, TX_POOL_CACHE};
+use core_rpc_types::lazy::{CURRENT_EPOCH_NUMBER, INPUT_RESERVATIONS, TX_POOL_CACHE};
 use core_rpc_types::{lazy::CURRENT_BLOCK_NUMBER, DaoInfo};
 use core_rpc_types::{
-    AssetInfo, AssetType, Balance, DaoState, ExtraFilter, ExtraType, IOType, Identity,
-    IdentityFlag, Item, JsonItem, Record, SinceConfig, SinceFlag, SinceType,
+    AssetInfo, AssetType, Balance, BalanceBreakdown, DaoState, ExtraFilter, ExtraType, IOType,
+    Identity, IdentityFlag, Item, JsonItem, MultisigConfig, Record, SinceConfig, SinceFlag,
+    SinceType,
 };
-use core_storage::{Storage, TransactionWrapper};
+use core_storage::{Storage, TransactionFilter, TransactionWrapper};
 use num_bigint::{BigInt, BigUint};
 use num_traits::{ToPrimitive, Zero};
 
 use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::convert::TryInto;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+tokio::task_local! {
+    /// The id of the transaction being built, which reserves the cells taken by the
+    /// poolers until the transaction is built.
+    pub(crate) static BUILD_ID: H256;
+}
+
+static BUILD_COUNT: AtomicU64 = AtomicU64::new(0);
+
+/// A build id, which is not the hash of any transaction in practice.
+pub(crate) fn new_build_id() -> H256 {
+    let mut id = [0u8; 32];
+    id[24..].copy_from_slice(&BUILD_COUNT.fetch_add(1, Ordering::SeqCst).to_be_bytes());
+    H256(id)
+}
 
 impl<C: CkbRpc> MercuryRpcImpl<C> {
     pub(crate) fn get_script_builder(
@@ -122,29 +141,66 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                     scripts.push(pw_lock_script);
                 }
             }
+            IdentityFlag::Tron | IdentityFlag::Bitcoin | IdentityFlag::Dogecoin => {
+                if OMNI_LOCK_CODE_HASH.get().is_none() {
+                    return Err(CoreError::UnsupportIdentityFlag.into());
+                }
+            }
             _ => {
                 return Err(CoreError::UnsupportIdentityFlag.into());
             }
         }
 
+        if let Some(omni_lock_code_hash) = OMNI_LOCK_CODE_HASH.get() {
+            if lock_filter.is_none() || lock_filter == Some(omni_lock_code_hash) {
+                // the auth part of omni lock args shares the layout of identity
+                let mut omni_lock_scripts = self
+                    .storage
+                    .get_scripts_by_partial_arg(
+                        omni_lock_code_hash,
+                        Bytes::from(ident.0.to_vec()),
+                        (0, 21),
+                    )
+                    .await
+                    .map_err(|e| CoreError::DBError(e.to_string()))?;
+                scripts.append(&mut omni_lock_scripts);
+            }
+        }
+
         Ok(scripts)
     }
 
-    pub(crate) fn get_scripts_by_address(
+    pub(crate) async fn get_scripts_by_address(
         &self,
         addr: &Address,
         lock_filter: Option<&H256>,
-    ) -> Vec<packed::Script> {
+    ) -> InnerResult<Vec<packed::Script>> {
         let mut ret = Vec::new();
         let script = address_to_script(addr.payload());
         if let Some(lock_filter) = lock_filter {
             let lock_hash: H256 = script.code_hash().unpack();
             if lock_hash != *lock_filter {
-                return vec![];
+                return Ok(vec![]);
+            }
+        }
+
+        // a multisig address also owns the cells locked by its since-locked variants
+        let args = script.args().raw_data();
+        if is_multisig(addr) && args.len() == 20 {
+            let mut multisig_scripts = self
+                .storage
+                .get_scripts_by_partial_arg(&MULTISIG_TYPE_HASH, args, (0, 20))
+                .await
+                .map_err(|e| CoreError::DBError(e.to_string()))?;
+            if !multisig_scripts.contains(&script) {
+                ret.push(script);
             }
+            ret.append(&mut multisig_scripts);
+            return Ok(ret);
         }
+
         ret.push(script);
-        ret
+        Ok(ret)
     }
 
     pub(crate) async fn get_live_cells_by_item(
@@ -157,6 +213,29 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         extra: Option<ExtraType>,
         pagination: &mut PaginationRequest,
     ) -> InnerResult<Vec<DetailedCell>> {
+        let cell_page = self
+            .get_live_cells_page_by_item(
+                item,
+                asset_infos,
+                tip_block_number,
+                lock_filter,
+                extra,
+                pagination.clone(),
+            )
+            .await?;
+        pagination.update_by_response(cell_page.next_cursor);
+        Ok(cell_page.response)
+    }
+
+    pub(crate) async fn get_live_cells_page_by_item(
+        &self,
+        item: Item,
+        asset_infos: HashSet<AssetInfo>,
+        tip_block_number: Option<BlockNumber>,
+        lock_filter: Option<&H256>,
+        extra: Option<ExtraType>,
+        pagination: PaginationRequest,
+    ) -> InnerResult<PaginationResponse<DetailedCell>> {
         let type_hashes = self.get_type_hashes(asset_infos, extra.clone());
         let (scripts, out_point) = match item {
             Item::Identity(ident) => (
@@ -165,7 +244,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             ),
             Item::Address(addr) => {
                 let addr = Address::from_str(&addr).map_err(CoreError::ParseAddressError)?;
-                (self.get_scripts_by_address(&addr, lock_filter), None)
+                (self.get_scripts_by_address(&addr, lock_filter).await?, None)
             }
             Item::OutPoint(out_point) => {
                 let addr = self
@@ -173,7 +252,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                     .await
                     .map(|script| self.script_to_address(&script))?;
                 (
-                    self.get_scripts_by_address(&addr, lock_filter),
+                    self.get_scripts_by_address(&addr, lock_filter).await?,
                     Some(out_point.into()),
                 )
             }
@@ -183,30 +262,24 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             .map(|script| script.calc_script_hash().unpack())
             .collect::<Vec<H256>>();
         if lock_hashes.is_empty() {
-            pagination.cursor = None;
-            return Ok(vec![]);
+            return Ok(PaginationResponse::default());
         }
-        let cell_page = self
+        let mut cell_page = self
             .get_live_cells(
                 out_point,
                 lock_hashes,
                 type_hashes,
                 tip_block_number,
                 None,
-                pagination.clone(),
+                pagination,
             )
             .await
             .map_err(|e| CoreError::DBError(e.to_string()))?;
-        pagination.update_by_response(cell_page.next_cursor.map(Into::into));
 
-        let mut cells = cell_page.response;
         if extra == Some(ExtraType::Cellbase) {
-            cells = cells
-                .into_iter()
-                .filter(|cell| cell.tx_index == 0)
-                .collect();
+            cell_page.response.retain(|cell| cell.tx_index == 0);
         }
-        Ok(cells)
+        Ok(cell_page)
     }
 
     async fn get_live_cells(
@@ -247,6 +320,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         asset_infos: HashSet<AssetInfo>,
         extra: Option<ExtraType>,
         range: Option<Range>,
+        filter: TransactionFilter,
         pagination: PaginationRequest,
     ) -> InnerResult<PaginationResponse<TransactionWrapper>> {
         let limit_cellbase = extra == Some(ExtraType::Cellbase);
@@ -265,6 +339,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                         type_hashes,
                         range,
                         limit_cellbase,
+                        filter.clone(),
                         pagination,
                     )
                     .await
@@ -273,7 +348,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
 
             Item::Address(address) => {
                 let address = Address::from_str(&address).map_err(CoreError::ParseAddressError)?;
-                let scripts = self.get_scripts_by_address(&address, None);
+                let scripts = self.get_scripts_by_address(&address, None).await?;
                 let lock_hashes = scripts
                     .iter()
                     .map(|script| script.calc_script_hash().unpack())
@@ -284,12 +359,20 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                         type_hashes,
                         range,
                         limit_cellbase,
+                        filter.clone(),
                         pagination,
                     )
                     .await
                     .map_err(|e| CoreError::DBError(e.to_string()))?
             }
 
+            Item::OutPoint(_) if !filter.is_empty() => {
+                return Err(CoreError::InvalidRpcParams(
+                    "the transaction filters are not supported for an out point".to_string(),
+                )
+                .into())
+            }
+
             Item::OutPoint(out_point) => self
                 .storage
                 .get_transactions(
@@ -327,6 +410,9 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                 match flag {
                     IdentityFlag::Ckb => Ok(self.get_builtin_script(SECP256K1, pubkey_hash)),
                     IdentityFlag::Ethereum => Ok(self.get_builtin_script(PW_LOCK, pubkey_hash)),
+                    IdentityFlag::Tron | IdentityFlag::Bitcoin | IdentityFlag::Dogecoin => {
+                        self.get_omni_lock_by_auth(&ident.0)
+                    }
                     _ => Err(CoreError::UnsupportIdentityFlag.into()),
                 }
             }
@@ -357,11 +443,60 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         } else if self.is_script(&script, PW_LOCK)? {
             let args = H160::from_slice(&lock_args[0..20]).expect("Impossible: parse args");
             Ok(self.get_builtin_script(PW_LOCK, args))
+        } else if is_omni_lock_script(&script) && lock_args.len() >= 21 {
+            self.get_omni_lock_by_auth(&lock_args[0..21])
+        } else if is_multisig(&self.script_to_address(&script)) && lock_args.len() >= 20 {
+            // the since-locked multisig cells belong to the multisig lock without since
+            Ok(script
+                .as_builder()
+                .args(lock_args.slice(0..20).pack())
+                .build())
         } else {
             Err(CoreError::UnsupportAddress.into())
         }
     }
 
+    /// Build an omni lock script without any omni lock flags, which is the default
+    /// owner lock of the given 21 bytes auth.
+    fn get_omni_lock_by_auth(&self, auth: &[u8]) -> InnerResult<packed::Script> {
+        let mut args = auth.to_vec();
+        args.push(0u8);
+        Ok(self
+            .get_script_builder(OMNI_LOCK)?
+            .args(args.pack())
+            .build())
+    }
+
+    /// Map the multisig scripts of the given configs by their lock args, and make sure every
+    /// multisig item has its config, which is required to size the witness placeholder.
+    pub(crate) async fn get_multisig_scripts(
+        &self,
+        items: &[JsonItem],
+        multisig_configs: &Option<Vec<MultisigConfig>>,
+    ) -> InnerResult<HashMap<H160, Bytes>> {
+        let mut multisig_scripts = HashMap::new();
+        for config in multisig_configs.iter().flatten() {
+            let multisig_script = build_multisig_script(config)?;
+            multisig_scripts.insert(H160(blake2b_160(&multisig_script)), multisig_script);
+        }
+
+        for json_item in items {
+            let item = Item::try_from(json_item.to_owned())?;
+            if let Ok(lock) = self.get_default_owner_lock_by_item(&item).await {
+                if !is_multisig(&self.script_to_address(&lock)) {
+                    continue;
+                }
+                let args = H160::from_slice(&lock.args().raw_data()[0..20])
+                    .expect("Impossible: parse args");
+                if !multisig_scripts.contains_key(&args) {
+                    return Err(CoreError::MissingMultisigConfig(format!("{:#x}", args)).into());
+                }
+            }
+        }
+
+        Ok(multisig_scripts)
+    }
+
     async fn get_lock_by_out_point(
         &self,
         out_point: packed::OutPoint,
@@ -481,11 +616,41 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         }
     }
 
-    fn is_in_cache(&self, cell: &packed::OutPoint) -> bool {
+    pub(crate) fn is_in_cache(&self, cell: &packed::OutPoint) -> bool {
         let cache = TX_POOL_CACHE.read();
         cache.contains(cell)
     }
 
+    /// Take the cell for the build in scope, which fails if the cell is reserved by
+    /// another transaction or build. The cell is reserved by the build at once, so
+    /// that the concurrent builds do not take it.
+    pub(crate) fn take_unreserved(&self, cell: &packed::OutPoint) -> bool {
+        match BUILD_ID.try_with(Clone::clone) {
+            Ok(build_id) if !self.input_reservation_ttl.is_zero() => INPUT_RESERVATIONS
+                .write()
+                .try_reserve(&build_id, cell.clone(), self.input_reservation_ttl),
+            _ => !INPUT_RESERVATIONS.read().is_reserved(cell),
+        }
+    }
+
+    /// Reserve the inputs of a built transaction, so that they are not selected
+    /// by other build requests before the reservation expires.
+    pub(crate) fn reserve_inputs(&self, tx_view: &JsonTransactionView) {
+        if self.input_reservation_ttl.is_zero() {
+            return;
+        }
+
+        INPUT_RESERVATIONS.write().reserve(
+            tx_view.hash.clone(),
+            tx_view
+                .inner
+                .inputs
+                .iter()
+                .map(|input| input.previous_output.clone().into()),
+            self.input_reservation_ttl,
+        );
+    }
+
     #[allow(clippy::unnecessary_unwrap)]
     pub(crate) async fn to_record(
         &self,
@@ -500,11 +665,8 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         let block_number = cell.block_number;
         let epoch_number = cell.epoch_number;
         let udt_record = if let Some(type_script) = cell.cell_output.type_().to_opt() {
-            let type_code_hash: H256 = type_script.code_hash().unpack();
-
-            if type_code_hash == *SUDT_CODE_HASH.get().expect("get sudt code hash") {
+            if let Some(asset_info) = udt_asset_info(&type_script) {
                 let out_point = cell.out_point.to_owned().into();
-                let asset_info = AssetInfo::new_udt(type_script.calc_script_hash().unpack());
                 let amount = self.generate_udt_amount(cell);
                 let extra = None;
 
@@ -518,6 +680,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                     extra,
                     block_number: block_number.into(),
                     epoch_number: epoch_number.into(),
+                    label: None,
                 })
             } else {
                 None
@@ -552,15 +715,19 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             && cell.cell_output.type_().is_none()
             && (Some(&lock_code_hash) == SECP256K1_CODE_HASH.get()
                 || Some(&lock_code_hash) == ACP_CODE_HASH.get()
-                || Some(&lock_code_hash) == PW_LOCK_CODE_HASH.get())
+                || Some(&lock_code_hash) == PW_LOCK_CODE_HASH.get()
+                || is_omni_lock_script(&cell.cell_output.lock())
+                || lock_code_hash == MULTISIG_TYPE_HASH)
         {
             occupied = 0;
         }
         if let Some(type_script) = cell.cell_output.type_().to_opt() {
             let type_code_hash: H256 = type_script.code_hash().unpack();
-            // a secp sUDT cell with 0 udt amount should be spendable.
-            if Some(&type_code_hash) == SUDT_CODE_HASH.get()
-                && Some(&lock_code_hash) == SECP256K1_CODE_HASH.get()
+            // a secp/omni lock/multisig UDT cell with 0 udt amount should be spendable.
+            if is_udt_code_hash(&type_code_hash)
+                && (Some(&lock_code_hash) == SECP256K1_CODE_HASH.get()
+                    || is_omni_lock_script(&cell.cell_output.lock())
+                    || lock_code_hash == MULTISIG_TYPE_HASH)
                 && self.generate_udt_amount(cell).is_zero()
             {
                 occupied = 0;
@@ -598,6 +765,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             extra,
             block_number: block_number.into(),
             epoch_number: epoch_number.into(),
+            label: None,
         };
         records.push(ckb_record);
 
@@ -688,6 +856,11 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             return Ok(Some(ExtraFilter::Cellbase));
         }
 
+        // A since-locked multisig cell can not be spent until its since is satisfied.
+        if io_type == IOType::Output && !is_multisig_cell_unlocked(cell) {
+            return Ok(Some(ExtraFilter::Frozen));
+        }
+
         if let Some(type_script) = cell.cell_output.type_().to_opt() {
             let type_code_hash: H256 = type_script.code_hash().unpack();
 
@@ -741,26 +914,31 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             }
 
             let lock_code_hash: H256 = cell.cell_output.lock().code_hash().unpack();
-            // If the cell is sUDT acp cell, as Mercury can collect CKB by it, so its ckb amount minus 'occupied' is spendable.
-            if Some(&type_code_hash) == SUDT_CODE_HASH.get()
-                && Some(&lock_code_hash) == ACP_CODE_HASH.get()
-            {
+            // If the cell is UDT acp cell, as Mercury can collect CKB by it, so its ckb amount minus 'occupied' is spendable.
+            if is_udt_code_hash(&type_code_hash) && Some(&lock_code_hash) == ACP_CODE_HASH.get() {
                 return Ok(None);
             }
-            // If the cell is sUDT sepc cell, as Mercury can collect CKB by it, so its ckb amount minus 'occupied' is spendable.
-            if Some(&type_code_hash) == SUDT_CODE_HASH.get()
+            // If the cell is UDT sepc cell, as Mercury can collect CKB by it, so its ckb amount minus 'occupied' is spendable.
+            if is_udt_code_hash(&type_code_hash)
                 && Some(&lock_code_hash) == SECP256K1_CODE_HASH.get()
             {
                 return Ok(None);
             }
-            // If the cell is sUDT pw-lock cell, as Mercury can collect CKB by it, so its ckb amount minus 'occupied' is spendable.
-            if Some(&type_code_hash) == SUDT_CODE_HASH.get()
-                && Some(&lock_code_hash) == PW_LOCK_CODE_HASH.get()
+            // If the cell is UDT pw-lock cell, as Mercury can collect CKB by it, so its ckb amount minus 'occupied' is spendable.
+            if is_udt_code_hash(&type_code_hash) && Some(&lock_code_hash) == PW_LOCK_CODE_HASH.get()
             {
                 return Ok(None);
             }
+            // If the cell is UDT omni lock cell, as Mercury can collect CKB by it, so its ckb amount minus 'occupied' is spendable.
+            if is_udt_code_hash(&type_code_hash) && is_omni_lock_script(&cell.cell_output.lock()) {
+                return Ok(None);
+            }
+            // If the cell is UDT multisig cell, as Mercury can collect CKB by it, so its ckb amount minus 'occupied' is spendable.
+            if is_udt_code_hash(&type_code_hash) && lock_code_hash == MULTISIG_TYPE_HASH {
+                return Ok(None);
+            }
 
-            // Except sUDT acp cell, sUDT secp and sUDT pw lock cell, cells with type setting can not spend its CKB.
+            // Except UDT acp cell, UDT secp, UDT pw lock, UDT omni lock and UDT multisig cell, cells with type setting can not spend its CKB.
             return Ok(Some(ExtraFilter::Frozen));
         } else if !cell.cell_data.is_empty() {
             // If cell data is not empty but type is empty which often used for storing contract binary,
@@ -821,11 +999,16 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
     /// `occupied`: the capacity consumed for storage, except pure CKB cell (cell_data and type are both empty). Pure CKB cell's `occupied` is zero.
     /// `frozen`: any cell which data or type is not empty, then its amount minus `occupied` is `frozen`. Except sUDT acp cell, sUDT secp cell and sUDT pw lock cell which can be used to collect CKB in Mercury.
     /// `free`: amount minus `occupied` and `frozen`.
+    ///
+    /// The `free` amount of an unconfirmed cell is accumulated to `unconfirmed` instead.
     pub(crate) async fn accumulate_balance_from_records(
         &self,
         balances_map: &mut BTreeMap<(String, AssetInfo), Balance>,
         records: Vec<Record>,
         tip_epoch_number: Option<RationalU256>,
+        cell: &DetailedCell,
+        detailed: bool,
+        confirmed: bool,
     ) -> InnerResult<()> {
         for record in records {
             let key = (record.ownership, record.asset_info);
@@ -884,14 +1067,57 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
 
             let free = amount - occupied - frozen;
 
-            let accumulate_free: u128 = free + balance.free.value();
             let accumulate_occupied: u128 = occupied + balance.occupied.value();
             let accumulate_frozen: u128 = frozen + balance.frozen.value();
 
-            balance.free = accumulate_free.into();
+            if confirmed {
+                let accumulate_free: u128 = free + balance.free.value();
+                balance.free = accumulate_free.into();
+            } else {
+                let accumulate_unconfirmed: u128 = free + balance.unconfirmed.value();
+                balance.unconfirmed = accumulate_unconfirmed.into();
+            }
             balance.occupied = accumulate_occupied.into();
             balance.frozen = accumulate_frozen.into();
 
+            if detailed {
+                let breakdown = balance
+                    .breakdown
+                    .get_or_insert_with(BalanceBreakdown::default);
+                if !frozen.is_zero() {
+                    let reason = match &record.extra {
+                        Some(ExtraFilter::Dao(dao_info)) => match dao_info.state {
+                            DaoState::Deposit(_) => &mut breakdown.dao_deposited,
+                            DaoState::Withdraw(_, _) => &mut breakdown.dao_withdrawing_locked,
+                        },
+                        Some(ExtraFilter::Cellbase) => &mut breakdown.immature_cellbase,
+                        _ if self.is_script(&cell.cell_output.lock(), CHEQUE)? => {
+                            &mut breakdown.pending_cheque
+                        }
+                        _ if !is_multisig_cell_unlocked(cell) => &mut breakdown.since_locked,
+                        _ => &mut breakdown.unknown_type,
+                    };
+                    *reason = (reason.value() + frozen).into();
+                }
+                if !occupied.is_zero() {
+                    let reason = if self.is_script(&cell.cell_output.lock(), ACP)? {
+                        &mut breakdown.acp_occupied
+                    } else if cell
+                        .cell_output
+                        .type_()
+                        .to_opt()
+                        .map_or(false, |type_script| {
+                            is_udt_code_hash(&type_script.code_hash().unpack())
+                        })
+                    {
+                        &mut breakdown.udt_occupied
+                    } else {
+                        &mut breakdown.other_occupied
+                    };
+                    *reason = (reason.value() + occupied).into();
+                }
+            }
+
             balances_map.insert(key, balance);
         }
 
@@ -1033,7 +1259,24 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         Address::new(self.network_type, payload, true)
     }
 
-    fn is_cellbase_mature(&self, cell: &DetailedCell) -> bool {
+    /// Labels are stored by the addresses in the form used as the ownership of the records
+    /// and the identities in lowercase hex.
+    pub(crate) fn to_label_item(&self, item: JsonItem) -> InnerResult<JsonItem> {
+        match Item::try_from(item)? {
+            Item::Identity(identity) => Ok(JsonItem::Identity(identity.encode())),
+            Item::Address(address) => {
+                let address = Address::from_str(&address).map_err(CoreError::ParseAddressError)?;
+                let lock = address_to_script(address.payload());
+                Ok(JsonItem::Address(self.script_to_address(&lock).to_string()))
+            }
+            Item::OutPoint(_) => Err(CoreError::InvalidRpcParams(
+                "an out point can not be labeled".to_string(),
+            )
+            .into()),
+        }
+    }
+
+    pub(crate) fn is_cellbase_mature(&self, cell: &DetailedCell) -> bool {
         (**CURRENT_EPOCH_NUMBER.load()).clone().saturating_sub(
             EpochNumberWithFraction::from_full_value(cell.epoch_number).to_rational(),
         ) > self.cellbase_maturity
@@ -1080,6 +1323,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         ckb_cells_cache
             .pagination
             .set_limit(Some(self.pool_cache_size));
+        ckb_cells_cache.coin_selection = transfer_components.coin_selection;
 
         let required_capacity = self
             .pool_inputs_for_capacity(
@@ -1256,6 +1500,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         // change acp cell from db
         let mut cells_cache = AcpCellsCache::new(from_items.to_owned(), None);
         cells_cache.pagination.set_limit(Some(self.pool_cache_size));
+        cells_cache.coin_selection = transfer_components.coin_selection;
         let ret = self
             .pool_next_live_acp_cell(&mut cells_cache, &transfer_components.inputs)
             .await;
@@ -1412,6 +1657,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         udt_cells_cache
             .pagination
             .set_limit(Some(self.pool_cache_size));
+        udt_cells_cache.coin_selection = transfer_components.coin_selection;
 
         loop {
             if required_udt_amount <= zero {
@@ -1454,6 +1700,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                     Some(asset_info.clone()),
                 );
                 cells_cache.pagination.set_limit(Some(self.pool_cache_size));
+                cells_cache.coin_selection = transfer_components.coin_selection;
                 let ret = self
                     .pool_next_live_acp_cell(&mut cells_cache, &transfer_components.inputs)
                     .await;
@@ -1482,9 +1729,16 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                 let secp_address = self
                     .get_secp_address_by_item(&Item::Address(receiver_address))
                     .await?;
+                let secp_lock: packed::Script = secp_address.payload().into();
+                let capacity = calculate_cell_capacity(
+                    &secp_lock,
+                    &Some(type_script.clone()).pack(),
+                    Capacity::bytes(Bytes::from(encode_udt_amount(change_udt_amount)).len())
+                        .expect("generate capacity"),
+                );
                 build_cell_for_output(
-                    STANDARD_SUDT_CAPACITY,
-                    secp_address.payload().into(),
+                    capacity,
+                    secp_lock,
                     Some(type_script),
                     Some(change_udt_amount),
                     &mut transfer_components.outputs,
@@ -1504,9 +1758,10 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         used_input: &[DetailedCell],
     ) -> InnerResult<(DetailedCell, AssetScriptType)> {
         loop {
-            if let Some((cell, asset_script_type)) = ckb_cells_cache.cell_deque.pop_front() {
+            if let Some((cell, asset_script_type)) = ckb_cells_cache.pop_cell(required_capacity) {
                 if self.is_in_cache(&cell.out_point)
                     || used_input.iter().any(|i| i.out_point == cell.out_point)
+                    || !self.take_unreserved(&cell.out_point)
                 {
                     continue;
                 }
@@ -1611,7 +1866,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                             )
                         })
                         .collect::<VecDeque<_>>();
-                    ckb_cells_cache.cell_deque = dao_cells;
+                    ckb_cells_cache.cell_deque.extend(dao_cells);
                 }
                 PoolCkbCategory::CkbCellbase => {
                     let mut asset_ckb_set = HashSet::new();
@@ -1638,7 +1893,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                         .filter(|cell| !cell.tx_index.is_zero() && cell.cell_data.is_empty())
                         .map(|cell| (cell, AssetScriptType::Secp256k1))
                         .collect::<VecDeque<_>>();
-                    ckb_cells_cache.cell_deque = cell_base_cells;
+                    ckb_cells_cache.cell_deque.extend(cell_base_cells);
                     ckb_cells_cache.cell_deque.append(&mut normal_ckb_cells);
                 }
                 PoolCkbCategory::CkbNormalSecp => {
@@ -1662,14 +1917,14 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                         .filter(|cell| {
                             if let Some(type_script) = cell.cell_output.type_().to_opt() {
                                 let type_code_hash: H256 = type_script.code_hash().unpack();
-                                Some(&type_code_hash) == SUDT_CODE_HASH.get()
+                                is_udt_code_hash(&type_code_hash)
                             } else {
                                 false
                             }
                         })
                         .map(|cell| (cell, AssetScriptType::Secp256k1))
                         .collect::<VecDeque<_>>();
-                    ckb_cells_cache.cell_deque = secp_udt_cells;
+                    ckb_cells_cache.cell_deque.extend(secp_udt_cells);
                 }
                 PoolCkbCategory::CkbAcp => {
                     let acp_cells = self
@@ -1687,7 +1942,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                         .into_iter()
                         .map(|cell| (cell, AssetScriptType::ACP))
                         .collect::<VecDeque<_>>();
-                    ckb_cells_cache.cell_deque = acp_cells;
+                    ckb_cells_cache.cell_deque.extend(acp_cells);
                 }
                 PoolCkbCategory::PwLockEthereum => {
                     let pw_lock_cells = self
@@ -1713,7 +1968,64 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                         })
                         .map(|cell| (cell, AssetScriptType::PwLock))
                         .collect::<VecDeque<_>>();
-                    ckb_cells_cache.cell_deque = pw_lock_cells;
+                    ckb_cells_cache.cell_deque.extend(pw_lock_cells);
+                }
+                PoolCkbCategory::Omnilock => {
+                    if OMNI_LOCK_CODE_HASH.get().is_none() {
+                        ckb_cells_cache.array_index += 1;
+                        continue;
+                    }
+                    let omni_lock_cells = self
+                        .get_live_cells_by_item(
+                            ckb_cells_cache.items[item_index].clone(),
+                            HashSet::new(),
+                            None,
+                            None,
+                            OMNI_LOCK_CODE_HASH.get(),
+                            None,
+                            &mut ckb_cells_cache.pagination,
+                        )
+                        .await?;
+                    let omni_lock_cells = omni_lock_cells
+                        .into_iter()
+                        .filter(|cell| {
+                            if let Some(type_script) = cell.cell_output.type_().to_opt() {
+                                let type_code_hash: H256 = type_script.code_hash().unpack();
+                                is_udt_code_hash(&type_code_hash)
+                            } else {
+                                cell.cell_data.is_empty()
+                            }
+                        })
+                        .map(|cell| (cell, AssetScriptType::Omnilock))
+                        .collect::<VecDeque<_>>();
+                    ckb_cells_cache.cell_deque.extend(omni_lock_cells);
+                }
+                PoolCkbCategory::Multisig => {
+                    let multisig_cells = self
+                        .get_live_cells_by_item(
+                            ckb_cells_cache.items[item_index].clone(),
+                            HashSet::new(),
+                            None,
+                            None,
+                            Some(&MULTISIG_TYPE_HASH),
+                            None,
+                            &mut ckb_cells_cache.pagination,
+                        )
+                        .await?;
+                    let multisig_cells = multisig_cells
+                        .into_iter()
+                        .filter(|cell| {
+                            if let Some(type_script) = cell.cell_output.type_().to_opt() {
+                                let type_code_hash: H256 = type_script.code_hash().unpack();
+                                is_udt_code_hash(&type_code_hash)
+                            } else {
+                                cell.cell_data.is_empty()
+                            }
+                        })
+                        .filter(is_multisig_cell_unlocked)
+                        .map(|cell| (cell, AssetScriptType::Multisig))
+                        .collect::<VecDeque<_>>();
+                    ckb_cells_cache.cell_deque.extend(multisig_cells);
                 }
             }
             if ckb_cells_cache.pagination.cursor.is_none() {
@@ -1732,9 +2044,11 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         asset_udt_set.insert(udt_cells_cache.asset_info.clone());
 
         loop {
-            if let Some((cell, asset_script_type)) = udt_cells_cache.cell_deque.pop_front() {
+            if let Some((cell, asset_script_type)) = udt_cells_cache.pop_cell(&required_udt_amount)
+            {
                 if self.is_in_cache(&cell.out_point)
                     || used_inputs.iter().any(|i| i.out_point == cell.out_point)
+                    || !self.take_unreserved(&cell.out_point)
                 {
                     continue;
                 }
@@ -1770,17 +2084,13 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                         })
                         .collect::<VecDeque<_>>();
                     if !cheque_cells_unlock.is_empty() {
-                        udt_cells_cache.cell_deque = cheque_cells_unlock
-                            .into_iter()
-                            .map(|cell| {
-                                (
-                                    cell,
-                                    AssetScriptType::Cheque(
-                                        udt_cells_cache.items[item_index].clone(),
-                                    ),
-                                )
-                            })
-                            .collect::<VecDeque<_>>();
+                        let cheque_cells = cheque_cells_unlock.into_iter().map(|cell| {
+                            (
+                                cell,
+                                AssetScriptType::Cheque(udt_cells_cache.items[item_index].clone()),
+                            )
+                        });
+                        udt_cells_cache.cell_deque.extend(cheque_cells);
                     }
                 }
                 PoolUdtCategory::CkbSecpUdt => {
@@ -1799,7 +2109,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                         .into_iter()
                         .map(|cell| (cell, AssetScriptType::Secp256k1))
                         .collect::<VecDeque<_>>();
-                    udt_cells_cache.cell_deque = secp_cells;
+                    udt_cells_cache.cell_deque.extend(secp_cells);
                 }
                 PoolUdtCategory::CkbAcp => {
                     let acp_cells = self
@@ -1817,7 +2127,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                         .into_iter()
                         .map(|cell| (cell, AssetScriptType::ACP))
                         .collect::<VecDeque<_>>();
-                    udt_cells_cache.cell_deque = acp_cells;
+                    udt_cells_cache.cell_deque.extend(acp_cells);
                 }
                 PoolUdtCategory::PwLockEthereum => {
                     let pw_lock_cells = self
@@ -1835,7 +2145,48 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                         .into_iter()
                         .map(|cell| (cell, AssetScriptType::PwLock))
                         .collect::<VecDeque<_>>();
-                    udt_cells_cache.cell_deque = pw_lock_cells;
+                    udt_cells_cache.cell_deque.extend(pw_lock_cells);
+                }
+                PoolUdtCategory::Omnilock => {
+                    if OMNI_LOCK_CODE_HASH.get().is_none() {
+                        udt_cells_cache.array_index += 1;
+                        continue;
+                    }
+                    let omni_lock_cells = self
+                        .get_live_cells_by_item(
+                            udt_cells_cache.items[item_index].clone(),
+                            asset_udt_set.clone(),
+                            None,
+                            None,
+                            OMNI_LOCK_CODE_HASH.get(),
+                            None,
+                            &mut udt_cells_cache.pagination,
+                        )
+                        .await?;
+                    let omni_lock_cells = omni_lock_cells
+                        .into_iter()
+                        .map(|cell| (cell, AssetScriptType::Omnilock))
+                        .collect::<VecDeque<_>>();
+                    udt_cells_cache.cell_deque.extend(omni_lock_cells);
+                }
+                PoolUdtCategory::Multisig => {
+                    let multisig_cells = self
+                        .get_live_cells_by_item(
+                            udt_cells_cache.items[item_index].clone(),
+                            asset_udt_set.clone(),
+                            None,
+                            None,
+                            Some(&MULTISIG_TYPE_HASH),
+                            None,
+                            &mut udt_cells_cache.pagination,
+                        )
+                        .await?;
+                    let multisig_cells = multisig_cells
+                        .into_iter()
+                        .filter(is_multisig_cell_unlocked)
+                        .map(|cell| (cell, AssetScriptType::Multisig))
+                        .collect::<VecDeque<_>>();
+                    udt_cells_cache.cell_deque.extend(multisig_cells);
                 }
             }
             if udt_cells_cache.pagination.cursor.is_none() {
@@ -1850,9 +2201,10 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         used_inputs: &[DetailedCell],
     ) -> InnerResult<(DetailedCell, AssetScriptType)> {
         loop {
-            if let Some((cell, asset_script_type)) = acp_cells_cache.cell_deque.pop_front() {
+            if let Some((cell, asset_script_type)) = acp_cells_cache.pop_cell() {
                 if self.is_in_cache(&cell.out_point)
                     || used_inputs.iter().any(|i| i.out_point == cell.out_point)
+                    || !self.take_unreserved(&cell.out_point)
                 {
                     continue;
                 }
@@ -1891,7 +2243,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                         .into_iter()
                         .map(|cell| (cell, AssetScriptType::ACP))
                         .collect::<VecDeque<_>>();
-                    acp_cells_cache.cell_deque = acp_cells;
+                    acp_cells_cache.cell_deque.extend(acp_cells);
                 }
                 PoolAcpCategory::PwLockEthereum => {
                     let pw_lock_cells = self
@@ -1917,7 +2269,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                         })
                         .map(|cell| (cell, AssetScriptType::ACP))
                         .collect::<VecDeque<_>>();
-                    acp_cells_cache.cell_deque = pw_lock_cells;
+                    acp_cells_cache.cell_deque.extend(pw_lock_cells);
                 }
             }
             if acp_cells_cache.pagination.cursor.is_none() {
@@ -1935,26 +2287,31 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         header_dep_map: &mut HashMap<packed::Byte32, usize>,
     ) -> i128 {
         let provided_capacity = match asset_script_type {
-            AssetScriptType::Secp256k1 => {
+            AssetScriptType::Secp256k1 | AssetScriptType::Omnilock | AssetScriptType::Multisig => {
                 if cell.cell_output.type_().is_none() {
-                    transfer_components
-                        .script_deps
-                        .insert(SECP256K1.to_string());
+                    insert_owner_lock_script_deps(&asset_script_type, transfer_components);
                     let provided_capacity: u64 = cell.cell_output.capacity().unpack();
                     provided_capacity as i128
                 } else {
+                    let udt_script_name = get_udt_script_name(&cell.cell_output);
                     let current_udt_amount = decode_udt_amount(&cell.cell_data).unwrap_or(0);
                     if current_udt_amount.is_zero() {
+                        insert_owner_lock_script_deps(&asset_script_type, transfer_components);
                         transfer_components
                             .script_deps
-                            .insert(SECP256K1.to_string());
-                        transfer_components.script_deps.insert(SUDT.to_string());
+                            .insert(udt_script_name.to_string());
                         let provided_capacity: u64 = cell.cell_output.capacity().unpack();
                         provided_capacity as i128
                     } else {
                         let current_capacity: u64 = cell.cell_output.capacity().unpack();
-                        let max_provided_capacity =
-                            current_capacity.saturating_sub(STANDARD_SUDT_CAPACITY);
+                        let data_occupied = Capacity::bytes(cell.cell_data.len())
+                            .expect("impossible: get data occupied capacity fail");
+                        let occupied = cell
+                            .cell_output
+                            .occupied_capacity(data_occupied)
+                            .expect("impossible: get cell occupied capacity fail")
+                            .as_u64();
+                        let max_provided_capacity = current_capacity.saturating_sub(occupied);
                         let provided_capacity =
                             if required_capacity >= max_provided_capacity as i128 {
                                 max_provided_capacity as i128
@@ -1966,17 +2323,17 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                             return provided_capacity;
                         }
 
+                        insert_owner_lock_script_deps(&asset_script_type, transfer_components);
                         transfer_components
                             .script_deps
-                            .insert(SECP256K1.to_string());
-                        transfer_components.script_deps.insert(SUDT.to_string());
+                            .insert(udt_script_name.to_string());
                         let outputs_capacity =
                             u64::try_from(current_capacity as i128 - provided_capacity)
                                 .expect("impossible: overflow");
-                        build_cell_for_output(
+                        rebuild_udt_cell_for_output(
+                            &cell,
                             outputs_capacity,
                             cell.cell_output.lock(),
-                            cell.cell_output.type_().to_opt(),
                             Some(current_udt_amount),
                             &mut transfer_components.outputs,
                             &mut transfer_components.outputs_data,
@@ -1991,7 +2348,9 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                 let current_udt_amount = decode_udt_amount(&cell.cell_data);
 
                 let provided_capacity = if cell.cell_output.type_().to_opt().is_some() {
-                    transfer_components.script_deps.insert(SUDT.to_string());
+                    transfer_components
+                        .script_deps
+                        .insert(get_udt_script_name(&cell.cell_output).to_string());
 
                     let data_occupied = Capacity::bytes(cell.cell_data.len())
                         .expect("impossible: get data occupied capacity fail");
@@ -2022,10 +2381,10 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                     let outputs_capacity =
                         u64::try_from(current_capacity as i128 - provided_capacity)
                             .expect("impossible: overflow");
-                    build_cell_for_output(
+                    rebuild_udt_cell_for_output(
+                        &cell,
                         outputs_capacity,
                         cell.cell_output.lock(),
-                        cell.cell_output.type_().to_opt(),
                         current_udt_amount,
                         &mut transfer_components.outputs,
                         &mut transfer_components.outputs_data,
@@ -2087,7 +2446,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
 
                 // add since
                 transfer_components
-                    .dao_since_map
+                    .since_map
                     .insert(transfer_components.inputs.len(), since);
 
                 // build header deps
@@ -2141,7 +2500,9 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                 let current_udt_amount = decode_udt_amount(&cell.cell_data);
 
                 let provided_capacity = if cell.cell_output.type_().to_opt().is_some() {
-                    transfer_components.script_deps.insert(SUDT.to_string());
+                    transfer_components
+                        .script_deps
+                        .insert(get_udt_script_name(&cell.cell_output).to_string());
 
                     let data_occupied = Capacity::bytes(cell.cell_data.len())
                         .expect("impossible: get data occupied capacity fail");
@@ -2175,10 +2536,10 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                     let outputs_capacity =
                         u64::try_from(current_capacity as i128 - provided_capacity)
                             .expect("impossible: overflow");
-                    build_cell_for_output(
+                    rebuild_udt_cell_for_output(
+                        &cell,
                         outputs_capacity,
                         cell.cell_output.lock(),
-                        cell.cell_output.type_().to_opt(),
                         current_udt_amount,
                         &mut transfer_components.outputs,
                         &mut transfer_components.outputs_data,
@@ -2191,6 +2552,11 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             _ => unreachable!(),
         };
 
+        if let Some(since) = get_multisig_since(&cell.cell_output.lock()) {
+            transfer_components
+                .since_map
+                .insert(transfer_components.inputs.len(), since);
+        }
         transfer_components.inputs.push(cell);
 
         provided_capacity
@@ -2203,7 +2569,9 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         required_udt_amount: BigInt,
         transfer_components: &mut TransferComponents,
     ) -> InnerResult<BigInt> {
-        transfer_components.script_deps.insert(SUDT.to_string());
+        transfer_components
+            .script_deps
+            .insert(get_udt_script_name(&cell.cell_output).to_string());
 
         let provided_udt_amount = match asset_script_type {
             AssetScriptType::Cheque(item) => {
@@ -2236,10 +2604,10 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                         - required_udt_amount.clone())
                     .to_u128()
                     .expect("impossible: overflow");
-                    build_cell_for_output(
+                    rebuild_udt_cell_for_output(
+                        &cell,
                         cell.cell_output.capacity().unpack(),
                         sender_lock,
-                        cell.cell_output.type_().to_opt(),
                         Some(outputs_udt_amount),
                         &mut transfer_components.outputs,
                         &mut transfer_components.outputs_data,
@@ -2247,14 +2615,12 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                     required_udt_amount
                 }
             }
-            AssetScriptType::Secp256k1 => {
-                transfer_components
-                    .script_deps
-                    .insert(SECP256K1.to_string());
+            AssetScriptType::Secp256k1 | AssetScriptType::Omnilock | AssetScriptType::Multisig => {
+                insert_owner_lock_script_deps(&asset_script_type, transfer_components);
 
                 let max_provided_udt_amount = decode_udt_amount(&cell.cell_data).unwrap_or(0);
                 if required_udt_amount >= BigInt::from(max_provided_udt_amount) {
-                    // convert to secp cell without type
+                    // convert to a cell without type
                     build_cell_for_output(
                         cell.cell_output.capacity().unpack(),
                         cell.cell_output.lock(),
@@ -2269,10 +2635,10 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                         - required_udt_amount.clone())
                     .to_u128()
                     .expect("impossible: overflow");
-                    build_cell_for_output(
+                    rebuild_udt_cell_for_output(
+                        &cell,
                         cell.cell_output.capacity().unpack(),
                         cell.cell_output.lock(),
-                        cell.cell_output.type_().to_opt(),
                         Some(outputs_udt_amount),
                         &mut transfer_components.outputs,
                         &mut transfer_components.outputs_data,
@@ -2297,10 +2663,10 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                 let outputs_udt_amount = (max_provided_udt_amount - provided_udt_amount.clone())
                     .to_u128()
                     .expect("impossible: overflow");
-                build_cell_for_output(
+                rebuild_udt_cell_for_output(
+                    &cell,
                     cell.cell_output.capacity().unpack(),
                     cell.cell_output.lock(),
-                    cell.cell_output.type_().to_opt(),
                     Some(outputs_udt_amount),
                     &mut transfer_components.outputs,
                     &mut transfer_components.outputs_data,
@@ -2328,10 +2694,10 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                 let outputs_udt_amount = (max_provided_udt_amount - provided_udt_amount.clone())
                     .to_u128()
                     .expect("impossible: overflow");
-                build_cell_for_output(
+                rebuild_udt_cell_for_output(
+                    &cell,
                     cell.cell_output.capacity().unpack(),
                     cell.cell_output.lock(),
-                    cell.cell_output.type_().to_opt(),
                     Some(outputs_udt_amount),
                     &mut transfer_components.outputs,
                     &mut transfer_components.outputs_data,
@@ -2342,6 +2708,11 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             _ => unreachable!(),
         };
 
+        if let Some(since) = get_multisig_since(&cell.cell_output.lock()) {
+            transfer_components
+                .since_map
+                .insert(transfer_components.inputs.len(), since);
+        }
         transfer_components.inputs.push(cell);
 
         Ok(provided_udt_amount)
@@ -2362,10 +2733,17 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
         if let Ok(address) = address {
             if is_secp256k1(&address) {
                 if let Some(script) = cell.type_().to_opt() {
-                    if let Ok(true) = self.is_script(&script, SUDT) {
+                    let type_code_hash: H256 = script.code_hash().unpack();
+                    if is_udt_code_hash(&type_code_hash) {
                         let current_capacity: u64 = cell.capacity().unpack();
-                        let extra_capacity =
-                            current_capacity.saturating_sub(STANDARD_SUDT_CAPACITY);
+                        let cell_data: Bytes = cell_data.unpack();
+                        let data_occupied = Capacity::bytes(cell_data.len())
+                            .expect("impossible: get data occupied capacity fail");
+                        let occupied = cell
+                            .occupied_capacity(data_occupied)
+                            .expect("impossible: get cell occupied capacity fail")
+                            .as_u64();
+                        let extra_capacity = current_capacity.saturating_sub(occupied);
                         Some((current_capacity, extra_capacity))
                     } else {
                         None
@@ -2375,7 +2753,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
                     let extra_capacity = current_capacity.saturating_sub(MIN_CKB_CAPACITY);
                     Some((current_capacity, extra_capacity))
                 }
-            } else if is_acp(&address) | is_pw_lock(&address) {
+            } else if is_acp(&address) | is_pw_lock(&address) | is_omni_lock(&address) {
                 let current_capacity: u64 = cell.capacity().unpack();
 
                 let cell_data: Bytes = cell_data.unpack();
@@ -2415,7 +2793,7 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             };
         if let Some(type_script) = cell.type_().to_opt() {
             let type_code_hash: H256 = type_script.code_hash().unpack();
-            if Some(&type_code_hash) != SUDT_CODE_HASH.get() {
+            if !is_udt_code_hash(&type_code_hash) {
                 return false;
             }
         }
@@ -2491,14 +2869,14 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             asset_infos
                 .into_iter()
                 .filter(|asset_info| {
-                    !(extra == Some(ExtraType::Dao) && asset_info.asset_type == AssetType::UDT)
+                    !(extra == Some(ExtraType::Dao) && asset_info.asset_type != AssetType::CKB)
                 })
                 .map(|asset_info| match asset_info.asset_type {
                     AssetType::CKB => match extra {
                         Some(ExtraType::Dao) => dao_script_hash.clone(),
                         _ => H256::default(),
                     },
-                    AssetType::UDT => asset_info.udt_hash,
+                    AssetType::UDT | AssetType::XUDT => asset_info.udt_hash,
                 })
                 .collect()
         }
@@ -2507,7 +2885,13 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
     pub fn address_to_identity(&self, address: &str) -> InnerResult<Identity> {
         let address = Address::from_str(address).map_err(CoreError::ParseAddressError)?;
         let script = address_to_script(address.payload());
-        let pub_key_hash = script.args().as_slice()[4..24].to_vec();
+        let args = script.args().raw_data();
+        if args.len() < 20 {
+            return Err(
+                CoreError::UnsupportLockScript(hex::encode(script.code_hash().as_slice())).into(),
+            );
+        }
+        let pub_key_hash = args[0..20].to_vec();
 
         if is_secp256k1(&address) || is_acp(&address) {
             return Ok(Identity::new(
@@ -2523,6 +2907,14 @@ impl<C: CkbRpc> MercuryRpcImpl<C> {
             ));
         }
 
+        if is_omni_lock(&address) && args.len() >= 21 {
+            let flag = IdentityFlag::try_from(args[0])?;
+            return Ok(Identity::new(
+                flag,
+                H160::from_slice(&args[1..21]).expect("get auth content h160"),
+            ));
+        }
+
         Err(CoreError::UnsupportLockScript(hex::encode(script.code_hash().as_slice())).into())
     }
 }
@@ -2554,6 +2946,175 @@ pub(crate) fn build_cell_for_output(
     Ok(cell_index)
 }
 
+/// Rebuild an output from an existing UDT cell with a new capacity, lock and amount.
+/// The bytes following the amount are kept, so that xUDT extension data survives.
+pub(crate) fn rebuild_udt_cell_for_output(
+    cell: &DetailedCell,
+    capacity: u64,
+    lock_script: packed::Script,
+    udt_amount: Option<u128>,
+    outputs: &mut Vec<packed::CellOutput>,
+    cells_data: &mut Vec<packed::Bytes>,
+) -> InnerResult<usize> {
+    let cell_index = build_cell_for_output(
+        capacity,
+        lock_script,
+        cell.cell_output.type_().to_opt(),
+        udt_amount,
+        outputs,
+        cells_data,
+    )?;
+
+    if let Some(udt_amount) = udt_amount {
+        if cell.cell_data.len() > 16 {
+            let mut data = encode_udt_amount(udt_amount);
+            data.extend_from_slice(&cell.cell_data[16..]);
+            cells_data[cell_index] = Bytes::from(data).pack();
+        }
+    }
+
+    Ok(cell_index)
+}
+
+pub(crate) fn is_udt_code_hash(code_hash: &H256) -> bool {
+    Some(code_hash) == SUDT_CODE_HASH.get() || Some(code_hash) == XUDT_CODE_HASH.get()
+}
+
+pub(crate) fn udt_asset_info(type_script: &packed::Script) -> Option<AssetInfo> {
+    let type_code_hash: H256 = type_script.code_hash().unpack();
+    let type_hash: H256 = type_script.calc_script_hash().unpack();
+    if Some(&type_code_hash) == SUDT_CODE_HASH.get() {
+        Some(AssetInfo::new_udt(type_hash))
+    } else if Some(&type_code_hash) == XUDT_CODE_HASH.get() {
+        Some(AssetInfo::new_xudt(type_hash))
+    } else {
+        None
+    }
+}
+
+/// The cheque lock args are the receiver lock hash and the sender lock hash, 20 bytes each.
+pub(crate) fn has_cheque_lock_args(cell: &DetailedCell) -> bool {
+    cell.cell_output.lock().args().raw_data().len() >= 40
+}
+
+pub(crate) fn is_omni_lock_script(script: &packed::Script) -> bool {
+    let code_hash: H256 = script.code_hash().unpack();
+    script.hash_type() == ScriptHashType::Type.into()
+        && Some(&code_hash) == OMNI_LOCK_CODE_HASH.get()
+}
+
+/// Omni lock verifies signatures with the secp256k1 data cell, which comes along with
+/// the secp256k1 dep group. The multisig dep group contains the secp256k1 data cell too.
+pub(crate) fn insert_owner_lock_script_deps(
+    asset_script_type: &AssetScriptType,
+    transfer_components: &mut TransferComponents,
+) {
+    match asset_script_type {
+        AssetScriptType::Multisig => {
+            transfer_components.script_deps.insert(MULTISIG.to_string());
+        }
+        AssetScriptType::Omnilock => {
+            transfer_components
+                .script_deps
+                .insert(SECP256K1.to_string());
+            transfer_components
+                .script_deps
+                .insert(OMNI_LOCK.to_string());
+        }
+        _ => {
+            transfer_components
+                .script_deps
+                .insert(SECP256K1.to_string());
+        }
+    }
+}
+
+/// Serialize the multisig config into the multisig script, whose blake160 hash is the
+/// multisig lock args.
+pub(crate) fn build_multisig_script(config: &MultisigConfig) -> InnerResult<Bytes> {
+    let pubkey_hash_count = config.pubkey_hashes.len();
+    if config.threshold == 0
+        || pubkey_hash_count > u8::MAX as usize
+        || config.threshold as usize > pubkey_hash_count
+        || config.require_first_n > config.threshold
+    {
+        return Err(CoreError::InvalidMultisigConfig(format!("{:?}", config)).into());
+    }
+
+    let mut script = vec![0u8, config.require_first_n, config.threshold];
+    script.push(pubkey_hash_count as u8);
+    for pubkey_hash in config.pubkey_hashes.iter() {
+        script.extend_from_slice(pubkey_hash.as_bytes());
+    }
+    Ok(Bytes::from(script))
+}
+
+/// The since value in the args of a since-locked multisig lock.
+pub(crate) fn get_multisig_since(lock: &packed::Script) -> Option<u64> {
+    let code_hash: H256 = lock.code_hash().unpack();
+    let args = lock.args().raw_data();
+    if code_hash != MULTISIG_TYPE_HASH || args.len() != 28 {
+        return None;
+    }
+    let mut since = [0u8; 8];
+    since.copy_from_slice(&args[20..28]);
+    Some(u64::from_le_bytes(since))
+}
+
+/// Whether the since lock of a multisig cell is satisfied at the current tip. Timestamp
+/// since is never considered unlocked, as the median time of the tip is unknown here.
+pub(crate) fn is_multisig_cell_unlocked(cell: &DetailedCell) -> bool {
+    let since = if let Some(since) = get_multisig_since(&cell.cell_output.lock()) {
+        since
+    } else {
+        return true;
+    };
+
+    let is_relative = since & (1 << 63) != 0;
+    let metric = (since >> 61) & 0b11;
+    let value = since & 0x00ff_ffff_ffff_ffff;
+    match (metric, is_relative) {
+        (0b00, false) => **CURRENT_BLOCK_NUMBER.load() >= value,
+        (0b00, true) => **CURRENT_BLOCK_NUMBER.load() >= cell.block_number.saturating_add(value),
+        (0b01, false) => {
+            EpochNumberWithFraction::from_full_value(value).to_rational()
+                <= **CURRENT_EPOCH_NUMBER.load()
+        }
+        (0b01, true) => {
+            EpochNumberWithFraction::from_full_value(cell.epoch_number).to_rational()
+                + EpochNumberWithFraction::from_full_value(value).to_rational()
+                <= **CURRENT_EPOCH_NUMBER.load()
+        }
+        _ => false,
+    }
+}
+
+/// The builtin script name of the UDT type script of the cell, which is used as the
+/// key of its cell dep.
+pub(crate) fn get_udt_script_name(cell_output: &packed::CellOutput) -> &'static str {
+    let is_xudt = cell_output.type_().to_opt().map_or(false, |type_script| {
+        let type_code_hash: H256 = type_script.code_hash().unpack();
+        Some(&type_code_hash) == XUDT_CODE_HASH.get()
+    });
+    if is_xudt {
+        XUDT
+    } else {
+        SUDT
+    }
+}
+
+/// A cell in the tip block has one confirmation.
+pub(crate) fn is_confirmed(
+    block_number: BlockNumber,
+    tip_block_number: BlockNumber,
+    min_confirmations: u64,
+) -> bool {
+    tip_block_number
+        .saturating_add(1)
+        .saturating_sub(block_number)
+        >= min_confirmations
+}
+
 pub(crate) fn is_dao_withdraw_unlock(
     deposit_epoch: RationalU256,
     withdraw_epoch: RationalU256,
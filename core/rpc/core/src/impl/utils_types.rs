@@ -1,9 +1,11 @@
-use common::{DetailedCell, PaginationRequest};
-use core_rpc_types::{AssetInfo, Item};
+use common::{utils::decode_udt_amount, DetailedCell, PaginationRequest};
+use core_rpc_types::{AssetInfo, AssetType, CoinSelection, Item};
 
-use ckb_types::packed;
+use ckb_types::{bytes::Bytes, core::Capacity, packed, prelude::*, H160};
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
 
-use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 
 #[allow(clippy::upper_case_acronyms)]
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
@@ -13,6 +15,8 @@ pub enum AssetScriptType {
     Cheque(Item),
     Dao(Item),
     PwLock,
+    Omnilock,
+    Multisig,
 }
 
 #[derive(Debug, Default)]
@@ -25,8 +29,10 @@ pub struct TransferComponents {
     pub type_witness_args: HashMap<usize, (packed::BytesOpt, packed::BytesOpt)>,
     pub fee_change_cell_index: Option<usize>,
     pub dao_reward_capacity: u64,
-    pub dao_since_map: HashMap<usize, u64>,
+    pub since_map: HashMap<usize, u64>,
     pub inputs_not_require_signature: HashSet<usize>,
+    pub multisig_scripts: HashMap<H160, Bytes>,
+    pub coin_selection: CoinSelection,
 }
 
 impl TransferComponents {
@@ -43,6 +49,8 @@ pub enum PoolCkbCategory {
     CkbNormalSecp,
     CkbSecpUdt,
     PwLockEthereum,
+    Omnilock,
+    Multisig,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -51,6 +59,8 @@ pub enum PoolUdtCategory {
     CkbSecpUdt,
     CkbAcp,
     PwLockEthereum,
+    Omnilock,
+    Multisig,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -65,6 +75,8 @@ pub struct CkbCellsCache {
     pub array_index: usize,
     pub cell_deque: VecDeque<(DetailedCell, AssetScriptType)>,
     pub pagination: PaginationRequest,
+    pub coin_selection: CoinSelection,
+    pub selection_pool: SelectionPool,
 }
 
 impl CkbCellsCache {
@@ -78,6 +90,8 @@ impl CkbCellsCache {
                 PoolCkbCategory::CkbSecpUdt,
                 PoolCkbCategory::CkbAcp,
                 PoolCkbCategory::PwLockEthereum,
+                PoolCkbCategory::Omnilock,
+                PoolCkbCategory::Multisig,
             ] {
                 item_category_array.push((item_index, category_index.to_owned()))
             }
@@ -88,9 +102,27 @@ impl CkbCellsCache {
             array_index: 0,
             cell_deque: VecDeque::new(),
             pagination: PaginationRequest::default(),
+            coin_selection: CoinSelection::default(),
+            selection_pool: SelectionPool::default(),
         }
     }
 
+    pub fn pop_cell(&mut self, required_capacity: i128) -> Option<(DetailedCell, AssetScriptType)> {
+        let is_pooled_all = self.is_pooled_all();
+        self.selection_pool.select_cell(
+            &mut self.cell_deque,
+            self.coin_selection,
+            is_pooled_all,
+            pool_size(&self.pagination),
+            cell_spendable_capacity,
+            required_capacity.max(0) as u128,
+        )
+    }
+
+    pub fn is_pooled_all(&self) -> bool {
+        self.array_index >= self.item_category_array.len()
+    }
+
     pub fn get_current_item_index(&self) -> usize {
         if self.array_index >= self.item_category_array.len() {
             return self.items.len();
@@ -106,6 +138,8 @@ pub struct UdtCellsCache {
     pub array_index: usize,
     pub cell_deque: VecDeque<(DetailedCell, AssetScriptType)>,
     pub pagination: PaginationRequest,
+    pub coin_selection: CoinSelection,
+    pub selection_pool: SelectionPool,
 }
 
 impl UdtCellsCache {
@@ -117,6 +151,8 @@ impl UdtCellsCache {
                 PoolUdtCategory::CkbSecpUdt,
                 PoolUdtCategory::CkbAcp,
                 PoolUdtCategory::PwLockEthereum,
+                PoolUdtCategory::Omnilock,
+                PoolUdtCategory::Multisig,
             ] {
                 item_category_array.push((item_index, category_index.to_owned()))
             }
@@ -128,8 +164,29 @@ impl UdtCellsCache {
             array_index: 0,
             cell_deque: VecDeque::new(),
             pagination: PaginationRequest::default(),
+            coin_selection: CoinSelection::default(),
+            selection_pool: SelectionPool::default(),
         }
     }
+
+    pub fn is_pooled_all(&self) -> bool {
+        self.array_index >= self.item_category_array.len()
+    }
+
+    pub fn pop_cell(
+        &mut self,
+        required_udt_amount: &BigInt,
+    ) -> Option<(DetailedCell, AssetScriptType)> {
+        let is_pooled_all = self.is_pooled_all();
+        self.selection_pool.select_cell(
+            &mut self.cell_deque,
+            self.coin_selection,
+            is_pooled_all,
+            pool_size(&self.pagination),
+            cell_udt_amount,
+            required_udt_amount.to_u128().unwrap_or(u128::MAX),
+        )
+    }
 }
 
 pub struct AcpCellsCache {
@@ -139,6 +196,8 @@ pub struct AcpCellsCache {
     pub array_index: usize,
     pub cell_deque: VecDeque<(DetailedCell, AssetScriptType)>,
     pub pagination: PaginationRequest,
+    pub coin_selection: CoinSelection,
+    pub selection_pool: SelectionPool,
 }
 
 impl AcpCellsCache {
@@ -156,6 +215,118 @@ impl AcpCellsCache {
             array_index: 0,
             cell_deque: VecDeque::new(),
             pagination: PaginationRequest::default(),
+            coin_selection: CoinSelection::default(),
+            selection_pool: SelectionPool::default(),
+        }
+    }
+
+    /// An ACP cell receives the asset rather than provides it, so the minimize change
+    /// strategy takes the smallest cell first.
+    pub fn pop_cell(&mut self) -> Option<(DetailedCell, AssetScriptType)> {
+        let value: fn(&DetailedCell, &AssetScriptType) -> u128 = match &self.asset_info {
+            Some(asset_info) if asset_info.asset_type != AssetType::CKB => cell_udt_amount,
+            _ => cell_capacity,
+        };
+        let is_pooled_all = self.array_index >= self.item_category_array.len();
+        self.selection_pool.select_cell(
+            &mut self.cell_deque,
+            self.coin_selection,
+            is_pooled_all,
+            pool_size(&self.pagination),
+            value,
+            0,
+        )
+    }
+}
+
+/// The cells loaded for a coin selection strategy other than the default one, ordered
+/// by the key of the strategy.
+#[derive(Debug, Default)]
+pub struct SelectionPool {
+    cells: BTreeMap<(u128, usize), (DetailedCell, AssetScriptType)>,
+    seq: usize,
+}
+
+impl SelectionPool {
+    /// Take the next cell of the coin selection strategy. The default strategy takes the
+    /// cells in the order they are loaded, while the other strategies wait until the
+    /// loaded cells fill the pool or all the categories are loaded, and select from
+    /// them. The pool is refilled from the loaded cells when it runs out.
+    fn select_cell(
+        &mut self,
+        cell_deque: &mut VecDeque<(DetailedCell, AssetScriptType)>,
+        coin_selection: CoinSelection,
+        is_pooled_all: bool,
+        pool_size: usize,
+        value: fn(&DetailedCell, &AssetScriptType) -> u128,
+        required: u128,
+    ) -> Option<(DetailedCell, AssetScriptType)> {
+        if coin_selection == CoinSelection::Default {
+            return cell_deque.pop_front();
+        }
+        if self.cells.is_empty() {
+            if !is_pooled_all && cell_deque.len() < pool_size {
+                return None;
+            }
+            for (cell, asset_script_type) in cell_deque.drain(..) {
+                let key = match coin_selection {
+                    CoinSelection::OldestFirst => {
+                        let index: u32 = cell.out_point.index().unpack();
+                        (cell.block_number as u128) << 64
+                            | (cell.tx_index as u128) << 32
+                            | index as u128
+                    }
+                    _ => value(&cell, &asset_script_type),
+                };
+                self.cells
+                    .insert((key, self.seq), (cell, asset_script_type));
+                self.seq += 1;
+            }
         }
+
+        let key = match coin_selection {
+            CoinSelection::LargestFirst => self.cells.keys().next_back(),
+            // the smallest cell covering the required value, or the largest one
+            CoinSelection::MinimizeChange => self
+                .cells
+                .range((required, 0)..)
+                .map(|(key, _)| key)
+                .next()
+                .or_else(|| self.cells.keys().next_back()),
+            _ => self.cells.keys().next(),
+        };
+        let key = *key?;
+        self.cells.remove(&key)
     }
 }
+
+/// The number of the loaded cells a coin selection strategy selects from at a time.
+fn pool_size(pagination: &PaginationRequest) -> usize {
+    pagination.limit.map_or(usize::MAX, usize::from)
+}
+
+fn cell_capacity(cell: &DetailedCell, _: &AssetScriptType) -> u128 {
+    let capacity: u64 = cell.cell_output.capacity().unpack();
+    capacity as u128
+}
+
+/// The capacity a cell provides when pooled for CKB. A UDT cell holding an amount and an
+/// ACP cell with type script are kept in the outputs, so only the capacity above their
+/// occupied capacity is spendable.
+fn cell_spendable_capacity(cell: &DetailedCell, asset_script_type: &AssetScriptType) -> u128 {
+    let capacity: u64 = cell.cell_output.capacity().unpack();
+    let is_kept = cell.cell_output.type_().is_some()
+        && (*asset_script_type == AssetScriptType::ACP
+            || decode_udt_amount(&cell.cell_data).unwrap_or(0) != 0);
+    if !is_kept {
+        return capacity as u128;
+    }
+    let occupied = Capacity::bytes(cell.cell_data.len())
+        .and_then(|data_occupied| cell.cell_output.occupied_capacity(data_occupied))
+        .map_or(0, |occupied| occupied.as_u64());
+    capacity.saturating_sub(occupied) as u128
+}
+
+fn cell_udt_amount(cell: &DetailedCell, _: &AssetScriptType) -> u128 {
+    decode_udt_amount(&cell.cell_data).unwrap_or(0)
+}
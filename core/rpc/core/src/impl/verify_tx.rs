@@ -0,0 +1,339 @@
+use crate::{error::CoreError, InnerResult, MercuryRpcImpl};
+
+use ckb_chain_spec::consensus::{Consensus, ConsensusBuilder};
+use ckb_chain_spec::ChainSpec;
+use ckb_jsonrpc_types::TransactionView as JsonTransactionView;
+use ckb_resource::Resource;
+use ckb_script::{ScriptError, TransactionScriptsVerifier};
+use ckb_traits::{CellDataProvider, HeaderProvider};
+use ckb_types::core::cell::{CellMeta, ResolvedTransaction};
+use ckb_types::core::{
+    Capacity, Cycle, DepType, EpochNumberWithFraction, HeaderView, TransactionInfo, TransactionView,
+};
+use ckb_types::{bytes::Bytes, packed, prelude::*};
+use common::{DetailedCell, NetworkType, PaginationRequest};
+use core_ckb_client::CkbRpc;
+use core_rpc_types::consts::MAX_TX_VERIFY_CYCLES;
+use core_rpc_types::{
+    ScriptGroup, ScriptGroupType, ScriptGroupVerification, TransactionCompletionResponse,
+    VerifyTransactionPayload, VerifyTransactionResponse,
+};
+use core_storage::Storage;
+
+use std::collections::HashMap;
+
+const SIGNATURE_SIZE: usize = 65;
+// The cycles to verify one secp256k1 signature and the cycles to load and hash the
+// transaction, measured with the secp256k1 blake160 sighash all and multisig all locks
+// and rounded up. They estimate the cycles of a lock group holding placeholder signatures.
+const SIGNATURE_VERIFICATION_CYCLES: Cycle = 1_500_000;
+const LOCK_BASE_CYCLES: Cycle = 300_000;
+
+/// Provides the cell data and headers resolved from the storage to the script verifier.
+struct VerificationDataLoader {
+    cell_data: HashMap<packed::OutPoint, Bytes>,
+    headers: HashMap<packed::Byte32, HeaderView>,
+}
+
+impl CellDataProvider for VerificationDataLoader {
+    fn get_cell_data(&self, out_point: &packed::OutPoint) -> Option<Bytes> {
+        self.cell_data.get(out_point).cloned()
+    }
+
+    fn get_cell_data_hash(&self, out_point: &packed::OutPoint) -> Option<packed::Byte32> {
+        self.cell_data
+            .get(out_point)
+            .map(|data| packed::CellOutput::calc_data_hash(data))
+    }
+}
+
+impl HeaderProvider for VerificationDataLoader {
+    fn get_header(&self, hash: &packed::Byte32) -> Option<HeaderView> {
+        self.headers.get(hash).cloned()
+    }
+}
+
+impl<C: CkbRpc> MercuryRpcImpl<C> {
+    pub(crate) async fn inner_verify_transaction(
+        &self,
+        payload: VerifyTransactionPayload,
+    ) -> InnerResult<VerifyTransactionResponse> {
+        let tx = packed::Transaction::from(payload.tx_view.inner).into_view();
+        let max_cycles = payload
+            .max_cycles
+            .map(Into::into)
+            .unwrap_or(MAX_TX_VERIFY_CYCLES);
+        self.verify_transaction(tx, max_cycles).await
+    }
+
+    pub(crate) async fn build_transaction_completion_response(
+        &self,
+        tx_view: JsonTransactionView,
+        script_groups: Vec<ScriptGroup>,
+        verify: bool,
+    ) -> InnerResult<TransactionCompletionResponse> {
+        self.reserve_inputs(&tx_view);
+        let mut response = TransactionCompletionResponse::new(tx_view, script_groups);
+        if verify {
+            let tx = packed::Transaction::from(response.tx_view.inner.clone()).into_view();
+            response.verification = Some(self.verify_transaction(tx, MAX_TX_VERIFY_CYCLES).await?);
+        }
+        Ok(response)
+    }
+
+    async fn verify_transaction(
+        &self,
+        tx: TransactionView,
+        max_cycles: Cycle,
+    ) -> InnerResult<VerifyTransactionResponse> {
+        let (rtx, data_loader) = match self.resolve_transaction(&tx).await? {
+            Ok(resolved) => resolved,
+            Err(error) => return Ok(invalid_response(error)),
+        };
+        if let Err(error) = check_outputs(&tx) {
+            return Ok(invalid_response(error));
+        }
+
+        let verifier = TransactionScriptsVerifier::new(&rtx, &data_loader);
+
+        let mut is_valid = true;
+        let mut total_cycles: Cycle = 0;
+        let mut script_groups = vec![];
+        for (script_hash, group) in verifier.groups() {
+            let (group_type, placeholder_signatures) = match group.group_type {
+                ckb_script::ScriptGroupType::Lock => (
+                    ScriptGroupType::Lock,
+                    count_placeholder_signatures(&tx, group.input_indices[0]),
+                ),
+                ckb_script::ScriptGroupType::Type => (ScriptGroupType::Type, None),
+            };
+            let (cycles, error) = match verifier.verify_single(
+                group.group_type,
+                script_hash,
+                max_cycles.saturating_sub(total_cycles),
+            ) {
+                Ok(cycles) => {
+                    total_cycles = total_cycles.saturating_add(cycles);
+                    (Some(cycles.into()), None)
+                }
+                // only the placeholder signatures failing the lock script itself are
+                // expected, a missing script or a cycle overrun is still invalid
+                Err(e @ ScriptError::ValidationFailure(..)) if placeholder_signatures.is_some() => {
+                    let count = placeholder_signatures.unwrap_or_default();
+                    let cycles = LOCK_BASE_CYCLES + SIGNATURE_VERIFICATION_CYCLES * count;
+                    total_cycles = total_cycles.saturating_add(cycles);
+                    (Some(cycles.into()), Some(e.to_string()))
+                }
+                Err(e) => {
+                    is_valid = false;
+                    (None, Some(e.to_string()))
+                }
+            };
+            script_groups.push(ScriptGroupVerification {
+                script: group.script.clone().into(),
+                group_type,
+                placeholder_witness: placeholder_signatures.is_some(),
+                cycles,
+                error,
+            });
+        }
+
+        // the estimated cycles are not limited by the verifier
+        let error = (total_cycles > max_cycles).then(|| {
+            is_valid = false;
+            format!(
+                "the cycles {} exceed the max cycles {}",
+                total_cycles, max_cycles
+            )
+        });
+
+        Ok(VerifyTransactionResponse {
+            is_valid,
+            cycles: total_cycles.into(),
+            script_groups,
+            error,
+        })
+    }
+
+    /// Resolve the inputs, cell deps and header deps of the transaction from the
+    /// storage. A cell or header which cannot be found is a structural failure of
+    /// the transaction, which is returned as the inner error message.
+    async fn resolve_transaction(
+        &self,
+        tx: &TransactionView,
+    ) -> InnerResult<Result<(ResolvedTransaction, VerificationDataLoader), String>> {
+        let mut cell_data = HashMap::new();
+        let mut headers = HashMap::new();
+
+        let mut resolved_inputs = vec![];
+        for (index, out_point) in tx.input_pts_iter().enumerate() {
+            match self.get_live_cell_meta(out_point, &mut cell_data).await? {
+                Some(cell_meta) => resolved_inputs.push(cell_meta),
+                None => return Ok(Err(format!("input {} is not a live cell", index))),
+            }
+        }
+
+        let mut resolved_cell_deps = vec![];
+        let mut resolved_dep_groups = vec![];
+        for (index, cell_dep) in tx.cell_deps_iter().enumerate() {
+            let cell_meta = match self
+                .get_live_cell_meta(cell_dep.out_point(), &mut cell_data)
+                .await?
+            {
+                Some(cell_meta) => cell_meta,
+                None => return Ok(Err(format!("cell dep {} is not a live cell", index))),
+            };
+
+            if cell_dep.dep_type() == DepType::DepGroup.into() {
+                let data = cell_meta.mem_cell_data.clone().unwrap_or_default();
+                let out_points = match packed::OutPointVec::from_slice(&data) {
+                    Ok(out_points) => out_points,
+                    Err(_) => {
+                        return Ok(Err(format!("cell dep {} is an invalid dep group", index)))
+                    }
+                };
+                for out_point in out_points.into_iter() {
+                    match self.get_live_cell_meta(out_point, &mut cell_data).await? {
+                        Some(member) => resolved_cell_deps.push(member),
+                        None => {
+                            return Ok(Err(format!(
+                                "a member of dep group {} is not a live cell",
+                                index
+                            )))
+                        }
+                    }
+                }
+                resolved_dep_groups.push(cell_meta);
+            } else {
+                resolved_cell_deps.push(cell_meta);
+            }
+        }
+
+        for (index, block_hash) in tx.header_deps_iter().enumerate() {
+            match self
+                .storage
+                .get_block_header(Some(block_hash.unpack()), None)
+                .await
+            {
+                Ok(header) => {
+                    headers.insert(block_hash, header);
+                }
+                Err(_) => return Ok(Err(format!("header dep {} cannot be found", index))),
+            }
+        }
+
+        let rtx = ResolvedTransaction {
+            transaction: tx.clone(),
+            resolved_cell_deps,
+            resolved_inputs,
+            resolved_dep_groups,
+        };
+        Ok(Ok((rtx, VerificationDataLoader { cell_data, headers })))
+    }
+
+    async fn get_live_cell_meta(
+        &self,
+        out_point: packed::OutPoint,
+        cell_data: &mut HashMap<packed::OutPoint, Bytes>,
+    ) -> InnerResult<Option<CellMeta>> {
+        let cell = self
+            .storage
+            .get_live_cells(
+                Some(out_point),
+                vec![],
+                vec![],
+                None,
+                None,
+                None,
+                PaginationRequest::default(),
+            )
+            .await
+            .map_err(|e| CoreError::DBError(e.to_string()))?
+            .response
+            .pop();
+
+        Ok(cell.map(|cell| {
+            cell_data.insert(cell.out_point.clone(), cell.cell_data.clone());
+            to_cell_meta(cell)
+        }))
+    }
+}
+
+pub(crate) fn build_consensus(network_type: NetworkType) -> Consensus {
+    let spec_name = match network_type {
+        NetworkType::Mainnet => "mainnet",
+        NetworkType::Testnet => "testnet",
+        NetworkType::Staging | NetworkType::Dev => return ConsensusBuilder::default().build(),
+    };
+    ChainSpec::load_from(&Resource::bundled(format!("specs/{}.toml", spec_name)))
+        .and_then(|spec| spec.build_consensus())
+        .expect("build consensus from bundled chain spec")
+}
+
+fn to_cell_meta(cell: DetailedCell) -> CellMeta {
+    let transaction_info = TransactionInfo::new(
+        cell.block_number,
+        EpochNumberWithFraction::from_full_value(cell.epoch_number),
+        cell.block_hash.pack(),
+        cell.tx_index as usize,
+    );
+    CellMeta {
+        cell_output: cell.cell_output,
+        out_point: cell.out_point,
+        transaction_info: Some(transaction_info),
+        data_bytes: cell.cell_data.len() as u64,
+        mem_cell_data_hash: Some(packed::CellOutput::calc_data_hash(&cell.cell_data)),
+        mem_cell_data: Some(cell.cell_data),
+    }
+}
+
+fn check_outputs(tx: &TransactionView) -> Result<(), String> {
+    if tx.outputs().len() != tx.outputs_data().len() {
+        return Err("the numbers of outputs and outputs data are not equal".to_string());
+    }
+
+    for (index, (output, data)) in tx.outputs_with_data_iter().enumerate() {
+        let occupied = Capacity::bytes(data.len())
+            .and_then(|data_capacity| output.occupied_capacity(data_capacity))
+            .map_err(|e| e.to_string())?;
+        let capacity: Capacity = output.capacity().unpack();
+        if capacity < occupied {
+            return Err(format!("output {} has insufficient capacity", index));
+        }
+    }
+
+    Ok(())
+}
+
+/// A witness whose lock field is empty or ends with zero filled signatures is the
+/// placeholder built by mercury, which is expected to fail the lock script. Returns
+/// the number of the placeholder signatures, which is the threshold of multisig.
+fn count_placeholder_signatures(tx: &TransactionView, input_index: usize) -> Option<u64> {
+    let lock = tx
+        .witnesses()
+        .get(input_index)
+        .and_then(|witness| packed::WitnessArgs::from_slice(&witness.raw_data()).ok())
+        .and_then(|witness_args| witness_args.lock().to_opt())
+        .map(|lock| lock.raw_data());
+
+    match lock {
+        Some(lock) if lock.is_empty() => Some(1),
+        Some(lock) => {
+            let count = lock
+                .rchunks_exact(SIGNATURE_SIZE)
+                .take_while(|signature| signature.iter().all(|byte| *byte == 0))
+                .count();
+            (count > 0).then(|| count as u64)
+        }
+        None => Some(1),
+    }
+}
+
+fn invalid_response(error: String) -> VerifyTransactionResponse {
+    VerifyTransactionResponse {
+        is_valid: false,
+        cycles: 0u64.into(),
+        script_groups: vec![],
+        error: Some(error),
+    }
+}
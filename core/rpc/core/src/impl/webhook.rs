@@ -0,0 +1,308 @@
+use crate::subscription::ItemMatcher;
+use crate::{error::CoreError, InnerResult, MercuryRpcImpl};
+
+use common::utils::unix_timestamp_millis;
+use common::DetailedCell;
+use core_ckb_client::CkbRpc;
+use core_rpc_types::consts::MAX_ITEM_NUM;
+use core_rpc_types::uints::Uint64;
+use core_rpc_types::{
+    IOType, RegisterWebhookPayload, Webhook, WebhookEvent, WebhookEventType, WebhookTransaction,
+};
+use core_storage::{Storage, WebhookDelivery};
+
+use ckb_types::core::BlockView;
+use ckb_types::{packed, prelude::*, H256};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+pub(crate) const WEBHOOK_SIGNATURE_HEADER: &str = "X-Mercury-Signature";
+pub(crate) const WEBHOOK_DELIVERY_HEADER: &str = "X-Mercury-Delivery";
+
+const WEBHOOK_DELIVERY_BATCH_SIZE: u64 = 1000;
+const MAX_WEBHOOK_DELIVERY_ATTEMPTS: u32 = 10;
+const WEBHOOK_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// the retry interval in milliseconds doubles after each failed attempt
+const WEBHOOK_RETRY_BASE_INTERVAL: u64 = 1000;
+const WEBHOOK_RETRY_MAX_INTERVAL: u64 = 3_600_000;
+
+impl<C: CkbRpc> MercuryRpcImpl<C> {
+    pub(crate) async fn inner_register_webhook(
+        &self,
+        payload: RegisterWebhookPayload,
+    ) -> InnerResult<Uint64> {
+        match reqwest::Url::parse(&payload.url) {
+            Ok(url) if url.scheme() == "http" || url.scheme() == "https" => (),
+            _ => {
+                return Err(CoreError::InvalidRpcParams(format!(
+                    "invalid webhook url {}",
+                    payload.url
+                ))
+                .into())
+            }
+        }
+        if payload.items.is_empty() {
+            return Err(CoreError::InvalidRpcParams("need at least one item".to_string()).into());
+        }
+        if payload.items.len() > MAX_ITEM_NUM {
+            return Err(CoreError::ExceedMaxItemNum.into());
+        }
+        if payload.secret.is_empty() {
+            return Err(CoreError::InvalidRpcParams("the secret is empty".to_string()).into());
+        }
+        for item in payload.items.iter() {
+            ItemMatcher::new(item.clone())?;
+        }
+
+        self.storage
+            .register_webhook(
+                payload.url,
+                payload.items,
+                payload.secret,
+                unix_timestamp_millis(),
+            )
+            .await
+            .map(Into::into)
+            .map_err(|error| CoreError::DBError(error.to_string()).into())
+    }
+
+    pub(crate) async fn inner_get_webhooks(&self) -> InnerResult<Vec<Webhook>> {
+        self.storage
+            .get_webhooks()
+            .await
+            .map_err(|error| CoreError::DBError(error.to_string()).into())
+    }
+
+    pub(crate) async fn inner_remove_webhook(&self, id: Uint64) -> InnerResult<bool> {
+        self.storage
+            .remove_webhook(id.into())
+            .await
+            .map_err(|error| CoreError::DBError(error.to_string()).into())
+    }
+
+    /// Build the events of the block for the webhooks watching its cells, each of which
+    /// is the webhook id and the payload. It must be called before the block is appended
+    /// or rolled back, so that the events are queued in the same database transaction.
+    pub async fn build_webhook_deliveries(
+        &self,
+        block: &BlockView,
+        event_type: WebhookEventType,
+    ) -> InnerResult<Vec<(u64, String)>> {
+        let webhooks = self.inner_get_webhooks().await?;
+        if webhooks.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut matchers = vec![];
+        for webhook in webhooks.iter() {
+            let mut webhook_matchers = vec![];
+            for item in webhook.items.iter() {
+                webhook_matchers.push(ItemMatcher::new(item.clone())?);
+            }
+            matchers.push(webhook_matchers);
+        }
+
+        // the inputs created by the former transactions of the block are taken from it, and
+        // the others are looked up at once
+        let block_number = block.number();
+        let block_out_points: HashSet<packed::OutPoint> = block
+            .transactions()
+            .iter()
+            .flat_map(|tx| tx.output_pts())
+            .collect();
+        let stored_out_points = block
+            .transactions()
+            .iter()
+            .filter(|tx| !tx.is_cellbase())
+            .flat_map(|tx| tx.input_pts_iter())
+            .filter(|out_point| !block_out_points.contains(out_point))
+            .collect::<Vec<_>>();
+        let mut stored_cells: HashMap<packed::OutPoint, DetailedCell> = self
+            .storage
+            .get_cells_by_out_points(stored_out_points)
+            .await
+            .map_err(|error| CoreError::DBError(error.to_string()))?
+            .into_iter()
+            .map(|cell| (cell.out_point.clone(), cell))
+            .collect();
+        let mut block_cells: HashMap<packed::OutPoint, DetailedCell> = HashMap::new();
+        let mut events: Vec<Vec<WebhookTransaction>> = vec![vec![]; webhooks.len()];
+        for (tx_index, tx) in block.transactions().iter().enumerate() {
+            let mut cells = vec![];
+            if !tx.is_cellbase() {
+                for out_point in tx.input_pts_iter() {
+                    if let Some(cell) = block_cells
+                        .get(&out_point)
+                        .cloned()
+                        .or_else(|| stored_cells.remove(&out_point))
+                    {
+                        cells.push((cell, IOType::Input));
+                    }
+                }
+            }
+            for (index, (output, data)) in tx.outputs_with_data_iter().enumerate() {
+                let cell = DetailedCell {
+                    epoch_number: block.epoch().full_value(),
+                    block_number,
+                    block_hash: block.hash().unpack(),
+                    tx_index: tx_index as u32,
+                    out_point: packed::OutPoint::new(tx.hash(), index as u32),
+                    cell_output: output,
+                    cell_data: data,
+                    consumed_block_number: None,
+                    consumed_block_hash: None,
+                    consumed_tx_hash: None,
+                    consumed_tx_index: None,
+                    consumed_input_index: None,
+                    since: None,
+                };
+                block_cells.insert(cell.out_point.clone(), cell.clone());
+                cells.push((cell, IOType::Output));
+            }
+
+            let tx_hash: H256 = tx.hash().unpack();
+            for (webhook_matchers, webhook_events) in matchers.iter().zip(events.iter_mut()) {
+                let mut records = vec![];
+                for (cell, io_type) in cells.iter() {
+                    let lock = cell.cell_output.lock();
+                    if webhook_matchers
+                        .iter()
+                        .any(|matcher| matcher.is_cell_match(&lock, &cell.out_point))
+                    {
+                        records.extend(
+                            self.to_record(cell, io_type.clone(), Some(block_number))
+                                .await?,
+                        );
+                    }
+                }
+                if !records.is_empty() {
+                    webhook_events.push(WebhookTransaction {
+                        tx_hash: tx_hash.clone(),
+                        records,
+                    });
+                }
+            }
+        }
+
+        let mut deliveries = vec![];
+        for (webhook, transactions) in webhooks.iter().zip(events.into_iter()) {
+            if transactions.is_empty() {
+                continue;
+            }
+            let event = WebhookEvent {
+                webhook_id: webhook.id,
+                event_type,
+                block_number: block_number.into(),
+                block_hash: block.hash().unpack(),
+                transactions,
+            };
+            let payload = serde_json::to_string(&event)
+                .map_err(|error| CoreError::CommonError(error.to_string()))?;
+            deliveries.push((webhook.id.into(), payload));
+        }
+        Ok(deliveries)
+    }
+
+    /// Post the due events in the queue, and return the number of the delivered ones. The
+    /// events of a webhook are delivered in order, so only the head of its queue is posted
+    /// at a time, and a failed event holds back the following ones until it is delivered
+    /// or given up. The other webhooks are not held back by it.
+    pub async fn deliver_webhook_events(&self) -> InnerResult<usize> {
+        let client = reqwest::Client::builder()
+            .timeout(WEBHOOK_REQUEST_TIMEOUT)
+            .build()
+            .map_err(|error| CoreError::CommonError(error.to_string()))?;
+        let now = unix_timestamp_millis();
+        let (mut posted, mut delivered) = (0, 0);
+        while posted < WEBHOOK_DELIVERY_BATCH_SIZE {
+            // each posted event leaves the head of its queue, or is retried after now
+            let deliveries = self
+                .storage
+                .get_pending_webhook_deliveries(now, WEBHOOK_DELIVERY_BATCH_SIZE - posted)
+                .await
+                .map_err(|error| CoreError::DBError(error.to_string()))?;
+            if deliveries.is_empty() {
+                break;
+            }
+            posted += deliveries.len() as u64;
+
+            for delivery in deliveries.iter() {
+                match post_webhook_event(&client, delivery).await {
+                    Ok(()) => {
+                        self.storage
+                            .complete_webhook_delivery(delivery.id)
+                            .await
+                            .map_err(|error| CoreError::DBError(error.to_string()))?;
+                        delivered += 1;
+                    }
+                    Err(error) => {
+                        let attempts = delivery.attempts + 1;
+                        let next_attempt_at = if attempts < MAX_WEBHOOK_DELIVERY_ATTEMPTS {
+                            Some(now + webhook_retry_interval(attempts))
+                        } else {
+                            log::error!(
+                                "give up the event {} of webhook {} after {} attempts",
+                                delivery.id,
+                                delivery.webhook_id,
+                                attempts
+                            );
+                            None
+                        };
+                        log::warn!(
+                            "deliver the event {} to {} error {}",
+                            delivery.id,
+                            delivery.url,
+                            error
+                        );
+                        self.storage
+                            .fail_webhook_delivery(delivery.id, attempts, next_attempt_at, error)
+                            .await
+                            .map_err(|error| CoreError::DBError(error.to_string()))?;
+                    }
+                }
+            }
+        }
+        Ok(delivered)
+    }
+}
+
+async fn post_webhook_event(
+    client: &reqwest::Client,
+    delivery: &WebhookDelivery,
+) -> Result<(), String> {
+    let response = client
+        .post(&delivery.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(WEBHOOK_DELIVERY_HEADER, delivery.id.to_string())
+        .header(
+            WEBHOOK_SIGNATURE_HEADER,
+            sign_webhook_payload(&delivery.secret, &delivery.payload),
+        )
+        .body(delivery.payload.clone())
+        .send()
+        .await
+        .map_err(|error| error.to_string())?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("response status {}", response.status()))
+    }
+}
+
+/// The HMAC-SHA256 of the payload keyed by the secret of the webhook, so that the
+/// receiver can verify the event is sent by mercury.
+pub(crate) fn sign_webhook_payload(secret: &str, payload: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac accepts keys of any size");
+    mac.update(payload.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+fn webhook_retry_interval(attempts: u32) -> u64 {
+    WEBHOOK_RETRY_BASE_INTERVAL
+        .saturating_mul(1 << attempts.saturating_sub(1).min(32))
+        .min(WEBHOOK_RETRY_MAX_INTERVAL)
+}
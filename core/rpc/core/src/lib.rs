@@ -2,22 +2,33 @@
 
 mod error;
 mod r#impl;
+mod subscription;
 #[cfg(test)]
 mod tests;
 
 pub use r#impl::MercuryRpcImpl;
+pub use subscription::MercurySubscriptionImpl;
 
+use ckb_jsonrpc_types::OutPoint;
 use ckb_types::{H160, H256};
 use common::{Order, Result};
 use core_rpc_types::error::MercuryRpcError;
 use core_rpc_types::{
     indexer,
     uints::{Uint16, Uint64},
-    AdjustAccountPayload, BlockInfo, DaoClaimPayload, DaoDepositPayload, DaoWithdrawPayload,
-    GetAccountInfoPayload, GetAccountInfoResponse, GetBalancePayload, GetBalanceResponse,
-    GetBlockInfoPayload, GetSpentTransactionPayload, GetTransactionInfoResponse, MercuryInfo,
-    PaginationResponse, QueryTransactionsPayload, SimpleTransferPayload, SudtIssuePayload,
-    SyncState, TransactionCompletionResponse, TransferPayload, TxView,
+    AccountStatement, AddressLabel, AdjustAccountPayload, BatchPayoutPayload, BlockInfo,
+    ChequeClaimPayload, ChequeInfo, ChequeTransactionResponse, ChequeWithdrawPayload,
+    ConsolidatePayload, DaoClaimPayload, DaoDepositPayload, DaoPosition, DaoWithdrawPayload,
+    GetAccountInfoPayload, GetAccountInfoResponse, GetAccountStatementPayload, GetBalancePayload,
+    GetBalanceResponse, GetBlockInfoPayload, GetBlocksInfoPayload, GetChainEventsPayload,
+    GetChainEventsResponse, GetChequesPayload, GetDaoPositionsPayload, GetSpentTransactionPayload,
+    GetSubmittedTransactionsPayload, GetTransactionInfoResponse, JsonItem, MercuryInfo,
+    PaginationResponse, QueryTransactionsPayload, QueryTransactionsResponse,
+    RegisterWebhookPayload, ReleaseInputReservationPayload, SearchAddressLabelsPayload,
+    SendTransactionPayload, SetAddressLabelPayload, SimpleTransferPayload, SubmittedTransaction,
+    SudtIssuePayload, SweepPayload, SweepTransactionResponse, SyncState,
+    TransactionCompletionResponse, TransferPayload, TxView, VerifyTransactionPayload,
+    VerifyTransactionResponse, Webhook,
 };
 use core_storage::DBInfo;
 use jsonrpsee_core::RpcResult;
@@ -33,6 +44,15 @@ pub trait MercuryRpc {
     #[method(name = "get_block_info")]
     async fn get_block_info(&self, payload: GetBlockInfoPayload) -> RpcResult<BlockInfo>;
 
+    #[method(name = "get_blocks_info")]
+    async fn get_blocks_info(&self, payload: GetBlocksInfoPayload) -> RpcResult<Vec<BlockInfo>>;
+
+    #[method(name = "get_chain_events")]
+    async fn get_chain_events(
+        &self,
+        payload: GetChainEventsPayload,
+    ) -> RpcResult<GetChainEventsResponse>;
+
     #[method(name = "get_transaction_info")]
     async fn get_transaction_info(&self, tx_hash: H256) -> RpcResult<GetTransactionInfoResponse>;
 
@@ -40,7 +60,25 @@ pub trait MercuryRpc {
     async fn query_transactions(
         &self,
         payload: QueryTransactionsPayload,
-    ) -> RpcResult<PaginationResponse<TxView>>;
+    ) -> RpcResult<QueryTransactionsResponse>;
+
+    #[method(name = "get_cheques")]
+    async fn get_cheques(
+        &self,
+        payload: GetChequesPayload,
+    ) -> RpcResult<PaginationResponse<ChequeInfo>>;
+
+    #[method(name = "get_dao_positions")]
+    async fn get_dao_positions(
+        &self,
+        payload: GetDaoPositionsPayload,
+    ) -> RpcResult<PaginationResponse<DaoPosition>>;
+
+    #[method(name = "get_account_statement")]
+    async fn get_account_statement(
+        &self,
+        payload: GetAccountStatementPayload,
+    ) -> RpcResult<AccountStatement>;
 
     #[method(name = "get_account_info")]
     async fn get_account_info(
@@ -66,9 +104,51 @@ pub trait MercuryRpc {
         payload: SimpleTransferPayload,
     ) -> RpcResult<TransactionCompletionResponse>;
 
+    #[method(name = "build_batch_payout")]
+    async fn build_batch_payout(
+        &self,
+        payload: BatchPayoutPayload,
+    ) -> RpcResult<Vec<TransactionCompletionResponse>>;
+
+    #[method(name = "build_sweep_transaction")]
+    async fn build_sweep_transaction(
+        &self,
+        payload: SweepPayload,
+    ) -> RpcResult<SweepTransactionResponse>;
+
+    #[method(name = "build_consolidate_transaction")]
+    async fn build_consolidate_transaction(
+        &self,
+        payload: ConsolidatePayload,
+    ) -> RpcResult<TransactionCompletionResponse>;
+
     #[method(name = "register_addresses")]
     async fn register_addresses(&self, addresses: Vec<String>) -> RpcResult<Vec<H160>>;
 
+    #[method(name = "set_address_labels")]
+    async fn set_address_labels(
+        &self,
+        payload: Vec<SetAddressLabelPayload>,
+    ) -> RpcResult<Vec<JsonItem>>;
+
+    #[method(name = "get_address_labels")]
+    async fn get_address_labels(&self, items: Vec<JsonItem>) -> RpcResult<Vec<AddressLabel>>;
+
+    #[method(name = "search_address_labels")]
+    async fn search_address_labels(
+        &self,
+        payload: SearchAddressLabelsPayload,
+    ) -> RpcResult<PaginationResponse<AddressLabel>>;
+
+    #[method(name = "register_webhook")]
+    async fn register_webhook(&self, payload: RegisterWebhookPayload) -> RpcResult<Uint64>;
+
+    #[method(name = "get_webhooks")]
+    async fn get_webhooks(&self) -> RpcResult<Vec<Webhook>>;
+
+    #[method(name = "remove_webhook")]
+    async fn remove_webhook(&self, id: Uint64) -> RpcResult<bool>;
+
     #[method(name = "get_mercury_info")]
     async fn get_mercury_info(&self) -> RpcResult<MercuryInfo>;
 
@@ -93,6 +173,24 @@ pub trait MercuryRpc {
         payload: DaoClaimPayload,
     ) -> RpcResult<TransactionCompletionResponse>;
 
+    #[method(name = "build_cheque_claim_transaction")]
+    async fn build_cheque_claim_transaction(
+        &self,
+        payload: ChequeClaimPayload,
+    ) -> RpcResult<ChequeTransactionResponse>;
+
+    #[method(name = "build_cheque_withdraw_transaction")]
+    async fn build_cheque_withdraw_transaction(
+        &self,
+        payload: ChequeWithdrawPayload,
+    ) -> RpcResult<ChequeTransactionResponse>;
+
+    #[method(name = "verify_transaction")]
+    async fn verify_transaction(
+        &self,
+        payload: VerifyTransactionPayload,
+    ) -> RpcResult<VerifyTransactionResponse>;
+
     #[method(name = "build_sudt_issue_transaction")]
     async fn build_sudt_issue_transaction(
         &self,
@@ -103,6 +201,21 @@ pub trait MercuryRpc {
     async fn get_spent_transaction(&self, payload: GetSpentTransactionPayload)
         -> RpcResult<TxView>;
 
+    #[method(name = "send_transaction")]
+    async fn send_transaction(&self, payload: SendTransactionPayload) -> RpcResult<H256>;
+
+    #[method(name = "release_input_reservation")]
+    async fn release_input_reservation(
+        &self,
+        payload: ReleaseInputReservationPayload,
+    ) -> RpcResult<Vec<OutPoint>>;
+
+    #[method(name = "get_submitted_transactions")]
+    async fn get_submitted_transactions(
+        &self,
+        payload: GetSubmittedTransactionsPayload,
+    ) -> RpcResult<PaginationResponse<SubmittedTransaction>>;
+
     #[method(name = "get_tip")]
     async fn get_tip(&self) -> RpcResult<Option<indexer::Tip>>;
 
@@ -166,3 +279,20 @@ pub trait MercuryRpc {
     #[method(name = "report_pprof")]
     async fn report_pprof(&self) -> RpcResult<()>;
 }
+
+#[rpc(server)]
+pub trait MercurySubscription {
+    #[subscription(
+        name = "subscribe_new_tip",
+        unsubscribe = "unsubscribe_new_tip",
+        item = indexer::Tip
+    )]
+    fn subscribe_new_tip(&self);
+
+    #[subscription(
+        name = "subscribe_item_events",
+        unsubscribe = "unsubscribe_item_events",
+        item = ItemEvent
+    )]
+    fn subscribe_item_events(&self, item: JsonItem);
+}
@@ -0,0 +1,193 @@
+use crate::r#impl::address_to_script;
+use crate::{error::CoreError, InnerResult, MercurySubscriptionServer};
+
+use ckb_types::{packed, prelude::*, H160, H256};
+use common::hash::blake2b_256_to_160;
+use common::lazy::{
+    ACP_CODE_HASH, CHEQUE_CODE_HASH, OMNI_LOCK_CODE_HASH, PW_LOCK_CODE_HASH, SECP256K1_CODE_HASH,
+};
+use common::{Address, AddressPayload};
+use core_rpc_types::{indexer, ChainEvent, Identity, IdentityFlag, Item, ItemEvent, JsonItem};
+use jsonrpsee::core::server::rpc_module::SubscriptionSink;
+use jsonrpsee::types::error::ErrorObject;
+use jsonrpsee::types::SubscriptionResult;
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use std::convert::TryInto;
+use std::str::FromStr;
+
+pub struct MercurySubscriptionImpl {
+    chain_event_sender: broadcast::Sender<ChainEvent>,
+}
+
+impl MercurySubscriptionServer for MercurySubscriptionImpl {
+    fn subscribe_new_tip(&self, mut sink: SubscriptionSink) -> SubscriptionResult {
+        sink.accept()?;
+
+        let receiver = self.chain_event_sender.subscribe();
+        tokio::spawn(async move {
+            forward_events(receiver, sink, |event| vec![event.tip]).await;
+        });
+
+        Ok(())
+    }
+
+    fn subscribe_item_events(
+        &self,
+        mut sink: SubscriptionSink,
+        item: JsonItem,
+    ) -> SubscriptionResult {
+        let matcher = match ItemMatcher::new(item) {
+            Ok(matcher) => matcher,
+            Err(err) => {
+                let _ = sink.reject(ErrorObject::owned(
+                    err.0.err_code(),
+                    err.0.message(),
+                    None::<()>,
+                ));
+                return Ok(());
+            }
+        };
+        sink.accept()?;
+
+        let receiver = self.chain_event_sender.subscribe();
+        tokio::spawn(async move {
+            forward_events(receiver, sink, |event| {
+                event
+                    .item_events
+                    .into_iter()
+                    .filter(|item_event| matcher.is_match(&item_event.cell))
+                    .collect::<Vec<ItemEvent>>()
+            })
+            .await;
+        });
+
+        Ok(())
+    }
+}
+
+impl MercurySubscriptionImpl {
+    pub fn new(chain_event_sender: broadcast::Sender<ChainEvent>) -> Self {
+        MercurySubscriptionImpl { chain_event_sender }
+    }
+}
+
+async fn forward_events<T, F>(
+    mut receiver: broadcast::Receiver<ChainEvent>,
+    mut sink: SubscriptionSink,
+    select: F,
+) where
+    T: Serialize,
+    F: Fn(ChainEvent) -> Vec<T>,
+{
+    loop {
+        match receiver.recv().await {
+            Ok(event) => {
+                for notification in select(event).iter() {
+                    match sink.send(notification) {
+                        Ok(true) => (),
+                        Ok(false) => return,
+                        Err(e) => {
+                            log::error!("serialize subscription notification error {:?}", e);
+                            return;
+                        }
+                    }
+                }
+            }
+
+            Err(RecvError::Lagged(skipped)) => {
+                log::warn!("subscription lagged, {} chain events skipped", skipped);
+            }
+
+            Err(RecvError::Closed) => return,
+        }
+    }
+}
+
+/// Decides whether a created or consumed cell belongs to the subscribed item.
+/// The item is parsed once when subscribing so that matching a cell does not
+/// touch the database.
+pub(crate) enum ItemMatcher {
+    Identity(IdentityFlag, H160),
+    Lock(packed::Script),
+    OutPoint(packed::OutPoint),
+}
+
+impl ItemMatcher {
+    pub(crate) fn new(json_item: JsonItem) -> InnerResult<Self> {
+        let item: Item = json_item.try_into()?;
+        match item {
+            Item::Identity(ident) => {
+                let (flag, pubkey_hash) = ident.parse()?;
+                match flag {
+                    IdentityFlag::Ckb
+                    | IdentityFlag::Ethereum
+                    | IdentityFlag::Tron
+                    | IdentityFlag::Bitcoin
+                    | IdentityFlag::Dogecoin => Ok(ItemMatcher::Identity(flag, pubkey_hash)),
+                    _ => Err(CoreError::UnsupportIdentityFlag.into()),
+                }
+            }
+            Item::Address(addr) => {
+                let address = Address::from_str(&addr).map_err(CoreError::ParseAddressError)?;
+                Ok(ItemMatcher::Lock(address_to_script(address.payload())))
+            }
+            Item::OutPoint(out_point) => Ok(ItemMatcher::OutPoint(out_point.into())),
+        }
+    }
+
+    fn is_match(&self, cell: &indexer::Cell) -> bool {
+        self.is_cell_match(
+            &cell.output.lock.clone().into(),
+            &cell.out_point.clone().into(),
+        )
+    }
+
+    pub(crate) fn is_cell_match(
+        &self,
+        lock: &packed::Script,
+        out_point: &packed::OutPoint,
+    ) -> bool {
+        match self {
+            ItemMatcher::Identity(flag, pubkey_hash) => is_identity_lock(flag, pubkey_hash, lock),
+            ItemMatcher::Lock(script) => lock == script,
+            ItemMatcher::OutPoint(item_out_point) => out_point == item_out_point,
+        }
+    }
+}
+
+fn is_identity_lock(flag: &IdentityFlag, pubkey_hash: &H160, lock: &packed::Script) -> bool {
+    let code_hash: H256 = lock.code_hash().unpack();
+    let args = lock.args().raw_data();
+
+    if Some(&code_hash) == OMNI_LOCK_CODE_HASH.get() {
+        let identity = Identity::new(flag.clone(), pubkey_hash.clone());
+        return args.len() >= 21 && args[0..21] == identity.0;
+    }
+
+    match flag {
+        IdentityFlag::Ckb => {
+            if Some(&code_hash) == SECP256K1_CODE_HASH.get()
+                || Some(&code_hash) == ACP_CODE_HASH.get()
+            {
+                return args.len() >= 20 && args[0..20] == pubkey_hash.0;
+            }
+
+            if Some(&code_hash) == CHEQUE_CODE_HASH.get() {
+                let secp_lock =
+                    address_to_script(&AddressPayload::from_pubkey_hash(pubkey_hash.clone()));
+                let lock_hash: H256 = secp_lock.calc_script_hash().unpack();
+                let lock_hash_160 = blake2b_256_to_160(&lock_hash);
+                return args.len() >= 40
+                    && (args[0..20] == lock_hash_160.0 || args[20..40] == lock_hash_160.0);
+            }
+
+            false
+        }
+        IdentityFlag::Ethereum => {
+            Some(&code_hash) == PW_LOCK_CODE_HASH.get() && args[..] == pubkey_hash.0
+        }
+        _ => false,
+    }
+}
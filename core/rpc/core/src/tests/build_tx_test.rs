@@ -0,0 +1,714 @@
+use super::*;
+
+use crate::r#impl::{build_tx, utils};
+
+use ckb_jsonrpc_types::OutPoint;
+use common::{DetailedCell, MULTISIG_TYPE_HASH, OMNI_LOCK};
+use core_rpc_types::consts::MAX_SWEEP_INPUT_NUM;
+use core_rpc_types::error::RpcError;
+use core_rpc_types::lazy::{CURRENT_EPOCH_NUMBER, INPUT_RESERVATIONS};
+use core_rpc_types::{
+    AssetInfo, BatchPayoutPayload, ChequeClaimPayload, ChequeWithdrawPayload, ConsolidatePayload,
+    MultisigConfig, OutputCapacityProvider, SweepPayload, ToInfo,
+};
+use tokio::test;
+
+use std::convert::TryInto;
+
+#[test]
+async fn test_build_lock_placeholder() {
+    let engine = RpcTestEngine::new().await;
+    let _ = engine.rpc(NetworkType::Testnet);
+
+    let addr = "ckt1qyqr79tnk3pp34xp92gerxjc4p3mus2690psf0dd70";
+    let secp_lock = address_to_script(parse_address(addr).unwrap().payload());
+    assert_eq!(
+        Bytes::from(vec![0u8; 65]),
+        build_tx::build_lock_placeholder(&secp_lock, &HashMap::new())
+    );
+
+    let mut args = secp_lock.args().raw_data().to_vec();
+    args.insert(0, 0);
+    args.push(0);
+    let omni_lock = engine
+        .script_map
+        .get(OMNI_LOCK)
+        .unwrap()
+        .script
+        .clone()
+        .as_builder()
+        .args(Bytes::from(args).pack())
+        .build();
+    let placeholder = build_tx::build_lock_placeholder(&omni_lock, &HashMap::new());
+    assert_eq!(85, placeholder.len());
+    let read_u32 = |index: usize| {
+        u32::from_le_bytes(placeholder[index * 4..index * 4 + 4].try_into().unwrap())
+    };
+    // total size and the offsets of signature, omni_identity and preimage
+    assert_eq!(85, read_u32(0));
+    assert_eq!(16, read_u32(1));
+    assert_eq!(85, read_u32(2));
+    assert_eq!(85, read_u32(3));
+    let signature = packed::Bytes::from_slice(&placeholder[16..]).unwrap();
+    assert_eq!(Bytes::from(vec![0u8; 65]), signature.raw_data());
+
+    let config = MultisigConfig {
+        require_first_n: 0,
+        threshold: 2,
+        pubkey_hashes: vec![
+            h160!("0x1111111111111111111111111111111111111111"),
+            h160!("0x2222222222222222222222222222222222222222"),
+            h160!("0x3333333333333333333333333333333333333333"),
+        ],
+    };
+    let multisig_script = utils::build_multisig_script(&config).unwrap();
+    let script_hash = blake2b_160(&multisig_script);
+    let multisig_lock = packed::Script::new_builder()
+        .code_hash(MULTISIG_TYPE_HASH.pack())
+        .hash_type(ScriptHashType::Type.into())
+        .args(Bytes::from(script_hash.to_vec()).pack())
+        .build();
+    let mut multisig_scripts = HashMap::new();
+    let placeholder = build_tx::build_lock_placeholder(&multisig_lock, &multisig_scripts);
+    assert_eq!(Bytes::from(vec![0u8; 65]), placeholder);
+
+    multisig_scripts.insert(H160(script_hash), multisig_script.clone());
+    let placeholder = build_tx::build_lock_placeholder(&multisig_lock, &multisig_scripts);
+    assert_eq!(multisig_script.len() + 2 * 65, placeholder.len());
+    assert_eq!(&multisig_script[..], &placeholder[..multisig_script.len()]);
+    assert!(placeholder[multisig_script.len()..]
+        .iter()
+        .all(|byte| *byte == 0));
+}
+
+fn to_packed_tx(tx_view: &ckb_jsonrpc_types::TransactionView) -> TransactionView {
+    packed::Transaction::from(tx_view.inner.clone()).into_view()
+}
+
+#[test]
+async fn test_build_cheque_claim_transaction() {
+    let mut engine = RpcTestEngine::init_data(vec![
+        AddressData::new(SECP_ADDRESS_1, 200, 0, 0, 100),
+        AddressData::new(SECP_ADDRESS_2, 100, 0, 0, 0),
+    ])
+    .await;
+    let short_cheque_out_point = append_short_cheque_cell(&mut engine).await;
+    let rpc = engine.rpc(NetworkType::Testnet);
+
+    let payload = ChequeClaimPayload {
+        from: to_identity_item(SECP_ADDRESS_1),
+        out_points: vec![],
+        claim_to: None,
+        fee_rate: None,
+        verify: None,
+    };
+    let response = rpc
+        .build_cheque_claim_transaction(payload.clone())
+        .await
+        .unwrap();
+    assert_eq!(1, response.cheque_cells.len());
+    assert_ne!(
+        short_cheque_out_point,
+        response.cheque_cells[0].clone().into()
+    );
+
+    let tx = to_packed_tx(&response.tx_view);
+    let receiver_lock = address_to_script(parse_address(SECP_ADDRESS_1).unwrap().payload());
+    let sender_lock = address_to_script(parse_address(SECP_ADDRESS_2).unwrap().payload());
+    let outputs = tx.outputs().into_iter().collect::<Vec<_>>();
+    let returned_output = outputs
+        .iter()
+        .find(|output| output.lock() == sender_lock)
+        .unwrap();
+    let returned_capacity: u64 = returned_output.capacity().unpack();
+    assert_eq!(CHEQUE_CELL_CAPACITY, returned_capacity);
+    let (index, _) = outputs
+        .iter()
+        .enumerate()
+        .find(|(_, output)| {
+            output.lock() == receiver_lock
+                && output.type_().as_slice() == Some(engine.sudt_script.clone()).pack().as_slice()
+        })
+        .unwrap();
+    assert_eq!(
+        Some(100),
+        decode_udt_amount(&tx.outputs_data().get(index).unwrap().raw_data())
+    );
+
+    let payload = ChequeClaimPayload {
+        out_points: vec![short_cheque_out_point.into()],
+        ..payload
+    };
+    let res = rpc.build_cheque_claim_transaction(payload).await;
+    assert!(res.unwrap_err().to_string().contains("10131"));
+}
+
+#[test]
+async fn test_build_cheque_withdraw_transaction() {
+    let mut engine = RpcTestEngine::init_data(vec![
+        AddressData::new(SECP_ADDRESS_1, 0, 0, 0, 100),
+        AddressData::new(SECP_ADDRESS_2, 100, 0, 0, 0),
+    ])
+    .await;
+    let short_cheque_out_point = append_short_cheque_cell(&mut engine).await;
+    let rpc = engine.rpc(NetworkType::Testnet);
+
+    let payload = ChequeWithdrawPayload {
+        from: to_identity_item(SECP_ADDRESS_2),
+        out_points: vec![],
+        fee_rate: None,
+        verify: None,
+    };
+    CURRENT_EPOCH_NUMBER.swap(Arc::new(RationalU256::from_u256(10u64.into())));
+    let response = rpc
+        .build_cheque_withdraw_transaction(payload.clone())
+        .await
+        .unwrap();
+    assert_eq!(1, response.cheque_cells.len());
+
+    let tx = to_packed_tx(&response.tx_view);
+    let sender_lock = address_to_script(parse_address(SECP_ADDRESS_2).unwrap().payload());
+    let (index, withdrawn_output) = tx
+        .outputs()
+        .into_iter()
+        .enumerate()
+        .find(|(_, output)| output.type_().is_some())
+        .unwrap();
+    assert_eq!(sender_lock, withdrawn_output.lock());
+    assert_eq!(
+        Some(100),
+        decode_udt_amount(&tx.outputs_data().get(index).unwrap().raw_data())
+    );
+    // the cheque input waits for the cheque since
+    let cheque_input = tx
+        .inputs()
+        .into_iter()
+        .find(|input| input.previous_output() == response.cheque_cells[0].clone().into())
+        .unwrap();
+    assert_ne!(0u64, Unpack::<u64>::unpack(&cheque_input.since()));
+
+    let res = rpc
+        .build_cheque_withdraw_transaction(ChequeWithdrawPayload {
+            out_points: vec![short_cheque_out_point.into()],
+            ..payload.clone()
+        })
+        .await;
+    assert!(res.unwrap_err().to_string().contains("10131"));
+
+    // the cheque cells are not withdrawable before the timeout
+    CURRENT_EPOCH_NUMBER.swap(Arc::new(RationalU256::zero()));
+    let res = rpc.build_cheque_withdraw_transaction(payload).await;
+    assert!(res.unwrap_err().to_string().contains("10130"));
+}
+
+#[test]
+async fn test_input_reservation() {
+    let engine = RpcTestEngine::init_data(vec![
+        AddressData::new(SECP_ADDRESS_1, 100, 0, 0, 0),
+        AddressData::new(SECP_ADDRESS_2, 200, 0, 0, 0),
+        AddressData::new(SECP_ADDRESS_2, 201, 0, 0, 0),
+    ])
+    .await;
+    let rpc = engine.rpc_with_input_reservation(NetworkType::Testnet, 60);
+    let payload = TransferPayload {
+        asset_info: AssetInfo::new_ckb(),
+        from: vec![JsonItem::Address(SECP_ADDRESS_2.to_string())],
+        to: vec![ToInfo {
+            address: SECP_ADDRESS_1.to_string(),
+            amount: ((100 * BYTE_SHANNONS) as u128).into(),
+        }],
+        output_capacity_provider: Some(OutputCapacityProvider::From),
+        pay_fee: None,
+        fee_rate: None,
+        since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
+    };
+
+    // the second build does not take the inputs of the first one
+    let first = rpc
+        .build_transfer_transaction(payload.clone())
+        .await
+        .unwrap();
+    let second = rpc
+        .build_transfer_transaction(payload.clone())
+        .await
+        .unwrap();
+    let (first, second) = (to_packed_tx(&first.tx_view), to_packed_tx(&second.tx_view));
+    let inputs = first
+        .input_pts_iter()
+        .chain(second.input_pts_iter())
+        .collect::<Vec<_>>();
+    assert_eq!(2, inputs.iter().collect::<HashSet<_>>().len());
+    assert!(inputs
+        .iter()
+        .all(|input| INPUT_RESERVATIONS.read().is_reserved(input)));
+
+    // all cells are reserved
+    let res = rpc.build_transfer_transaction(payload.clone()).await;
+    assert!(res.is_err());
+
+    // a failed build releases the cells it has taken
+    for tx in [&first, &second] {
+        INPUT_RESERVATIONS
+            .write()
+            .release_transaction(&tx.hash().unpack());
+    }
+    let res = rpc
+        .build_transfer_transaction(TransferPayload {
+            to: vec![ToInfo {
+                address: SECP_ADDRESS_1.to_string(),
+                amount: ((1_000 * BYTE_SHANNONS) as u128).into(),
+            }],
+            ..payload
+        })
+        .await;
+    assert!(res.is_err());
+    assert!(inputs
+        .iter()
+        .all(|input| !INPUT_RESERVATIONS.read().is_reserved(input)));
+}
+
+#[test]
+async fn test_take_unreserved() {
+    let engine = RpcTestEngine::new().await;
+    let rpc = engine.rpc_with_input_reservation(NetworkType::Testnet, 60);
+    let out_point = packed::OutPoint::new(rand_h256().pack(), 0);
+    let (build_id, other_build_id) = (utils::new_build_id(), utils::new_build_id());
+
+    // the cell is reserved by the build taking it at once
+    let taken = utils::BUILD_ID
+        .scope(build_id.clone(), async { rpc.take_unreserved(&out_point) })
+        .await;
+    assert!(taken);
+    let taken_again = utils::BUILD_ID
+        .scope(build_id.clone(), async { rpc.take_unreserved(&out_point) })
+        .await;
+    assert!(taken_again);
+    let taken_by_other = utils::BUILD_ID
+        .scope(other_build_id, async { rpc.take_unreserved(&out_point) })
+        .await;
+    assert!(!taken_by_other);
+    assert!(!rpc.take_unreserved(&out_point));
+
+    INPUT_RESERVATIONS.write().release_transaction(&build_id);
+    assert!(rpc.take_unreserved(&out_point));
+}
+
+fn payout_output_num(tx: &TransactionView, amount: u64) -> usize {
+    let lock = address_to_script(parse_address(SECP_ADDRESS_1).unwrap().payload());
+    tx.outputs()
+        .into_iter()
+        .filter(|output| {
+            output.lock() == lock
+                && Unpack::<u64>::unpack(&output.capacity()) == amount * BYTE_SHANNONS
+        })
+        .count()
+}
+
+#[test]
+async fn test_build_batch_payout() {
+    let engine = RpcTestEngine::init_data(
+        (0..5)
+            .map(|i| AddressData::new(SECP_ADDRESS_2, 610 + i, 0, 0, 0))
+            .collect(),
+    )
+    .await;
+    let rpc = engine.rpc(NetworkType::Testnet);
+    let payload = BatchPayoutPayload {
+        asset_info: AssetInfo::new_ckb(),
+        from: vec![JsonItem::Address(SECP_ADDRESS_2.to_string())],
+        to: vec![
+            ToInfo {
+                address: SECP_ADDRESS_1.to_string(),
+                amount: ((500 * BYTE_SHANNONS) as u128).into(),
+            };
+            4
+        ],
+        output_capacity_provider: Some(OutputCapacityProvider::From),
+        pay_fee: None,
+        fee_rate: None,
+        since: None,
+        multisig_configs: None,
+        max_outputs_per_tx: Some(1u32.into()),
+        max_tx_size: None,
+        coin_selection: None,
+        verify: None,
+    };
+
+    let responses = rpc.build_batch_payout(payload.clone()).await.unwrap();
+    assert_eq!(4, responses.len());
+    let txs = responses
+        .iter()
+        .map(|response| to_packed_tx(&response.tx_view))
+        .collect::<Vec<_>>();
+    assert!(txs.iter().all(|tx| payout_output_num(tx, 500) == 1));
+
+    // no two transactions share an input
+    let inputs = txs
+        .iter()
+        .flat_map(|tx| tx.input_pts_iter())
+        .collect::<Vec<_>>();
+    assert_eq!(4, inputs.len());
+    assert_eq!(4, inputs.iter().collect::<HashSet<_>>().len());
+
+    // the inputs are released after the batch if the input reservation is disabled
+    assert!(inputs
+        .iter()
+        .all(|input| !INPUT_RESERVATIONS.read().is_reserved(input)));
+
+    // the fifth cell can not pay one more recipient
+    let res = rpc
+        .build_batch_payout(BatchPayoutPayload {
+            to: vec![payload.to[0].clone(); 6],
+            ..payload
+        })
+        .await;
+    assert!(res.is_err());
+    assert!(inputs
+        .iter()
+        .all(|input| !INPUT_RESERVATIONS.read().is_reserved(input)));
+}
+
+#[test]
+async fn test_build_batch_payout_split_oversized_transaction() {
+    let engine =
+        RpcTestEngine::init_data(vec![AddressData::new(SECP_ADDRESS_2, 710, 0, 0, 0)]).await;
+    let rpc = engine.rpc(NetworkType::Testnet);
+    let payload = BatchPayoutPayload {
+        asset_info: AssetInfo::new_ckb(),
+        from: vec![JsonItem::Address(SECP_ADDRESS_2.to_string())],
+        to: vec![
+            ToInfo {
+                address: SECP_ADDRESS_1.to_string(),
+                amount: ((100 * BYTE_SHANNONS) as u128).into(),
+            };
+            4
+        ],
+        output_capacity_provider: Some(OutputCapacityProvider::From),
+        pay_fee: None,
+        fee_rate: None,
+        since: None,
+        multisig_configs: None,
+        max_outputs_per_tx: Some(4u32.into()),
+        max_tx_size: None,
+        coin_selection: None,
+        verify: None,
+    };
+
+    let responses = rpc.build_batch_payout(payload.clone()).await.unwrap();
+    assert_eq!(1, responses.len());
+    let tx = to_packed_tx(&responses[0].tx_view);
+    assert_eq!(4, payout_output_num(&tx, 100));
+    let tx_size = build_tx::calculate_tx_size(&tx) as u64;
+
+    // the oversized transaction is split into halves
+    let engine = RpcTestEngine::init_data(vec![
+        AddressData::new(SECP_ADDRESS_2, 720, 0, 0, 0),
+        AddressData::new(SECP_ADDRESS_2, 721, 0, 0, 0),
+    ])
+    .await;
+    let rpc = engine.rpc(NetworkType::Testnet);
+    let responses = rpc
+        .build_batch_payout(BatchPayoutPayload {
+            max_tx_size: Some((tx_size - 1).into()),
+            ..payload.clone()
+        })
+        .await
+        .unwrap();
+    assert_eq!(2, responses.len());
+    let txs = responses
+        .iter()
+        .map(|response| to_packed_tx(&response.tx_view))
+        .collect::<Vec<_>>();
+    for tx in txs.iter() {
+        assert_eq!(2, payout_output_num(tx, 100));
+        assert!((build_tx::calculate_tx_size(tx) as u64) < tx_size);
+    }
+    assert!(txs[0]
+        .input_pts_iter()
+        .all(|input| !txs[1].input_pts_iter().any(|other| other == input)));
+
+    // a transaction paying a single recipient can not be split
+    let res = rpc
+        .build_batch_payout(BatchPayoutPayload {
+            max_tx_size: Some(100u64.into()),
+            ..payload
+        })
+        .await;
+    assert!(res.unwrap_err().to_string().contains("11034"));
+}
+
+fn output_capacity_of(tx: &TransactionView, address: &str) -> u64 {
+    let lock = address_to_script(parse_address(address).unwrap().payload());
+    tx.outputs()
+        .into_iter()
+        .filter(|output| output.lock() == lock)
+        .map(|output| Unpack::<u64>::unpack(&output.capacity()))
+        .sum()
+}
+
+#[test]
+async fn test_build_sweep_transaction() {
+    let engine = RpcTestEngine::init_data(vec![
+        AddressData::new(SECP_ADDRESS_1, 100, 0, 0, 0),
+        AddressData::new(SECP_ADDRESS_2, 310, 100, 50, 0),
+        AddressData::new(SECP_ADDRESS_2, 311, 0, 0, 0),
+    ])
+    .await;
+    let rpc = engine.rpc(NetworkType::Testnet);
+    let payload = SweepPayload {
+        asset_info: AssetInfo::new_ckb(),
+        from: vec![JsonItem::Address(SECP_ADDRESS_2.to_string())],
+        to: SECP_ADDRESS_1.to_string(),
+        fee_rate: None,
+        since: None,
+        multisig_configs: None,
+        verify: None,
+    };
+
+    let response = rpc.build_sweep_transaction(payload.clone()).await.unwrap();
+    assert!(!response.partial);
+    let tx = to_packed_tx(&response.tx_view);
+    // the capacity of the UDT cells is not swept
+    assert_eq!(2, tx.inputs().len());
+    assert_eq!(1, tx.outputs().len());
+    let swept_capacity = output_capacity_of(&tx, SECP_ADDRESS_1);
+    assert!(swept_capacity < 621 * BYTE_SHANNONS);
+    assert!(swept_capacity > 620 * BYTE_SHANNONS);
+
+    // the identity covers both the secp and the ACP UDT cells
+    let udt_info = AssetInfo::new_udt(engine.sudt_script.calc_script_hash().unpack());
+    let response = rpc
+        .build_sweep_transaction(SweepPayload {
+            asset_info: udt_info,
+            from: vec![to_identity_item(SECP_ADDRESS_2)],
+            ..payload
+        })
+        .await
+        .unwrap();
+    assert!(!response.partial);
+    let tx = to_packed_tx(&response.tx_view);
+    let receiver_lock = address_to_script(parse_address(SECP_ADDRESS_1).unwrap().payload());
+    let udt_amount = tx
+        .outputs()
+        .into_iter()
+        .zip(tx.outputs_data().into_iter())
+        .filter(|(output, _)| output.lock() == receiver_lock)
+        .filter_map(|(_, data)| decode_udt_amount(&data.raw_data()))
+        .sum::<u128>();
+    assert_eq!(150, udt_amount);
+}
+
+#[test]
+async fn test_build_partial_sweep_transaction() {
+    let mut data = vec![AddressData::new(SECP_ADDRESS_1, 100, 0, 0, 0)];
+    data.extend(
+        (0..MAX_SWEEP_INPUT_NUM as u64 + 1)
+            .map(|i| AddressData::new(SECP_ADDRESS_2, 1_000 + i, 0, 0, 0)),
+    );
+    let engine = RpcTestEngine::init_data(data).await;
+    let rpc = engine.rpc(NetworkType::Testnet);
+
+    let response = rpc
+        .build_sweep_transaction(SweepPayload {
+            asset_info: AssetInfo::new_ckb(),
+            from: vec![JsonItem::Address(SECP_ADDRESS_2.to_string())],
+            to: SECP_ADDRESS_1.to_string(),
+            fee_rate: None,
+            since: None,
+            multisig_configs: None,
+            verify: None,
+        })
+        .await
+        .unwrap();
+    assert!(response.partial);
+    assert_eq!(
+        MAX_SWEEP_INPUT_NUM,
+        to_packed_tx(&response.tx_view).inputs().len()
+    );
+}
+
+#[test]
+async fn test_build_consolidate_transaction() {
+    let engine = RpcTestEngine::init_data(vec![
+        AddressData::new(SECP_ADDRESS_2, 410, 0, 0, 0),
+        AddressData::new(SECP_ADDRESS_2, 411, 0, 0, 0),
+        AddressData::new(SECP_ADDRESS_2, 510, 0, 0, 0),
+    ])
+    .await;
+    let rpc = engine.rpc(NetworkType::Testnet);
+    let payload = ConsolidatePayload {
+        asset_info: AssetInfo::new_ckb(),
+        item: JsonItem::Address(SECP_ADDRESS_2.to_string()),
+        threshold: None,
+        max_inputs: None,
+        fee_rate: None,
+        multisig_configs: None,
+        verify: None,
+    };
+
+    let response = rpc
+        .build_consolidate_transaction(ConsolidatePayload {
+            max_inputs: Some(2.into()),
+            ..payload.clone()
+        })
+        .await
+        .unwrap();
+    let tx = to_packed_tx(&response.tx_view);
+    assert_eq!(2, tx.inputs().len());
+    assert_eq!(1, tx.outputs().len());
+
+    let response = rpc
+        .build_consolidate_transaction(ConsolidatePayload {
+            threshold: Some((450 * BYTE_SHANNONS as u128).into()),
+            ..payload.clone()
+        })
+        .await
+        .unwrap();
+    let tx = to_packed_tx(&response.tx_view);
+    assert_eq!(2, tx.inputs().len());
+    let merged_capacity = output_capacity_of(&tx, SECP_ADDRESS_2);
+    assert!(merged_capacity < 821 * BYTE_SHANNONS);
+    assert!(merged_capacity > 820 * BYTE_SHANNONS);
+
+    // only one cell is below the threshold
+    let res = rpc
+        .build_consolidate_transaction(ConsolidatePayload {
+            threshold: Some((411 * BYTE_SHANNONS as u128).into()),
+            ..payload
+        })
+        .await;
+    assert!(res.unwrap_err().to_string().contains("11035"));
+}
+
+#[test]
+async fn test_build_udt_consolidate_transaction() {
+    let engine = RpcTestEngine::init_data(vec![
+        AddressData::new(SECP_ADDRESS_2, 810, 130, 10, 0),
+        AddressData::new(SECP_ADDRESS_2, 0, 131, 11, 0),
+        AddressData::new(SECP_ADDRESS_2, 0, 132, 0, 0),
+    ])
+    .await;
+    let rpc = engine.rpc(NetworkType::Testnet);
+    let udt_info = AssetInfo::new_udt(engine.sudt_script.calc_script_hash().unpack());
+    let sender_lock = address_to_script(parse_address(SECP_ADDRESS_2).unwrap().payload());
+
+    let acp_lock = engine.acp_builder().args(sender_lock.args()).build();
+
+    // the ACP cells of the identity are merged into one ACP cell
+    let response = rpc
+        .build_consolidate_transaction(ConsolidatePayload {
+            asset_info: udt_info,
+            item: to_identity_item(SECP_ADDRESS_2),
+            threshold: None,
+            max_inputs: None,
+            fee_rate: None,
+            multisig_configs: None,
+            verify: None,
+        })
+        .await
+        .unwrap();
+    let tx = to_packed_tx(&response.tx_view);
+    let merged = tx
+        .outputs()
+        .into_iter()
+        .zip(tx.outputs_data().into_iter())
+        .filter(|(output, _)| output.type_().is_some())
+        .map(|(output, data)| {
+            let capacity: u64 = output.capacity().unpack();
+            (output.lock(), capacity, decode_udt_amount(&data.raw_data()))
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(2, merged.len());
+    assert_eq!((sender_lock, Some(393)), (merged[0].0.clone(), merged[0].2));
+    assert_eq!((acp_lock, 2 * STANDARD_SUDT_CAPACITY, Some(21)), merged[1]);
+}
+
+fn new_dao_cell(capacity: u64, block_number: u64) -> DetailedCell {
+    DetailedCell {
+        epoch_number: 0,
+        block_number,
+        block_hash: H256::default(),
+        tx_index: 1,
+        out_point: packed::OutPoint::new(rand_h256().pack(), 0),
+        cell_output: packed::CellOutput::new_builder()
+            .capacity((capacity * BYTE_SHANNONS).pack())
+            .build(),
+        cell_data: Bytes::from(vec![0u8; 8]),
+        consumed_block_number: None,
+        consumed_block_hash: None,
+        consumed_tx_hash: None,
+        consumed_tx_index: None,
+        consumed_input_index: None,
+        since: None,
+    }
+}
+
+fn out_points_of(cells: &[DetailedCell]) -> Vec<packed::OutPoint> {
+    cells.iter().map(|cell| cell.out_point.clone()).collect()
+}
+
+#[test]
+async fn test_select_dao_cells() {
+    let cells = vec![
+        new_dao_cell(1_000, 1),
+        new_dao_cell(2_000, 2),
+        new_dao_cell(3_000, 3),
+    ];
+    // the cells deposited before the block 3 are eligible
+    let is_eligible = |cell: &DetailedCell| cell.block_number < 3;
+
+    let selected = build_tx::select_dao_cells(cells.clone(), None, is_eligible, "").unwrap();
+    assert_eq!(out_points_of(&cells[0..2]), out_points_of(&selected));
+
+    // the specified out point only, whose duplicates are ignored
+    let out_point: OutPoint = cells[1].out_point.clone().into();
+    let selected = build_tx::select_dao_cells(
+        cells.clone(),
+        Some(vec![out_point.clone(), out_point]),
+        is_eligible,
+        "",
+    )
+    .unwrap();
+    assert_eq!(out_points_of(&cells[1..2]), out_points_of(&selected));
+
+    let res = build_tx::select_dao_cells(
+        cells.clone(),
+        Some(vec![cells[2].out_point.clone().into()]),
+        is_eligible,
+        "is not eligible",
+    );
+    let err = res.unwrap_err();
+    assert_eq!(-10072, err.0.err_code());
+    assert!(err.to_string().contains("is not eligible"));
+
+    let unknown = packed::OutPoint::new(rand_h256().pack(), 0);
+    let res = build_tx::select_dao_cells(cells, Some(vec![unknown.into()]), is_eligible, "");
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .contains("is not a live dao cell of the items"));
+}
+
+#[test]
+async fn test_take_dao_cells_for_amount() {
+    let cells = vec![
+        new_dao_cell(3_000, 3),
+        new_dao_cell(1_000, 1),
+        new_dao_cell(2_000, 2),
+    ];
+
+    // the earliest cells are taken until the amount is reached
+    let taken = build_tx::take_dao_cells_for_amount(cells.clone(), 1_500 * BYTE_SHANNONS).unwrap();
+    assert_eq!(
+        vec![cells[1].out_point.clone(), cells[2].out_point.clone()],
+        out_points_of(&taken)
+    );
+    let taken = build_tx::take_dao_cells_for_amount(cells.clone(), 1_000 * BYTE_SHANNONS).unwrap();
+    assert_eq!(vec![cells[1].out_point.clone()], out_points_of(&taken));
+
+    let res = build_tx::take_dao_cells_for_amount(cells, 6_001 * BYTE_SHANNONS);
+    assert!(res.is_err());
+}
@@ -1,9 +1,12 @@
 #![allow(dead_code, unused_imports)]
 
+mod build_tx_test;
 mod operation_test;
 mod query_test;
 mod rpc_test;
+mod subscription_test;
 mod utils_test;
+mod verify_tx_test;
 
 use crate::{
     r#impl::{address_to_script, load_code_hash},
@@ -25,16 +28,16 @@ use core_rpc_types::consts::{BYTE_SHANNONS, CHEQUE_CELL_CAPACITY, STANDARD_SUDT_
 use core_rpc_types::{
     AdjustAccountPayload, BlockInfo, DaoDepositPayload, DaoWithdrawPayload, GetBalancePayload,
     GetBalanceResponse, GetBlockInfoPayload, GetSpentTransactionPayload,
-    GetTransactionInfoResponse, MercuryInfo, QueryTransactionsPayload, SimpleTransferPayload,
-    StructureType, SyncState, TransactionCompletionResponse, TransactionStatus, TransferPayload,
-    TxView,
+    GetTransactionInfoResponse, Identity, IdentityFlag, JsonItem, MercuryInfo,
+    QueryTransactionsPayload, SimpleTransferPayload, StructureType, SyncState,
+    TransactionCompletionResponse, TransactionStatus, TransferPayload, TxView,
 };
 use core_storage::{DBDriver, RelationalStorage, Storage};
 
 use ckb_jsonrpc_types::Status as JsonTransactionStatus;
 use ckb_types::core::{
-    capacity_bytes, BlockBuilder, BlockView, Capacity, HeaderBuilder, RationalU256, ScriptHashType,
-    TransactionBuilder, TransactionView,
+    capacity_bytes, BlockBuilder, BlockView, Capacity, EpochNumberWithFraction, HeaderBuilder,
+    RationalU256, ScriptHashType, TransactionBuilder, TransactionView,
 };
 use ckb_types::{bytes::Bytes, h160, h256, packed, prelude::*, H160, H256};
 use parking_lot::RwLock;
@@ -49,6 +52,8 @@ const TESTNET_CONFIG: &str = "../../../devtools/config/testnet_config.toml";
 const OUTPUT_FILE: &str = "../../../free-space/output.json";
 const NETWORK_TYPE: NetworkType = NetworkType::Testnet;
 const MEMORY_DB: &str = ":memory:";
+const SECP_ADDRESS_1: &str = "ckt1qyqr79tnk3pp34xp92gerxjc4p3mus2690psf0dd70";
+const SECP_ADDRESS_2: &str = "ckt1qyqd5eyygtdmwdr7ge736zw6z0ju6wsw7rssu8fcve";
 
 lazy_static::lazy_static! {
     pub static ref CELLBASE_ADDRESS: Address =
@@ -171,6 +176,7 @@ impl RpcTestEngine {
 
     pub async fn init_data(data: Vec<AddressData>) -> Self {
         let mut engine = RpcTestEngine::new().await;
+        load_code_hash(&engine.script_map);
 
         let cellbase = TransactionBuilder::default()
             .input(packed::CellInput::new_cellbase_input(0))
@@ -296,6 +302,14 @@ impl RpcTestEngine {
     }
 
     pub fn rpc(&self, net_ty: NetworkType) -> MercuryRpcImpl<CkbRpcClient> {
+        self.rpc_with_input_reservation(net_ty, 0)
+    }
+
+    pub fn rpc_with_input_reservation(
+        &self,
+        net_ty: NetworkType,
+        input_reservation_ttl: u64,
+    ) -> MercuryRpcImpl<CkbRpcClient> {
         MercuryRpcImpl::new(
             self.store.clone(),
             self.script_map.clone(),
@@ -306,6 +320,8 @@ impl RpcTestEngine {
             Arc::new(RwLock::new(SyncState::ReadOnly)),
             100u16,
             true,
+            vec![],
+            input_reservation_ttl,
         )
     }
 
@@ -333,20 +349,41 @@ impl RpcTestEngine {
 }
 
 fn cheque_args(receiver: &AddressPayload) -> packed::Bytes {
-    let sender = blake2b_160(
-        address_to_script(
-            parse_address("ckt1qyqd5eyygtdmwdr7ge736zw6z0ju6wsw7rssu8fcve")
-                .unwrap()
-                .payload(),
-        )
-        .as_slice(),
-    );
+    let sender =
+        blake2b_160(address_to_script(parse_address(SECP_ADDRESS_2).unwrap().payload()).as_slice());
 
     let mut ret = blake2b_160(address_to_script(receiver).as_slice()).to_vec();
     ret.extend_from_slice(&sender);
     ret.pack()
 }
 
+// A cell of the cheque lock whose args only hold the receiver lock hash.
+pub async fn append_short_cheque_cell(engine: &mut RpcTestEngine) -> packed::OutPoint {
+    let args = cheque_args(parse_address(SECP_ADDRESS_1).unwrap().payload()).raw_data();
+    let tx = TransactionBuilder::default()
+        .output(
+            packed::CellOutputBuilder::default()
+                .capacity(CHEQUE_CELL_CAPACITY.pack())
+                .type_(Some(engine.sudt_script.clone()).pack())
+                .lock(engine.cheque_builder().args(args[0..20].pack()).build())
+                .build(),
+        )
+        .output_data(100u128.to_le_bytes().to_vec().pack())
+        .build();
+    let out_point = packed::OutPoint::new(tx.hash(), 0);
+    let epoch = EpochNumberWithFraction::new(0, 1, 1000).full_value();
+    engine
+        .append(RpcTestEngine::new_block(vec![tx], 1, epoch))
+        .await;
+    out_point
+}
+
+pub fn to_identity_item(address: &str) -> JsonItem {
+    let lock = address_to_script(parse_address(address).unwrap().payload());
+    let pubkey_hash = H160::from_slice(&lock.args().raw_data()).unwrap();
+    JsonItem::Identity(hex::encode(Identity::new(IdentityFlag::Ckb, pubkey_hash).0))
+}
+
 #[derive(Clone, Debug)]
 pub struct AddressData {
     addr: String,
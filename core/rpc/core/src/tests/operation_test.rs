@@ -1,5 +1,13 @@
 use super::*;
 
+use common::{MercuryError, PaginationRequest};
+use core_ckb_client::error::{ClientError, POOL_REJECTED_DUPLICATED_TRANSACTION};
+use core_ckb_client::CkbRpc;
+use core_rpc_types::{SendTransactionPayload, SubmittedTransaction};
+
+use ckb_jsonrpc_types::{
+    EpochView, LocalNode, OutputsValidator, RawTxPool, TransactionWithStatus, Uint64,
+};
 use tokio::test;
 
 #[test]
@@ -43,3 +51,411 @@ async fn test_address() {
         "3419a1c09eb2567f6552ee7a8ecffd64155cffe0f1796e6e61ec088d740c1356".to_string()
     );
 }
+
+#[test]
+async fn test_webhook() {
+    use crate::r#impl::webhook::{sign_webhook_payload, WEBHOOK_SIGNATURE_HEADER};
+    use common::utils::unix_timestamp_millis;
+    use core_rpc_types::{JsonItem, RegisterWebhookPayload, WebhookEvent, WebhookEventType};
+    use std::io::{Read, Write};
+
+    let engine = RpcTestEngine::new().await;
+    let rpc = engine.rpc(NetworkType::Testnet);
+
+    // a receiver answering a single request, which returns the raw request
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/events", listener.local_addr().unwrap());
+    let receiver = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = vec![];
+        let mut buf = [0u8; 4096];
+        loop {
+            let len = stream.read(&mut buf).unwrap();
+            request.extend_from_slice(&buf[..len]);
+            let text = String::from_utf8_lossy(&request).to_string();
+            if let Some(pos) = text.find("\r\n\r\n") {
+                let content_length = text[..pos]
+                    .lines()
+                    .find_map(|line| {
+                        line.to_lowercase()
+                            .strip_prefix("content-length:")
+                            .map(|len| len.trim().parse::<usize>().unwrap())
+                    })
+                    .unwrap_or_default();
+                if request.len() >= pos + 4 + content_length || len == 0 {
+                    break;
+                }
+            }
+        }
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+            .unwrap();
+        String::from_utf8(request).unwrap()
+    });
+
+    let addr = "ckt1qyqr79tnk3pp34xp92gerxjc4p3mus2690psf0dd70";
+    let id = rpc
+        .register_webhook(RegisterWebhookPayload {
+            url: url.clone(),
+            items: vec![JsonItem::Address(addr.to_string())],
+            secret: "secret".to_string(),
+        })
+        .await
+        .unwrap();
+    let webhooks = rpc.get_webhooks().await.unwrap();
+    assert_eq!(webhooks.len(), 1);
+    assert_eq!(webhooks[0].id, id);
+    assert_eq!(webhooks[0].url, url);
+
+    let invalid = rpc
+        .register_webhook(RegisterWebhookPayload {
+            url: "ftp://127.0.0.1/events".to_string(),
+            items: vec![JsonItem::Address(addr.to_string())],
+            secret: "secret".to_string(),
+        })
+        .await;
+    assert!(invalid.is_err());
+
+    let cellbase = TransactionBuilder::default()
+        .input(packed::CellInput::new_cellbase_input(0))
+        .witness(packed::Script::default().into_witness())
+        .output(
+            packed::CellOutputBuilder::default()
+                .capacity(capacity_bytes!(1_000_000_000).pack())
+                .lock(CELLBASE_ADDRESS.clone().payload().into())
+                .build(),
+        )
+        .output_data(Default::default())
+        .build();
+    let tx = TransactionBuilder::default()
+        .output(
+            packed::CellOutputBuilder::default()
+                .capacity((100 * BYTE_SHANNONS).pack())
+                .lock(parse_address(addr).unwrap().payload().into())
+                .build(),
+        )
+        .output_data(Default::default())
+        .build();
+    let tx_hash: H256 = tx.hash().unpack();
+    let block = RpcTestEngine::new_block(vec![cellbase, tx], 0, 0);
+    let deliveries = rpc
+        .build_webhook_deliveries(&block, WebhookEventType::BlockAppended)
+        .await
+        .unwrap();
+    assert_eq!(deliveries.len(), 1);
+    engine
+        .store
+        .append_block_with_webhook_deliveries(block, deliveries, unix_timestamp_millis())
+        .await
+        .unwrap();
+    let delivered = rpc.deliver_webhook_events().await.unwrap();
+    assert_eq!(delivered, 1);
+    assert_eq!(rpc.deliver_webhook_events().await.unwrap(), 0);
+
+    let request = receiver.join().unwrap();
+    let (headers, body) = request.split_once("\r\n\r\n").unwrap();
+    let signature = format!(
+        "{}: {}",
+        WEBHOOK_SIGNATURE_HEADER.to_lowercase(),
+        sign_webhook_payload("secret", body)
+    );
+    assert!(headers.to_lowercase().contains(&signature));
+
+    let event: WebhookEvent = serde_json::from_str(body).unwrap();
+    assert_eq!(event.webhook_id, id);
+    assert_eq!(event.event_type, WebhookEventType::BlockAppended);
+    assert_eq!(event.transactions.len(), 1);
+    assert_eq!(event.transactions[0].tx_hash, tx_hash);
+    assert_eq!(event.transactions[0].records.len(), 1);
+    assert_eq!(
+        event.transactions[0].records[0].amount,
+        (100 * BYTE_SHANNONS as u128).into()
+    );
+
+    assert!(rpc.remove_webhook(id).await.unwrap());
+    assert!(rpc.get_webhooks().await.unwrap().is_empty());
+}
+
+#[test]
+async fn test_webhook_deliveries_with_block() {
+    use core_rpc_types::{JsonItem, RegisterWebhookPayload, WebhookEvent, WebhookEventType};
+
+    let mut engine = RpcTestEngine::new().await;
+    let rpc = engine.rpc(NetworkType::Testnet);
+
+    let addr = "ckt1qyqr79tnk3pp34xp92gerxjc4p3mus2690psf0dd70";
+    let other_addr = "ckt1qyq2y6jdkynen2vx946tnsdw2dgucvv7ph0s8n4kfd";
+    rpc.register_webhook(RegisterWebhookPayload {
+        url: "http://127.0.0.1/events".to_string(),
+        items: vec![JsonItem::Address(addr.to_string())],
+        secret: "secret".to_string(),
+    })
+    .await
+    .unwrap();
+
+    let new_tx = |input: Option<packed::OutPoint>, addr: &str, capacity: u64| {
+        let builder = TransactionBuilder::default()
+            .output(
+                packed::CellOutputBuilder::default()
+                    .capacity((capacity * BYTE_SHANNONS).pack())
+                    .lock(parse_address(addr).unwrap().payload().into())
+                    .build(),
+            )
+            .output_data(Default::default());
+        match input {
+            Some(out_point) => builder.input(packed::CellInput::new(out_point, 0)),
+            None => builder,
+        }
+        .build()
+    };
+    let fund_tx = new_tx(None, addr, 100);
+    engine
+        .append(RpcTestEngine::new_block(
+            vec![
+                RpcTestEngine::build_cellbase_tx(other_addr, 1_000),
+                fund_tx.clone(),
+            ],
+            0,
+            0,
+        ))
+        .await;
+    // the second transfer spends the change created by the first one in the same block
+    let transfer_tx = new_tx(Some(packed::OutPoint::new(fund_tx.hash(), 0)), addr, 90);
+    let next_transfer_tx = new_tx(
+        Some(packed::OutPoint::new(transfer_tx.hash(), 0)),
+        other_addr,
+        80,
+    );
+    let block = RpcTestEngine::new_block(
+        vec![
+            RpcTestEngine::build_cellbase_tx(other_addr, 1_001),
+            transfer_tx.clone(),
+            next_transfer_tx.clone(),
+        ],
+        1,
+        EpochNumberWithFraction::new(0, 1, 1000).full_value(),
+    );
+
+    let get_events = || async {
+        engine
+            .store
+            .get_pending_webhook_deliveries(0, 10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|delivery| {
+                let event = serde_json::from_str::<WebhookEvent>(&delivery.payload).unwrap();
+                (delivery.id, event)
+            })
+            .collect::<Vec<_>>()
+    };
+    let record_nums = |event: &WebhookEvent| {
+        event
+            .transactions
+            .iter()
+            .map(|tx| (tx.tx_hash.clone(), tx.records.len()))
+            .collect::<Vec<_>>()
+    };
+    let expected_record_nums = vec![
+        (transfer_tx.hash().unpack(), 2),
+        (next_transfer_tx.hash().unpack(), 1),
+    ];
+
+    let deliveries = rpc
+        .build_webhook_deliveries(&block, WebhookEventType::BlockAppended)
+        .await
+        .unwrap();
+    engine
+        .store
+        .append_block_with_webhook_deliveries(block.clone(), deliveries, 0)
+        .await
+        .unwrap();
+    let events = get_events().await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].1.event_type, WebhookEventType::BlockAppended);
+    assert_eq!(record_nums(&events[0].1), expected_record_nums);
+    let appended_id = events[0].0;
+
+    let deliveries = rpc
+        .build_webhook_deliveries(&block, WebhookEventType::BlockReverted)
+        .await
+        .unwrap();
+    engine
+        .store
+        .rollback_block_with_webhook_deliveries(1, block.hash().unpack(), deliveries, 0)
+        .await
+        .unwrap();
+    // the reverted event waits for the appended one of the same webhook
+    assert_eq!(get_events().await[0].0, appended_id);
+    engine
+        .store
+        .complete_webhook_delivery(appended_id)
+        .await
+        .unwrap();
+    let events = get_events().await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].1.event_type, WebhookEventType::BlockReverted);
+    assert_eq!(record_nums(&events[0].1), expected_record_nums);
+}
+
+/// A CKB node which refuses `send_transaction` with the error code, or accepts the
+/// transaction if it is `None`.
+struct SendTransactionNode {
+    error_code: Option<i64>,
+}
+
+#[async_trait]
+impl CkbRpc for SendTransactionNode {
+    async fn local_node_info(&self) -> Result<LocalNode> {
+        unimplemented!()
+    }
+
+    async fn get_tip_block_number(&self) -> Result<u64> {
+        unimplemented!()
+    }
+
+    async fn get_raw_tx_pool(&self, _verbose: Option<bool>) -> Result<RawTxPool> {
+        unimplemented!()
+    }
+
+    async fn get_transactions(
+        &self,
+        _hashes: Vec<H256>,
+    ) -> Result<Vec<Option<TransactionWithStatus>>> {
+        unimplemented!()
+    }
+
+    async fn get_blocks_by_number(
+        &self,
+        _block_number: Vec<u64>,
+    ) -> Result<Vec<Option<ckb_jsonrpc_types::BlockView>>> {
+        unimplemented!()
+    }
+
+    async fn get_epoch_by_number(&self, _epoch_number: Uint64) -> Result<EpochView> {
+        unimplemented!()
+    }
+
+    async fn get_current_epoch(&self) -> Result<EpochView> {
+        unimplemented!()
+    }
+
+    async fn get_block(
+        &self,
+        _block_hash: H256,
+        _use_hex_format: bool,
+    ) -> Result<Option<ckb_jsonrpc_types::BlockView>> {
+        unimplemented!()
+    }
+
+    async fn send_transaction(
+        &self,
+        tx: ckb_jsonrpc_types::Transaction,
+        _outputs_validator: Option<OutputsValidator>,
+    ) -> Result<H256> {
+        match self.error_code {
+            Some(code) => Err(MercuryError::rpc(ClientError::NodeError {
+                code,
+                message: "refused".to_string(),
+            })
+            .into()),
+            None => Ok(packed::Transaction::from(tx).calc_tx_hash().unpack()),
+        }
+    }
+}
+
+fn node_rpc(
+    engine: &RpcTestEngine,
+    error_code: Option<i64>,
+) -> MercuryRpcImpl<SendTransactionNode> {
+    MercuryRpcImpl::new(
+        engine.store.clone(),
+        engine.script_map.clone(),
+        SendTransactionNode { error_code },
+        NetworkType::Testnet,
+        RationalU256::from_u256(6u64.into()),
+        RationalU256::from_u256(6u64.into()),
+        Arc::new(RwLock::new(SyncState::ReadOnly)),
+        100u16,
+        true,
+        vec![],
+        0,
+    )
+}
+
+async fn submitted_status(engine: &RpcTestEngine, tx_hash: &H256) -> SubmittedTransaction {
+    engine
+        .get_db()
+        .get_submitted_transactions(vec![tx_hash.clone()], vec![], PaginationRequest::default())
+        .await
+        .unwrap()
+        .response
+        .pop()
+        .unwrap()
+}
+
+#[test]
+async fn test_send_transaction() {
+    let engine = RpcTestEngine::new().await;
+    let tx = TransactionBuilder::default()
+        .output(
+            packed::CellOutputBuilder::default()
+                .capacity(capacity_bytes!(100).pack())
+                .build(),
+        )
+        .output_data(Default::default())
+        .build();
+    let tx_hash: H256 = tx.hash().unpack();
+    let payload = SendTransactionPayload {
+        transaction: tx.data().into(),
+        outputs_validator: None,
+    };
+
+    // whether an unreachable node received the transaction is unknown
+    let unreachable = engine.rpc(NetworkType::Testnet);
+    assert!(unreachable.send_transaction(payload.clone()).await.is_err());
+    let submitted = submitted_status(&engine, &tx_hash).await;
+    assert_eq!(TransactionStatus::Unknown, submitted.status);
+    assert!(submitted.reason.is_some());
+
+    let refusing = node_rpc(&engine, Some(-301));
+    assert!(refusing.send_transaction(payload.clone()).await.is_err());
+    let submitted = submitted_status(&engine, &tx_hash).await;
+    assert_eq!(TransactionStatus::Rejected, submitted.status);
+    assert!(submitted.reason.unwrap().contains("refused"));
+
+    // a duplicate submission means the transaction is in the tx pool
+    let duplicated = node_rpc(&engine, Some(POOL_REJECTED_DUPLICATED_TRANSACTION));
+    assert_eq!(
+        tx_hash,
+        duplicated.send_transaction(payload.clone()).await.unwrap()
+    );
+    let submitted = submitted_status(&engine, &tx_hash).await;
+    assert_eq!(TransactionStatus::Pending, submitted.status);
+    assert_eq!(None, submitted.reason);
+
+    // a refusal of the node does not reject the pending transaction
+    assert!(refusing.send_transaction(payload).await.is_err());
+    let submitted = submitted_status(&engine, &tx_hash).await;
+    assert_eq!(TransactionStatus::Pending, submitted.status);
+    assert_eq!(None, submitted.reason);
+
+    let other_tx = tx
+        .as_advanced_builder()
+        .output_data(Default::default())
+        .build();
+    let other_tx_hash: H256 = other_tx.hash().unpack();
+    let other_payload = SendTransactionPayload {
+        transaction: other_tx.data().into(),
+        outputs_validator: None,
+    };
+    let accepting = node_rpc(&engine, None);
+    assert_eq!(
+        other_tx_hash,
+        accepting.send_transaction(other_payload).await.unwrap()
+    );
+    assert_eq!(
+        TransactionStatus::Pending,
+        submitted_status(&engine, &other_tx_hash).await.status
+    );
+}
@@ -1,6 +1,15 @@
 use super::*;
 
-use core_rpc_types::StructureType;
+use core_rpc_types::consts::WITHDRAWING_DAO_CELL_OCCUPIED_CAPACITY;
+use core_rpc_types::{
+    AccountStatement, AssetInfo, AssetType, BalanceBreakdown, DaoPosition,
+    GetAccountStatementPayload, GetDaoPositionsPayload, PaginationRequest, Range, StatementEntry,
+    StatementFormat, StructureType,
+};
+
+use common::Order;
+
+use ckb_dao_utils::pack_dao_data;
 
 use tokio::test;
 
@@ -33,3 +42,668 @@ async fn test_get_spent_transaction() {
     assert!(res.is_err());
     assert!(res.unwrap_err().to_string().contains("10090"))
 }
+
+#[test]
+async fn test_get_balance_and_query_transactions_with_tx_pool() {
+    use core_rpc_types::lazy::{TX_POOL, TX_POOL_CACHE};
+    use core_rpc_types::tx_pool::{PoolCell, PoolTransaction, TxPool};
+    use core_rpc_types::{AssetInfo, JsonItem, PaginationRequest};
+
+    let mut engine = RpcTestEngine::new().await;
+    let rpc = engine.rpc(NetworkType::Testnet);
+
+    let new_address = |args: &[u8]| {
+        Address::new(
+            NetworkType::Testnet,
+            packed::ScriptBuilder::default()
+                .code_hash(H256([7u8; 32]).pack())
+                .hash_type(ScriptHashType::Data.into())
+                .args(Bytes::from(args.to_vec()).pack())
+                .build()
+                .into(),
+            false,
+        )
+    };
+    let sender = new_address(b"pool_sender");
+    let receiver = new_address(b"pool_receiver");
+
+    let cellbase = TransactionBuilder::default()
+        .input(packed::CellInput::new_cellbase_input(0))
+        .witness(packed::Script::default().into_witness())
+        .output(
+            packed::CellOutputBuilder::default()
+                .capacity(capacity_bytes!(1_000_000_000).pack())
+                .lock(CELLBASE_ADDRESS.clone().payload().into())
+                .build(),
+        )
+        .output_data(Default::default())
+        .build();
+    let deposit = TransactionBuilder::default()
+        .output(
+            packed::CellOutputBuilder::default()
+                .capacity(capacity_bytes!(100).pack())
+                .lock(sender.payload().into())
+                .build(),
+        )
+        .output_data(Default::default())
+        .build();
+    let deposit_out_point = packed::OutPoint::new(deposit.hash(), 0);
+    let deposit_output = deposit.outputs().get(0).unwrap();
+    engine
+        .append(RpcTestEngine::new_block(vec![cellbase, deposit], 0, 0))
+        .await;
+
+    let pool_tx = TransactionBuilder::default()
+        .input(packed::CellInput::new(deposit_out_point.clone(), 0))
+        .output(
+            packed::CellOutputBuilder::default()
+                .capacity(capacity_bytes!(90).pack())
+                .lock(receiver.payload().into())
+                .build(),
+        )
+        .output_data(Default::default())
+        .build();
+    *TX_POOL.write() = TxPool::new(vec![PoolTransaction {
+        transaction: pool_tx.clone(),
+        status: JsonTransactionStatus::Pending,
+        input_cells: vec![PoolCell {
+            out_point: deposit_out_point.clone(),
+            output: deposit_output,
+            data: Bytes::new(),
+        }],
+    }]);
+    TX_POOL_CACHE.write().insert(deposit_out_point);
+
+    let get_balance = |address: &Address| GetBalancePayload {
+        item: JsonItem::Address(address.to_string()),
+        asset_infos: vec![AssetInfo::new_ckb()].into_iter().collect(),
+        extra: None,
+        tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
+    };
+    let sender_balance = rpc.get_balance(get_balance(&sender)).await.unwrap();
+    assert_eq!(sender_balance.balances.len(), 1);
+    // 52 bytes of the deposit cell are occupied
+    assert_eq!(
+        sender_balance.balances[0].free,
+        (48 * BYTE_SHANNONS as u128).into()
+    );
+    assert_eq!(
+        sender_balance.balances[0].pending_outgoing,
+        (100 * BYTE_SHANNONS as u128).into()
+    );
+    let receiver_balance = rpc.get_balance(get_balance(&receiver)).await.unwrap();
+    assert_eq!(receiver_balance.balances.len(), 1);
+    assert_eq!(receiver_balance.balances[0].free, 0u128.into());
+    assert_eq!(
+        receiver_balance.balances[0].pending_incoming,
+        (90 * BYTE_SHANNONS as u128).into()
+    );
+
+    let query_transactions = |include_pending: Option<bool>| QueryTransactionsPayload {
+        item: JsonItem::Address(receiver.to_string()),
+        asset_infos: HashSet::new(),
+        extra: None,
+        block_range: None,
+        time_range: None,
+        counterparty: None,
+        direction: None,
+        amount_range: None,
+        pagination: PaginationRequest::default(),
+        structure_type: StructureType::Native,
+        with_labels: None,
+        min_confirmations: None,
+        include_pending,
+    };
+    let txs = rpc
+        .query_transactions(query_transactions(None))
+        .await
+        .unwrap();
+    assert!(txs.response.is_empty());
+    assert!(txs.pending.is_empty());
+    let txs = rpc
+        .query_transactions(query_transactions(Some(true)))
+        .await
+        .unwrap();
+    assert!(txs.response.is_empty());
+    assert_eq!(txs.pending.len(), 1);
+    match &txs.pending[0] {
+        TxView::TransactionWithRichStatus(tx) => {
+            assert_eq!(tx.tx_status.status, JsonTransactionStatus::Pending);
+            let tx_hash: H256 = pool_tx.hash().unpack();
+            assert_eq!(tx.transaction.as_ref().unwrap().hash, tx_hash);
+        }
+        _ => panic!("expect a native transaction"),
+    }
+
+    *TX_POOL.write() = TxPool::default();
+    TX_POOL_CACHE.write().clear();
+}
+
+#[test]
+async fn test_get_cheques() {
+    use core_rpc_types::{AssetInfo, GetChequesPayload, PaginationRequest};
+
+    let mut engine = RpcTestEngine::init_data(vec![
+        AddressData::new(SECP_ADDRESS_1, 100, 0, 0, 100),
+        AddressData::new(SECP_ADDRESS_2, 100, 0, 0, 0),
+    ])
+    .await;
+    append_short_cheque_cell(&mut engine).await;
+    let rpc = engine.rpc(NetworkType::Testnet);
+
+    let get_cheques = |item: JsonItem, asset_infos: HashSet<AssetInfo>| GetChequesPayload {
+        item,
+        asset_infos,
+        pagination: PaginationRequest::default(),
+    };
+    let to_lock = |address: &str| address_to_script(parse_address(address).unwrap().payload());
+
+    // the cell without the sender lock hash in its args is skipped
+    let cheques = rpc
+        .get_cheques(get_cheques(
+            JsonItem::Address(SECP_ADDRESS_1.to_string()),
+            HashSet::new(),
+        ))
+        .await
+        .unwrap()
+        .response;
+    assert_eq!(1, cheques.len());
+    let cheque = &cheques[0];
+    assert_eq!(to_lock(SECP_ADDRESS_2), to_lock(&cheque.sender));
+    assert_eq!(to_lock(SECP_ADDRESS_1), to_lock(&cheque.receiver));
+    assert_eq!(
+        AssetInfo::new_udt(SUDT_HASH.read().clone()),
+        cheque.asset_info
+    );
+    assert_eq!(100u128, cheque.amount.into());
+    assert_eq!(CHEQUE_CELL_CAPACITY, cheque.capacity.value());
+
+    // the sender finds the cheque by its identity
+    let sender_cheques = rpc
+        .get_cheques(get_cheques(
+            to_identity_item(SECP_ADDRESS_2),
+            vec![AssetInfo::new_udt(SUDT_HASH.read().clone())]
+                .into_iter()
+                .collect(),
+        ))
+        .await
+        .unwrap()
+        .response;
+    assert_eq!(cheques, sender_cheques);
+
+    let other_udt_cheques = rpc
+        .get_cheques(get_cheques(
+            JsonItem::Address(SECP_ADDRESS_1.to_string()),
+            vec![AssetInfo::new_udt(rand_h256())].into_iter().collect(),
+        ))
+        .await
+        .unwrap()
+        .response;
+    assert!(other_udt_cheques.is_empty());
+}
+
+/// The target epoch duration of the testnet consensus in milliseconds.
+const EPOCH_DURATION_MS: u64 = 4 * 60 * 60 * 1000;
+
+fn new_dao_block(
+    miner: &str,
+    txs: Vec<TransactionView>,
+    number: u64,
+    epoch: EpochNumberWithFraction,
+    timestamp: u64,
+    ar: u64,
+) -> BlockView {
+    let cellbase = RpcTestEngine::build_cellbase_tx(miner, 1_000 + number);
+    let dao = pack_dao_data(ar, Capacity::zero(), Capacity::zero(), Capacity::zero());
+    let header = HeaderBuilder::default()
+        .number(number.pack())
+        .epoch(epoch.full_value().pack())
+        .timestamp(timestamp.pack())
+        .dao(dao)
+        .build();
+    BlockBuilder::default()
+        .transaction(cellbase)
+        .transactions(txs)
+        .header(header)
+        .build()
+}
+
+async fn get_sender_dao_positions(rpc: &MercuryRpcImpl<CkbRpcClient>) -> Vec<DaoPosition> {
+    let mut positions = rpc
+        .get_dao_positions(GetDaoPositionsPayload {
+            item: JsonItem::Address(SECP_ADDRESS_2.to_string()),
+            pagination: PaginationRequest::new(None, Order::Asc, Some(10), false),
+        })
+        .await
+        .unwrap()
+        .response;
+    positions.sort_by_key(|position| position.capacity.value());
+    positions
+}
+
+#[test]
+async fn test_get_dao_positions() {
+    let mut engine = RpcTestEngine::new().await;
+    load_code_hash(&engine.script_map);
+    let rpc = engine.rpc(NetworkType::Testnet);
+
+    let lock = address_to_script(parse_address(SECP_ADDRESS_2).unwrap().payload());
+    let dao_type = engine.script_map.get(DAO).unwrap().script.clone();
+    let dao_output = |capacity: u64| {
+        packed::CellOutputBuilder::default()
+            .capacity((capacity * BYTE_SHANNONS).pack())
+            .lock(lock.clone())
+            .type_(Some(dao_type.clone()).pack())
+            .build()
+    };
+    let deposit_tx = TransactionBuilder::default()
+        .output(dao_output(1_000))
+        .output_data(Bytes::from(vec![0u8; 8]).pack())
+        .output(dao_output(2_000))
+        .output_data(Bytes::from(vec![0u8; 8]).pack())
+        .build();
+    let deposit_epoch = EpochNumberWithFraction::new(5, 0, 1000);
+    let ar = 10_000_000_000_000_000;
+    engine
+        .append(new_dao_block(
+            SECP_ADDRESS_1,
+            vec![deposit_tx.clone()],
+            1,
+            deposit_epoch,
+            1_000_000,
+            ar,
+        ))
+        .await;
+
+    // the unlock epoch of a deposit in the tip epoch is a whole lock period later
+    let positions = get_sender_dao_positions(&rpc).await;
+    assert_eq!(2, positions.len());
+    let deposit = &positions[0];
+    assert_eq!(0, deposit.compensation.value());
+    assert_eq!(
+        EpochNumberWithFraction::new(185, 0, 1000).full_value(),
+        deposit.unlock_epoch_number.value()
+    );
+    assert_eq!(
+        1_000_000 + 180 * EPOCH_DURATION_MS,
+        deposit.estimated_unlock_timestamp.value()
+    );
+    assert!(!deposit.claimable);
+
+    let withdraw_tx = TransactionBuilder::default()
+        .input(packed::CellInput::new(
+            packed::OutPoint::new(deposit_tx.hash(), 1),
+            0,
+        ))
+        .output(dao_output(2_000))
+        .output_data(Bytes::from(1u64.to_le_bytes().to_vec()).pack())
+        .build();
+    engine
+        .append(new_dao_block(
+            SECP_ADDRESS_1,
+            vec![withdraw_tx],
+            2,
+            EpochNumberWithFraction::new(100, 500, 1000),
+            2_000_000,
+            ar / 10 * 11,
+        ))
+        .await;
+
+    let positions = get_sender_dao_positions(&rpc).await;
+    let (deposit, withdrawing) = (&positions[0], &positions[1]);
+    let compensation = (1_000 * BYTE_SHANNONS - WITHDRAWING_DAO_CELL_OCCUPIED_CAPACITY) / 10;
+    assert_eq!(compensation, deposit.compensation.value());
+    assert_eq!(
+        EpochNumberWithFraction::new(185, 0, 1000).full_value(),
+        deposit.unlock_epoch_number.value()
+    );
+    // 84.5 epochs from the tip epoch 100.5
+    assert_eq!(
+        2_000_000 + 84 * EPOCH_DURATION_MS + EPOCH_DURATION_MS / 2,
+        deposit.estimated_unlock_timestamp.value()
+    );
+    assert_eq!(Some(2u64.into()), withdrawing.withdraw_block_number);
+    assert_eq!(
+        EpochNumberWithFraction::new(185, 0, 1000).full_value(),
+        withdrawing.unlock_epoch_number.value()
+    );
+    assert!(!withdrawing.claimable);
+
+    engine
+        .append(new_dao_block(
+            SECP_ADDRESS_1,
+            vec![],
+            3,
+            EpochNumberWithFraction::new(185, 0, 1000),
+            3_000_000,
+            ar / 10 * 12,
+        ))
+        .await;
+
+    // the deposit reaching the end of a lock period unlocks in the next period, while
+    // the reached unlock epoch is estimated as the tip timestamp
+    let positions = get_sender_dao_positions(&rpc).await;
+    let (deposit, withdrawing) = (&positions[0], &positions[1]);
+    assert_eq!(
+        EpochNumberWithFraction::new(365, 0, 1000).full_value(),
+        deposit.unlock_epoch_number.value()
+    );
+    assert_eq!(
+        3_000_000 + 180 * EPOCH_DURATION_MS,
+        deposit.estimated_unlock_timestamp.value()
+    );
+    assert!(!deposit.claimable);
+    assert_eq!(3_000_000, withdrawing.estimated_unlock_timestamp.value());
+    assert!(withdrawing.claimable);
+    // the compensation of the withdrawing cell stops at the withdraw block
+    let compensation = (2_000 * BYTE_SHANNONS - WITHDRAWING_DAO_CELL_OCCUPIED_CAPACITY) / 10;
+    assert_eq!(compensation, withdrawing.compensation.value());
+}
+
+#[test]
+async fn test_get_balance_breakdown() {
+    let mut engine = RpcTestEngine::new().await;
+    load_code_hash(&engine.script_map);
+    let rpc = engine.rpc(NetworkType::Testnet);
+
+    let lock = address_to_script(parse_address(SECP_ADDRESS_2).unwrap().payload());
+    let acp_lock = engine
+        .acp_builder()
+        .args(lock.args().raw_data().pack())
+        .build();
+    let new_output = |capacity: u64, lock: &packed::Script, type_: Option<packed::Script>| {
+        packed::CellOutputBuilder::default()
+            .capacity((capacity * BYTE_SHANNONS).pack())
+            .lock(lock.clone())
+            .type_(type_.pack())
+            .build()
+    };
+    let dao_type = engine.script_map.get(DAO).unwrap().script.clone();
+    let udt_data = Bytes::from(100u128.to_le_bytes().to_vec()).pack();
+    let tx = TransactionBuilder::default()
+        .output(new_output(1_000, &lock, None))
+        .output_data(Default::default())
+        .output(new_output(2_000, &lock, Some(dao_type)))
+        .output_data(Bytes::from(vec![0u8; 8]).pack())
+        .output(new_output(142, &lock, Some(engine.sudt_script.clone())))
+        .output_data(udt_data.clone())
+        .output(new_output(143, &acp_lock, Some(engine.sudt_script.clone())))
+        .output_data(udt_data)
+        .output(new_output(100, &acp_lock, None))
+        .output_data(Default::default())
+        .build();
+    // the cellbase of the sender is immature
+    engine
+        .append(new_dao_block(
+            SECP_ADDRESS_2,
+            vec![tx],
+            1,
+            EpochNumberWithFraction::new(1, 0, 1000),
+            1_000_000,
+            10_000_000_000_000_000,
+        ))
+        .await;
+
+    let balances = rpc
+        .get_balance(GetBalancePayload {
+            item: to_identity_item(SECP_ADDRESS_2),
+            asset_infos: HashSet::new(),
+            extra: None,
+            tip_block_number: Some(1u64.into()),
+            detailed: Some(true),
+            min_confirmations: None,
+        })
+        .await
+        .unwrap()
+        .balances;
+    let ckb_balances = balances
+        .iter()
+        .filter(|balance| balance.asset_info.asset_type == AssetType::CKB)
+        .collect::<Vec<_>>();
+    assert!(!ckb_balances.is_empty());
+    for balance in balances.iter() {
+        let breakdown = balance.breakdown.as_ref().unwrap();
+        assert_eq!(
+            balance.frozen.value(),
+            breakdown.immature_cellbase.value()
+                + breakdown.dao_deposited.value()
+                + breakdown.dao_withdrawing_locked.value()
+                + breakdown.since_locked.value()
+                + breakdown.pending_cheque.value()
+                + breakdown.unknown_type.value()
+        );
+        assert_eq!(
+            balance.occupied.value(),
+            breakdown.acp_occupied.value()
+                + breakdown.udt_occupied.value()
+                + breakdown.other_occupied.value()
+        );
+    }
+
+    let sum = |field: fn(&BalanceBreakdown) -> u128| {
+        ckb_balances
+            .iter()
+            .map(|balance| field(balance.breakdown.as_ref().unwrap()))
+            .sum::<u128>()
+    };
+    assert_eq!(
+        1_001 * BYTE_SHANNONS as u128,
+        sum(|breakdown| breakdown.immature_cellbase.value())
+    );
+    assert_eq!(
+        (2_000 - 102) * BYTE_SHANNONS as u128,
+        sum(|breakdown| breakdown.dao_deposited.value())
+    );
+    assert_eq!(
+        102 * BYTE_SHANNONS as u128,
+        sum(|breakdown| breakdown.other_occupied.value())
+    );
+    assert_eq!(
+        142 * BYTE_SHANNONS as u128,
+        sum(|breakdown| breakdown.udt_occupied.value())
+    );
+    assert!(sum(|breakdown| breakdown.acp_occupied.value()) > 0);
+}
+
+#[test]
+async fn test_query_transactions_by_amount_range() {
+    let engine =
+        RpcTestEngine::init_data(vec![AddressData::new(SECP_ADDRESS_2, 250, 100, 0, 0)]).await;
+    let rpc = engine.rpc(NetworkType::Testnet);
+
+    let query_transactions = |asset_infos: Vec<AssetInfo>, to: u64| QueryTransactionsPayload {
+        item: JsonItem::Address(SECP_ADDRESS_2.to_string()),
+        asset_infos: asset_infos.into_iter().collect(),
+        extra: None,
+        block_range: None,
+        time_range: None,
+        counterparty: None,
+        direction: None,
+        amount_range: Some(Range {
+            from: 0u64.into(),
+            to: to.into(),
+        }),
+        pagination: PaginationRequest::default(),
+        structure_type: StructureType::Native,
+        with_labels: None,
+        min_confirmations: None,
+        include_pending: None,
+    };
+    let txs = rpc
+        .query_transactions(query_transactions(
+            vec![AssetInfo::new_ckb()],
+            250 * BYTE_SHANNONS,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(1, txs.response.len());
+    let txs = rpc
+        .query_transactions(query_transactions(
+            vec![AssetInfo::new_ckb()],
+            BYTE_SHANNONS,
+        ))
+        .await
+        .unwrap();
+    assert!(txs.response.is_empty());
+
+    // the range of a UDT is of its amount
+    let udt_hash: H256 = engine.sudt_script.calc_script_hash().unpack();
+    let txs = rpc
+        .query_transactions(query_transactions(
+            vec![AssetInfo::new_udt(udt_hash.clone())],
+            100,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(1, txs.response.len());
+    let txs = rpc
+        .query_transactions(query_transactions(
+            vec![AssetInfo::new_udt(udt_hash.clone())],
+            99,
+        ))
+        .await
+        .unwrap();
+    assert!(txs.response.is_empty());
+
+    // the changes of different assets are not comparable
+    for asset_infos in [
+        vec![],
+        vec![AssetInfo::new_ckb(), AssetInfo::new_udt(udt_hash)],
+    ] {
+        let err = rpc
+            .query_transactions(query_transactions(asset_infos, 250 * BYTE_SHANNONS))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("11002"));
+    }
+}
+
+async fn get_sender_statement(
+    rpc: &MercuryRpcImpl<CkbRpcClient>,
+    format: StatementFormat,
+) -> AccountStatement {
+    rpc.get_account_statement(GetAccountStatementPayload {
+        item: JsonItem::Address(SECP_ADDRESS_2.to_string()),
+        asset_info: AssetInfo::new_ckb(),
+        block_range: Some(Range {
+            from: 0u64.into(),
+            to: 0u64.into(),
+        }),
+        time_range: None,
+        format: Some(format),
+        cursor: None,
+    })
+    .await
+    .unwrap()
+}
+
+#[test]
+async fn test_get_account_statement() {
+    let mut engine = RpcTestEngine::new().await;
+    let rpc = engine.rpc(NetworkType::Testnet);
+
+    let sender = address_to_script(parse_address(SECP_ADDRESS_2).unwrap().payload());
+    let receiver = address_to_script(parse_address(SECP_ADDRESS_1).unwrap().payload());
+    let new_output = |capacity: u64, lock: &packed::Script| {
+        packed::CellOutputBuilder::default()
+            .capacity((capacity * BYTE_SHANNONS).pack())
+            .lock(lock.clone())
+            .build()
+    };
+    let cellbase = TransactionBuilder::default()
+        .input(packed::CellInput::new_cellbase_input(0))
+        .witness(packed::Script::default().into_witness())
+        .output(new_output(1_000, &sender))
+        .output_data(Default::default())
+        .output(new_output(500, &receiver))
+        .output_data(Default::default())
+        .build();
+    // the fee of 100 CKB is shared by the owners of the inputs
+    let pay_tx = TransactionBuilder::default()
+        .input(packed::CellInput::new(
+            packed::OutPoint::new(cellbase.hash(), 0),
+            0,
+        ))
+        .input(packed::CellInput::new(
+            packed::OutPoint::new(cellbase.hash(), 1),
+            0,
+        ))
+        .output(new_output(1_200, &receiver))
+        .output_data(Default::default())
+        .output(new_output(200, &sender))
+        .output_data(Default::default())
+        .build();
+    // the change is all paid as the fee by a transaction without any output
+    let burn_tx = TransactionBuilder::default()
+        .input(packed::CellInput::new(
+            packed::OutPoint::new(pay_tx.hash(), 1),
+            0,
+        ))
+        .build();
+    engine
+        .append(RpcTestEngine::new_block(
+            vec![cellbase.clone(), pay_tx.clone(), burn_tx.clone()],
+            0,
+            0,
+        ))
+        .await;
+
+    let statement = get_sender_statement(&rpc, StatementFormat::Json).await;
+    assert_eq!(0, statement.opening_balance.value());
+    assert_eq!(0, statement.closing_balance.value());
+    assert_eq!(None, statement.next_cursor);
+    let entry =
+        |tx: &TransactionView, received: u64, sent: u64, fee: u64, balance: u64| StatementEntry {
+            tx_hash: tx.hash().unpack(),
+            block_number: 0u64.into(),
+            timestamp: 0u64.into(),
+            received: (received as u128).into(),
+            sent: (sent as u128).into(),
+            fee: fee.into(),
+            balance: (balance as u128).into(),
+        };
+    let ckb = |amount: u64| amount * BYTE_SHANNONS;
+    let pay_fee = ckb(100) * 1_000 / 1_500;
+    assert_eq!(
+        vec![
+            entry(&cellbase, ckb(1_000), 0, 0, ckb(1_000)),
+            entry(&pay_tx, ckb(200), ckb(1_000), pay_fee, ckb(200)),
+            entry(&burn_tx, 0, ckb(200), ckb(200), 0),
+        ],
+        statement.entries
+    );
+
+    let csv = get_sender_statement(&rpc, StatementFormat::Csv).await;
+    assert!(csv.entries.is_empty());
+    let csv = csv.export.unwrap();
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(4, lines.len());
+    assert_eq!(
+        "tx_hash,block_number,timestamp,received,sent,delta,fee,balance",
+        lines[0]
+    );
+    assert_eq!(
+        format!(
+            "{:#x},0,0,{},{},-{},{},{}",
+            pay_tx.hash(),
+            ckb(200),
+            ckb(1_000),
+            ckb(800),
+            pay_fee,
+            ckb(200)
+        ),
+        lines[2]
+    );
+
+    let json_lines = get_sender_statement(&rpc, StatementFormat::JsonLines)
+        .await
+        .export
+        .unwrap();
+    let entries = json_lines
+        .lines()
+        .map(|line| serde_json::from_str::<StatementEntry>(line).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(statement.entries, entries);
+}
@@ -0,0 +1,127 @@
+use super::*;
+
+use crate::subscription::{ItemMatcher, MercurySubscriptionImpl};
+use crate::MercurySubscriptionServer;
+
+use core_rpc_types::{
+    indexer, ChainEvent, Identity, IdentityFlag, ItemEvent, ItemEventType, JsonItem,
+};
+use tokio::sync::broadcast;
+use tokio::test;
+
+fn new_item_event(lock: packed::Script, tx_hash: H256) -> ItemEvent {
+    let output = packed::CellOutputBuilder::default()
+        .capacity(capacity_bytes!(100).pack())
+        .lock(lock)
+        .build();
+    ItemEvent {
+        event_type: ItemEventType::Created,
+        block_number: 1u64.into(),
+        block_hash: H256::default(),
+        tx_hash: tx_hash.clone(),
+        cell: indexer::Cell {
+            output: output.into(),
+            output_data: Default::default(),
+            out_point: packed::OutPoint::new(tx_hash.pack(), 0).into(),
+            block_number: 1u64.into(),
+            tx_index: 1u32.into(),
+        },
+    }
+}
+
+#[test]
+async fn test_item_matcher() {
+    let engine = RpcTestEngine::new().await;
+    let _ = engine.rpc(NetworkType::Testnet);
+
+    let addr = "ckt1qyqr79tnk3pp34xp92gerxjc4p3mus2690psf0dd70";
+    let other_addr = "ckt1qyq2y6jdkynen2vx946tnsdw2dgucvv7ph0s8n4kfd";
+    let lock = address_to_script(parse_address(addr).unwrap().payload());
+    let other_lock = address_to_script(parse_address(other_addr).unwrap().payload());
+    let out_point = packed::OutPoint::new(rand_h256().pack(), 0);
+
+    let matcher = ItemMatcher::new(JsonItem::Address(addr.to_string())).unwrap();
+    assert!(matcher.is_cell_match(&lock, &out_point));
+    assert!(!matcher.is_cell_match(&other_lock, &out_point));
+
+    let pubkey_hash = H160::from_slice(&lock.args().raw_data()).unwrap();
+    let identity = Identity::new(IdentityFlag::Ckb, pubkey_hash);
+    let matcher = ItemMatcher::new(JsonItem::Identity(hex::encode(identity.0))).unwrap();
+    assert!(matcher.is_cell_match(&lock, &out_point));
+    assert!(!matcher.is_cell_match(&other_lock, &out_point));
+
+    let matcher = ItemMatcher::new(JsonItem::OutPoint(out_point.clone().into())).unwrap();
+    assert!(matcher.is_cell_match(&other_lock, &out_point));
+    assert!(!matcher.is_cell_match(&lock, &packed::OutPoint::new(rand_h256().pack(), 1)));
+
+    assert!(ItemMatcher::new(JsonItem::Address("invalid".to_string())).is_err());
+}
+
+#[test]
+async fn test_subscribe_item_events() {
+    let engine = RpcTestEngine::new().await;
+    let _ = engine.rpc(NetworkType::Testnet);
+
+    let (sender, _) = broadcast::channel(16);
+    let module = MercurySubscriptionImpl::new(sender.clone()).into_rpc();
+
+    let addr = "ckt1qyqr79tnk3pp34xp92gerxjc4p3mus2690psf0dd70";
+    let other_addr = "ckt1qyq2y6jdkynen2vx946tnsdw2dgucvv7ph0s8n4kfd";
+    let lock = address_to_script(parse_address(addr).unwrap().payload());
+    let other_lock = address_to_script(parse_address(other_addr).unwrap().payload());
+
+    let mut tip_subscription = module
+        .subscribe("subscribe_new_tip", Vec::<()>::new())
+        .await
+        .unwrap();
+    let mut item_subscription = module
+        .subscribe(
+            "subscribe_item_events",
+            [JsonItem::Address(addr.to_string())],
+        )
+        .await
+        .unwrap();
+    assert!(module
+        .subscribe(
+            "subscribe_item_events",
+            [JsonItem::Address("invalid".to_string())],
+        )
+        .await
+        .is_err());
+
+    let tip = indexer::Tip {
+        block_hash: rand_h256(),
+        block_number: 1u64.into(),
+    };
+    let matched_event = new_item_event(lock.clone(), rand_h256());
+    sender
+        .send(ChainEvent {
+            tip: tip.clone(),
+            item_events: vec![
+                new_item_event(other_lock.clone(), rand_h256()),
+                matched_event.clone(),
+            ],
+        })
+        .unwrap();
+
+    let (notified_tip, _) = tip_subscription.next().await.unwrap().unwrap();
+    assert_eq!(tip, notified_tip);
+    let (notified_event, _) = item_subscription.next().await.unwrap().unwrap();
+    assert_eq!(matched_event, notified_event);
+
+    // a block without the cells of the item notifies nothing
+    let next_matched_event = new_item_event(lock, rand_h256());
+    for item_events in [
+        vec![new_item_event(other_lock, rand_h256())],
+        vec![next_matched_event.clone()],
+    ] {
+        sender
+            .send(ChainEvent {
+                tip: tip.clone(),
+                item_events,
+            })
+            .unwrap();
+    }
+    let (notified_event, _) = item_subscription.next().await.unwrap().unwrap();
+    assert_eq!(next_matched_event, notified_event);
+}
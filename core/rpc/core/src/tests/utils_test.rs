@@ -1,7 +1,13 @@
 use super::*;
 use crate::r#impl::utils::{self, calculate_cell_capacity};
+use crate::r#impl::utils_types::{AssetScriptType, CkbCellsCache, PoolCkbCategory};
 use ckb_jsonrpc_types::OutPoint;
-use core_rpc_types::{JsonItem, SinceConfig, SinceFlag, SinceType};
+use common::DetailedCell;
+use common::MULTISIG_TYPE_HASH;
+use core_rpc_types::{
+    CoinSelection, Identity, IdentityFlag, Item, JsonItem, MultisigConfig, SinceConfig, SinceFlag,
+    SinceType,
+};
 
 use ckb_types::core::EpochNumberWithFraction;
 
@@ -255,3 +261,249 @@ fn test_calculate_cell_capacity() {
     );
     assert_eq!(6100000000, capacity);
 }
+
+#[test]
+fn test_rebuild_udt_cell_for_output_keeps_extension_data() {
+    let address = Address::from_str("ckb1qzda0cr08m85hc8jlnfp3zer7xulejywt49kt2rr0vthywaa50xwsq4nnw7qkdnnclfkg59uzn8umtfd2kwxceqcydzyt").unwrap();
+    let lock = address_to_script(address.payload());
+    let mut cell_data = 100u128.to_le_bytes().to_vec();
+    cell_data.extend_from_slice(&[1, 2, 3, 4]);
+    let cell = DetailedCell {
+        epoch_number: 0,
+        block_number: 0,
+        block_hash: H256::default(),
+        tx_index: 0,
+        out_point: packed::OutPoint::default(),
+        cell_output: packed::CellOutput::new_builder()
+            .lock(lock.clone())
+            .type_(Some(packed::Script::default()).pack())
+            .build(),
+        cell_data: Bytes::from(cell_data),
+        consumed_block_number: None,
+        consumed_block_hash: None,
+        consumed_tx_hash: None,
+        consumed_tx_index: None,
+        consumed_input_index: None,
+        since: None,
+    };
+
+    let mut outputs = vec![];
+    let mut outputs_data = vec![];
+    let index = utils::rebuild_udt_cell_for_output(
+        &cell,
+        14_200_000_000,
+        lock,
+        Some(50),
+        &mut outputs,
+        &mut outputs_data,
+    )
+    .unwrap();
+
+    let data = outputs_data[index].raw_data();
+    assert_eq!(Some(50), decode_udt_amount(&data));
+    assert_eq!(&[1, 2, 3, 4], &data[16..]);
+    assert_eq!(
+        cell.cell_output.type_().as_slice(),
+        outputs[index].type_().as_slice()
+    );
+}
+
+#[test]
+fn test_build_multisig_script_and_since() {
+    let config = MultisigConfig {
+        require_first_n: 0,
+        threshold: 2,
+        pubkey_hashes: vec![
+            h160!("0x1111111111111111111111111111111111111111"),
+            h160!("0x2222222222222222222222222222222222222222"),
+            h160!("0x3333333333333333333333333333333333333333"),
+        ],
+    };
+    let script = utils::build_multisig_script(&config).unwrap();
+    assert_eq!(4 + 20 * 3, script.len());
+    assert_eq!(&[0, 0, 2, 3], &script[0..4]);
+
+    let invalid_config = MultisigConfig {
+        threshold: 4,
+        ..config
+    };
+    assert!(utils::build_multisig_script(&invalid_config).is_err());
+
+    let since = 0x2000_0000_0000_0064u64;
+    let mut args = blake2b_160(&script).to_vec();
+    args.extend_from_slice(&since.to_le_bytes());
+    let lock = packed::Script::new_builder()
+        .code_hash(MULTISIG_TYPE_HASH.pack())
+        .hash_type(ScriptHashType::Type.into())
+        .args(args.pack())
+        .build();
+    assert_eq!(Some(since), utils::get_multisig_since(&lock));
+
+    let lock = lock
+        .as_builder()
+        .args(Bytes::from(args[0..20].to_vec()).pack())
+        .build();
+    assert_eq!(None, utils::get_multisig_since(&lock));
+}
+
+#[test]
+fn test_is_confirmed() {
+    assert!(utils::is_confirmed(100, 100, 0));
+    assert!(utils::is_confirmed(100, 100, 1));
+    assert!(!utils::is_confirmed(100, 100, 2));
+    assert!(utils::is_confirmed(95, 100, 6));
+    assert!(!utils::is_confirmed(96, 100, 6));
+    assert!(!utils::is_confirmed(101, 100, 1));
+}
+
+fn new_pooled_cell(capacity: u64, block_number: u64) -> (DetailedCell, AssetScriptType) {
+    let cell = DetailedCell {
+        epoch_number: 0,
+        block_number,
+        block_hash: H256::default(),
+        tx_index: 1,
+        out_point: packed::OutPoint::new(rand_h256().pack(), 0),
+        cell_output: packed::CellOutput::new_builder()
+            .capacity((capacity * BYTE_SHANNONS).pack())
+            .build(),
+        cell_data: Bytes::new(),
+        consumed_block_number: None,
+        consumed_block_hash: None,
+        consumed_tx_hash: None,
+        consumed_tx_index: None,
+        consumed_input_index: None,
+        since: None,
+    };
+    (cell, AssetScriptType::Secp256k1)
+}
+
+fn pop_capacities(
+    coin_selection: CoinSelection,
+    cells: &[(u64, u64)],
+    mut required: i128,
+) -> Vec<u64> {
+    let mut cache = CkbCellsCache::new(vec![]);
+    cache.coin_selection = coin_selection;
+    cache.cell_deque = cells
+        .iter()
+        .map(|(capacity, block_number)| new_pooled_cell(*capacity, *block_number))
+        .collect();
+
+    let mut capacities = vec![];
+    while let Some((cell, _)) = cache.pop_cell(required * BYTE_SHANNONS as i128) {
+        let capacity = Unpack::<u64>::unpack(&cell.cell_output.capacity()) / BYTE_SHANNONS;
+        required -= capacity as i128;
+        capacities.push(capacity);
+    }
+    capacities
+}
+
+#[test]
+fn test_pop_cell_by_coin_selection() {
+    let cells = [(300, 3), (100, 1), (500, 4), (200, 2)];
+    assert_eq!(
+        vec![300, 100, 500, 200],
+        pop_capacities(CoinSelection::Default, &cells, 0)
+    );
+    assert_eq!(
+        vec![500, 300, 200, 100],
+        pop_capacities(CoinSelection::LargestFirst, &cells, 0)
+    );
+    assert_eq!(
+        vec![100, 200, 300, 500],
+        pop_capacities(CoinSelection::SmallestFirst, &cells, 0)
+    );
+    assert_eq!(
+        vec![100, 200, 300, 500],
+        pop_capacities(CoinSelection::OldestFirst, &cells, 0)
+    );
+
+    // the smallest cell covering the required capacity
+    assert_eq!(
+        vec![300, 100, 200, 500],
+        pop_capacities(CoinSelection::MinimizeChange, &cells, 250)
+    );
+    // the largest cell first, then the smallest one covering the rest
+    assert_eq!(
+        vec![500, 200, 100, 300],
+        pop_capacities(CoinSelection::MinimizeChange, &cells, 650)
+    );
+}
+
+#[test]
+fn test_pop_cell_after_pool_filled() {
+    let items = vec![Item::Identity(Identity::new(
+        IdentityFlag::Ckb,
+        H160::default(),
+    ))];
+    let mut cache = CkbCellsCache::new(items);
+    cache.cell_deque = vec![new_pooled_cell(100, 1), new_pooled_cell(200, 2)]
+        .into_iter()
+        .collect();
+
+    // the default strategy takes the cells of each loaded category
+    let (cell, _) = cache.pop_cell(0).unwrap();
+    assert_eq!(
+        100 * BYTE_SHANNONS,
+        Unpack::<u64>::unpack(&cell.cell_output.capacity())
+    );
+
+    // the other strategies wait for the cells of all the categories
+    cache.coin_selection = CoinSelection::SmallestFirst;
+    cache.cell_deque.push_back(new_pooled_cell(50, 3));
+    assert!(cache.pop_cell(0).is_none());
+    cache.array_index = cache.item_category_array.len();
+    let (cell, _) = cache.pop_cell(0).unwrap();
+    assert_eq!(
+        50 * BYTE_SHANNONS,
+        Unpack::<u64>::unpack(&cell.cell_output.capacity())
+    );
+
+    // or for as many cells as a page
+    let mut cache = CkbCellsCache::new(vec![]);
+    cache
+        .item_category_array
+        .push((0, PoolCkbCategory::CkbNormalSecp));
+    cache.pagination.set_limit(Some(2));
+    cache.coin_selection = CoinSelection::LargestFirst;
+    cache.cell_deque.push_back(new_pooled_cell(100, 1));
+    assert!(cache.pop_cell(0).is_none());
+    cache.cell_deque.push_back(new_pooled_cell(200, 2));
+    let (cell, _) = cache.pop_cell(0).unwrap();
+    assert_eq!(
+        200 * BYTE_SHANNONS,
+        Unpack::<u64>::unpack(&cell.cell_output.capacity())
+    );
+    cache.cell_deque.push_back(new_pooled_cell(300, 3));
+    let (cell, _) = cache.pop_cell(0).unwrap();
+    assert_eq!(
+        100 * BYTE_SHANNONS,
+        Unpack::<u64>::unpack(&cell.cell_output.capacity())
+    );
+}
+
+#[test]
+fn test_pop_cell_by_spendable_capacity() {
+    let (udt_cell, asset_script_type) = new_pooled_cell(300, 1);
+    let udt_cell = DetailedCell {
+        cell_output: udt_cell
+            .cell_output
+            .as_builder()
+            .type_(Some(packed::Script::default()).pack())
+            .build(),
+        cell_data: Bytes::from(100u128.to_le_bytes().to_vec()),
+        ..udt_cell
+    };
+    let mut cache = CkbCellsCache::new(vec![]);
+    cache.coin_selection = CoinSelection::LargestFirst;
+    cache.cell_deque = vec![(udt_cell, asset_script_type), new_pooled_cell(250, 2)]
+        .into_iter()
+        .collect();
+
+    // the UDT cell provides only the capacity above its occupied capacity
+    let (cell, _) = cache.pop_cell(0).unwrap();
+    assert_eq!(
+        250 * BYTE_SHANNONS,
+        Unpack::<u64>::unpack(&cell.cell_output.capacity())
+    );
+}
@@ -0,0 +1,261 @@
+use super::*;
+
+use core_rpc_types::{ScriptGroupType, VerifyTransactionPayload};
+
+use ckb_resource::Resource;
+use ckb_types::core::DepType;
+use common::hash::new_blake2b;
+use tokio::test;
+
+const SECRET_KEY: [u8; 32] = [7u8; 32];
+
+fn bundled_cell(name: &str) -> Bytes {
+    let data = Resource::bundled(format!("specs/cells/{}", name))
+        .get()
+        .unwrap();
+    Bytes::from(data.to_vec())
+}
+
+fn new_output(lock: packed::Script, capacity: u64) -> packed::CellOutput {
+    packed::CellOutputBuilder::default()
+        .capacity(capacity.pack())
+        .lock(lock)
+        .build()
+}
+
+fn new_code_dep(tx_hash: packed::Byte32, index: u32) -> packed::CellDep {
+    packed::CellDep::new_builder()
+        .out_point(packed::OutPoint::new(tx_hash, index))
+        .dep_type(DepType::Code.into())
+        .build()
+}
+
+/// The secp256k1 blake160 sighash lock and its cell deps, whose binaries are the
+/// bundled system cells referred by the data hash.
+struct SecpTestEnv {
+    lock: packed::Script,
+    cell_deps: Vec<packed::CellDep>,
+    input: packed::OutPoint,
+    broken_type: packed::Script,
+    broken_lock_input: packed::OutPoint,
+}
+
+async fn init_secp_env(engine: &mut RpcTestEngine) -> SecpTestEnv {
+    let secp = secp256k1::Secp256k1::new();
+    let secret_key = secp256k1::SecretKey::from_slice(&SECRET_KEY).unwrap();
+    let pubkey = secp256k1::PublicKey::from_secret_key(&secp, &secret_key);
+
+    let sighash_all = bundled_cell("secp256k1_blake160_sighash_all");
+    let secp_data = bundled_cell("secp256k1_data");
+    let broken_code = Bytes::from(b"not a risc-v binary".to_vec());
+    let lock = packed::Script::new_builder()
+        .code_hash(packed::CellOutput::calc_data_hash(&sighash_all))
+        .hash_type(ScriptHashType::Data.into())
+        .args(Bytes::from(blake2b_160(&pubkey.serialize()).to_vec()).pack())
+        .build();
+    let broken_type = packed::Script::new_builder()
+        .code_hash(packed::CellOutput::calc_data_hash(&broken_code))
+        .hash_type(ScriptHashType::Data.into())
+        .build();
+
+    let cellbase = TransactionBuilder::default()
+        .input(packed::CellInput::new_cellbase_input(0))
+        .witness(packed::Script::default().into_witness())
+        .output(new_output(
+            CELLBASE_ADDRESS.payload().into(),
+            1_000 * BYTE_SHANNONS,
+        ))
+        .output_data(Default::default())
+        .build();
+    let mut tx_builder = TransactionBuilder::default();
+    for data in [sighash_all, secp_data, broken_code] {
+        let capacity = (data.len() as u64 + 100) * BYTE_SHANNONS;
+        tx_builder = tx_builder
+            .output(new_output(packed::Script::default(), capacity))
+            .output_data(data.pack());
+    }
+    let genesis_tx = tx_builder
+        .output(new_output(lock.clone(), 1_000 * BYTE_SHANNONS))
+        .output_data(Default::default())
+        .output(new_output(broken_type.clone(), 1_000 * BYTE_SHANNONS))
+        .output_data(Default::default())
+        .build();
+    engine
+        .append(RpcTestEngine::new_block(
+            vec![cellbase, genesis_tx.clone()],
+            0,
+            0,
+        ))
+        .await;
+
+    SecpTestEnv {
+        lock,
+        cell_deps: vec![
+            new_code_dep(genesis_tx.hash(), 0),
+            new_code_dep(genesis_tx.hash(), 1),
+            new_code_dep(genesis_tx.hash(), 2),
+        ],
+        input: packed::OutPoint::new(genesis_tx.hash(), 3),
+        broken_type,
+        broken_lock_input: packed::OutPoint::new(genesis_tx.hash(), 4),
+    }
+}
+
+fn build_transfer(env: &SecpTestEnv, type_script: Option<packed::Script>) -> TransactionView {
+    let output = new_output(env.lock.clone(), 900 * BYTE_SHANNONS)
+        .as_builder()
+        .type_(type_script.pack())
+        .build();
+    let placeholder = packed::WitnessArgs::new_builder()
+        .lock(Some(Bytes::from(vec![0u8; 65])).pack())
+        .build();
+    TransactionBuilder::default()
+        .cell_deps(env.cell_deps.clone())
+        .input(packed::CellInput::new(env.input.clone(), 0))
+        .output(output)
+        .output_data(Default::default())
+        .witness(placeholder.as_bytes().pack())
+        .build()
+}
+
+/// Sign the transaction of one input by the secp256k1 blake160 sighash all lock.
+fn sign_transfer(tx: &TransactionView) -> TransactionView {
+    let placeholder = tx.witnesses().get(0).unwrap().raw_data();
+    let mut message = [0u8; 32];
+    let mut blake2b = new_blake2b();
+    blake2b.update(tx.hash().as_slice());
+    blake2b.update(&(placeholder.len() as u64).to_le_bytes());
+    blake2b.update(&placeholder);
+    blake2b.finalize(&mut message);
+
+    let secp = secp256k1::Secp256k1::new();
+    let secret_key = secp256k1::SecretKey::from_slice(&SECRET_KEY).unwrap();
+    let message = secp256k1::Message::from_slice(&message).unwrap();
+    let (recovery_id, signature) = secp
+        .sign_ecdsa_recoverable(&message, &secret_key)
+        .serialize_compact();
+    let mut lock = signature.to_vec();
+    lock.push(recovery_id.to_i32() as u8);
+
+    let witness = packed::WitnessArgs::new_builder()
+        .lock(Some(Bytes::from(lock)).pack())
+        .build();
+    tx.as_advanced_builder()
+        .set_witnesses(vec![witness.as_bytes().pack()])
+        .build()
+}
+
+fn verify_payload(tx: &TransactionView) -> VerifyTransactionPayload {
+    VerifyTransactionPayload {
+        tx_view: tx.clone().into(),
+        max_cycles: None,
+    }
+}
+
+#[test]
+async fn test_verify_secp_transfer() {
+    let mut engine = RpcTestEngine::new().await;
+    let env = init_secp_env(&mut engine).await;
+    let rpc = engine.rpc(NetworkType::Testnet);
+
+    let unsigned_tx = build_transfer(&env, None);
+    let signed_tx = sign_transfer(&unsigned_tx);
+    let signed = rpc
+        .verify_transaction(verify_payload(&signed_tx))
+        .await
+        .unwrap();
+    assert!(signed.is_valid);
+    assert_eq!(None, signed.error);
+    assert_eq!(1, signed.script_groups.len());
+    let group = &signed.script_groups[0];
+    assert_eq!(ScriptGroupType::Lock, group.group_type);
+    assert!(!group.placeholder_witness);
+    assert_eq!(None, group.error);
+    let signed_cycles: u64 = signed.cycles.into();
+    assert_eq!(Some(signed_cycles), group.cycles.map(Into::into));
+
+    // the lock group of the placeholder fails, whose cycles are estimated
+    let unsigned = rpc
+        .verify_transaction(verify_payload(&unsigned_tx))
+        .await
+        .unwrap();
+    assert!(unsigned.is_valid);
+    let group = &unsigned.script_groups[0];
+    assert!(group.placeholder_witness);
+    assert!(group.error.is_some());
+    let estimated_cycles: u64 = unsigned.cycles.into();
+    assert_eq!(Some(estimated_cycles), group.cycles.map(Into::into));
+    assert!(estimated_cycles >= signed_cycles);
+}
+
+#[test]
+async fn test_verify_broken_type_script() {
+    let mut engine = RpcTestEngine::new().await;
+    let env = init_secp_env(&mut engine).await;
+    let rpc = engine.rpc(NetworkType::Testnet);
+
+    let tx = sign_transfer(&build_transfer(&env, Some(env.broken_type.clone())));
+    let res = rpc.verify_transaction(verify_payload(&tx)).await.unwrap();
+    assert!(!res.is_valid);
+    assert_eq!(2, res.script_groups.len());
+
+    let lock_group = res
+        .script_groups
+        .iter()
+        .find(|group| group.group_type == ScriptGroupType::Lock)
+        .unwrap();
+    assert_eq!(None, lock_group.error);
+    assert!(lock_group.cycles.is_some());
+
+    let type_group = res
+        .script_groups
+        .iter()
+        .find(|group| group.group_type == ScriptGroupType::Type)
+        .unwrap();
+    assert!(!type_group.placeholder_witness);
+    assert!(type_group.error.is_some());
+    assert_eq!(None, type_group.cycles);
+}
+
+#[test]
+async fn test_verify_broken_lock_script() {
+    let mut engine = RpcTestEngine::new().await;
+    let env = init_secp_env(&mut engine).await;
+    let rpc = engine.rpc(NetworkType::Testnet);
+
+    // the placeholder does not cover a lock script which cannot be run
+    let unsigned_tx = build_transfer(&env, None);
+    let tx = unsigned_tx
+        .as_advanced_builder()
+        .set_inputs(vec![packed::CellInput::new(
+            env.broken_lock_input.clone(),
+            0,
+        )])
+        .build();
+    let res = rpc.verify_transaction(verify_payload(&tx)).await.unwrap();
+    assert!(!res.is_valid);
+    assert_eq!(1, res.script_groups.len());
+    let group = &res.script_groups[0];
+    assert!(group.placeholder_witness);
+    assert!(group.error.is_some());
+    assert_eq!(None, group.cycles);
+}
+
+#[test]
+async fn test_verify_missing_lock_dep() {
+    let mut engine = RpcTestEngine::new().await;
+    let env = init_secp_env(&mut engine).await;
+    let rpc = engine.rpc(NetworkType::Testnet);
+
+    let unsigned_tx = build_transfer(&env, None);
+    let tx = unsigned_tx
+        .as_advanced_builder()
+        .set_cell_deps(env.cell_deps[1..].to_vec())
+        .build();
+    let res = rpc.verify_transaction(verify_payload(&tx)).await.unwrap();
+    assert!(!res.is_valid);
+    let group = &res.script_groups[0];
+    assert!(group.placeholder_witness);
+    assert!(group.error.is_some());
+    assert_eq!(None, group.cycles);
+}
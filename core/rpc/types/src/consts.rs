@@ -6,8 +6,18 @@ pub const WITHDRAWING_DAO_CELL_OCCUPIED_CAPACITY: u64 = 102 * BYTE_SHANNONS;
 pub const INIT_ESTIMATE_FEE: u64 = BYTE_SHANNONS / 1000;
 pub const DEFAULT_FEE_RATE: u64 = 1000;
 pub const MAX_ITEM_NUM: usize = 1000;
+pub const MAX_SWEEP_INPUT_NUM: usize = 1000;
+pub const DEFAULT_CONSOLIDATE_INPUT_NUM: u32 = 500;
+pub const DEFAULT_BATCH_PAYOUT_OUTPUT_NUM: u32 = 200;
+pub const DEFAULT_BATCH_PAYOUT_TX_SIZE: u64 = 100_000;
+pub const DEFAULT_BLOCKS_INFO_LIMIT: u64 = 100;
+pub const DEFAULT_CHAIN_EVENTS_LIMIT: u64 = 10;
+pub const MAX_BLOCKS_INFO_LIMIT: u64 = 1000;
+pub const MAX_CHAIN_EVENTS_LIMIT: u64 = 100;
+pub const MAX_STATEMENT_ENTRY_NUM: usize = 10_000;
 pub const MIN_DAO_CAPACITY: u64 = 200 * BYTE_SHANNONS;
 pub const MIN_DAO_LOCK_PERIOD: u64 = 180;
+pub const MAX_TX_VERIFY_CYCLES: u64 = 70_000_000;
 
 pub const fn ckb(num: u64) -> u64 {
     num * BYTE_SHANNONS
@@ -1,3 +1,6 @@
+use crate::reservation::InputReservations;
+use crate::tx_pool::TxPool;
+
 use arc_swap::ArcSwap;
 use ckb_types::core::{BlockNumber, RationalU256};
 use ckb_types::packed;
@@ -7,6 +10,8 @@ use std::collections::HashSet;
 
 lazy_static::lazy_static! {
     pub static ref TX_POOL_CACHE: RwLock<HashSet<packed::OutPoint>> = RwLock::new(HashSet::new());
+    pub static ref TX_POOL: RwLock<TxPool> = RwLock::new(TxPool::default());
+    pub static ref INPUT_RESERVATIONS: RwLock<InputReservations> = RwLock::new(InputReservations::default());
     pub static ref CURRENT_BLOCK_NUMBER: ArcSwap<BlockNumber> = ArcSwap::from_pointee(0u64);
     pub static ref CURRENT_EPOCH_NUMBER: ArcSwap<RationalU256> = ArcSwap::from_pointee(RationalU256::zero());
 }
@@ -2,13 +2,18 @@ pub mod consts;
 pub mod error;
 pub mod indexer;
 pub mod lazy;
+pub mod reservation;
+pub mod tx_pool;
 pub mod uints;
 
 use uints::{Uint128, Uint16, Uint32, Uint64};
 
 use crate::error::TypeError;
 
-use ckb_jsonrpc_types::{BlockNumber, CellDep, CellOutput, OutPoint, Script, TransactionView};
+use ckb_jsonrpc_types::{
+    BlockNumber, CellDep, CellOutput, JsonBytes, OutPoint, OutputsValidator, Script, Transaction,
+    TransactionView,
+};
 use ckb_types::{bytes::Bytes, H160, H256};
 use common::{derive_more::Display, utils::to_fixed_array, NetworkType, Order, Result};
 use protocol::db::TransactionWrapper;
@@ -25,6 +30,8 @@ pub enum AssetType {
     CKB,
     #[serde(alias = "udt")]
     UDT,
+    #[serde(alias = "xudt")]
+    XUDT,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Display, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -43,6 +50,10 @@ impl AssetInfo {
         AssetInfo::new(AssetType::UDT, udt_hash)
     }
 
+    pub fn new_xudt(udt_hash: H256) -> Self {
+        AssetInfo::new(AssetType::XUDT, udt_hash)
+    }
+
     fn new(asset_type: AssetType, udt_hash: H256) -> Self {
         AssetInfo {
             asset_type,
@@ -311,6 +322,7 @@ pub struct Record {
     pub extra: Option<ExtraFilter>,
     pub block_number: BlockNumber,
     pub epoch_number: Uint64,
+    pub label: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
@@ -325,6 +337,8 @@ pub struct GetBalancePayload {
     pub asset_infos: HashSet<AssetInfo>,
     pub extra: Option<ExtraType>,
     pub tip_block_number: Option<BlockNumber>,
+    pub detailed: Option<bool>,
+    pub min_confirmations: Option<Uint64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
@@ -340,6 +354,10 @@ pub struct Balance {
     pub free: Uint128,
     pub occupied: Uint128,
     pub frozen: Uint128,
+    pub unconfirmed: Uint128,
+    pub pending_incoming: Uint128,
+    pub pending_outgoing: Uint128,
+    pub breakdown: Option<BalanceBreakdown>,
 }
 
 impl Balance {
@@ -350,16 +368,41 @@ impl Balance {
             free: 0u128.into(),
             occupied: 0u128.into(),
             frozen: 0u128.into(),
+            unconfirmed: 0u128.into(),
+            pending_incoming: 0u128.into(),
+            pending_outgoing: 0u128.into(),
+            breakdown: None,
         }
     }
 }
 
+/// The frozen and occupied amounts of a balance by reason. The frozen amounts add up
+/// to `frozen` of the balance, and the occupied amounts add up to `occupied`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct BalanceBreakdown {
+    pub immature_cellbase: Uint128,
+    pub dao_deposited: Uint128,
+    pub dao_withdrawing_locked: Uint128,
+    pub since_locked: Uint128,
+    pub pending_cheque: Uint128,
+    pub unknown_type: Uint128,
+    pub acp_occupied: Uint128,
+    pub udt_occupied: Uint128,
+    pub other_occupied: Uint128,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
 pub struct GetBlockInfoPayload {
     pub block_number: Option<BlockNumber>,
     pub block_hash: Option<H256>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct GetBlocksInfoPayload {
+    pub block_range: Range,
+    pub limit: Option<Uint64>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
 pub struct BlockInfo {
     pub block_number: BlockNumber,
@@ -381,8 +424,124 @@ pub struct QueryTransactionsPayload {
     pub asset_infos: HashSet<AssetInfo>,
     pub extra: Option<ExtraType>,
     pub block_range: Option<Range>,
+    pub time_range: Option<Range>,
+    pub counterparty: Option<JsonItem>,
+    pub direction: Option<TransactionDirection>,
+    pub amount_range: Option<Range>,
     pub pagination: PaginationRequest,
     pub structure_type: StructureType,
+    pub with_labels: Option<bool>,
+    pub min_confirmations: Option<Uint64>,
+    pub include_pending: Option<bool>,
+}
+
+/// The committed transactions are paged, while the pending ones of the pool are returned
+/// apart on the first page and counted in neither `limit` nor `count`.
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct QueryTransactionsResponse {
+    pub response: Vec<TxView>,
+    pub next_cursor: Option<Uint64>,
+    pub count: Option<Uint64>,
+    #[serde(default)]
+    pub pending: Vec<TxView>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum TransactionDirection {
+    #[serde(alias = "incoming")]
+    Incoming,
+    #[serde(alias = "outgoing")]
+    Outgoing,
+}
+
+impl std::convert::From<TransactionDirection> for protocol::db::TransactionDirection {
+    fn from(direction: TransactionDirection) -> Self {
+        match direction {
+            TransactionDirection::Incoming => protocol::db::TransactionDirection::Incoming,
+            TransactionDirection::Outgoing => protocol::db::TransactionDirection::Outgoing,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GetChequesPayload {
+    pub item: JsonItem,
+    pub asset_infos: HashSet<AssetInfo>,
+    pub pagination: PaginationRequest,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ChequeInfo {
+    pub out_point: OutPoint,
+    pub sender: String,
+    pub receiver: String,
+    pub asset_info: AssetInfo,
+    pub amount: Uint128,
+    pub capacity: Uint64,
+    pub block_number: BlockNumber,
+    pub epoch_number: Uint64,
+    pub sender_withdrawable: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GetDaoPositionsPayload {
+    pub item: JsonItem,
+    pub pagination: PaginationRequest,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DaoPosition {
+    pub out_point: OutPoint,
+    pub capacity: Uint64,
+    pub deposit_block_number: BlockNumber,
+    pub deposit_epoch_number: Uint64,
+    pub withdraw_block_number: Option<BlockNumber>,
+    pub withdraw_epoch_number: Option<Uint64>,
+    pub compensation: Uint64,
+    pub unlock_epoch_number: Uint64,
+    pub estimated_unlock_timestamp: Uint64,
+    pub claimable: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum StatementFormat {
+    #[serde(alias = "json")]
+    Json,
+    #[serde(alias = "csv")]
+    Csv,
+    #[serde(alias = "json_lines")]
+    JsonLines,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GetAccountStatementPayload {
+    pub item: JsonItem,
+    pub asset_info: AssetInfo,
+    pub block_range: Option<Range>,
+    pub time_range: Option<Range>,
+    pub format: Option<StatementFormat>,
+    pub cursor: Option<BlockNumber>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct StatementEntry {
+    pub tx_hash: H256,
+    pub block_number: BlockNumber,
+    pub timestamp: Uint64,
+    pub received: Uint128,
+    pub sent: Uint128,
+    pub fee: Uint64,
+    pub balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct AccountStatement {
+    pub asset_info: AssetInfo,
+    pub opening_balance: Uint128,
+    pub closing_balance: Uint128,
+    pub entries: Vec<StatementEntry>,
+    pub export: Option<String>,
+    pub next_cursor: Option<BlockNumber>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -412,12 +571,14 @@ pub struct AdjustAccountPayload {
     pub account_number: Option<Uint32>,
     pub extra_ckb: Option<Uint64>,
     pub fee_rate: Option<Uint64>,
+    pub verify: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct TransactionCompletionResponse {
     pub tx_view: TransactionView,
     pub script_groups: Vec<ScriptGroup>,
+    pub verification: Option<VerifyTransactionResponse>,
 }
 
 impl TransactionCompletionResponse {
@@ -425,10 +586,42 @@ impl TransactionCompletionResponse {
         TransactionCompletionResponse {
             tx_view,
             script_groups,
+            verification: None,
         }
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ReleaseInputReservationPayload {
+    pub tx_hash: H256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VerifyTransactionPayload {
+    pub tx_view: TransactionView,
+    pub max_cycles: Option<Uint64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct VerifyTransactionResponse {
+    pub is_valid: bool,
+    pub cycles: Uint64,
+    pub script_groups: Vec<ScriptGroupVerification>,
+    pub error: Option<String>,
+}
+
+/// The verification result of a script group. A lock script group whose witness
+/// still holds the placeholder signature is expected to fail, so its failure does
+/// not make the transaction invalid, and its cycles are estimated.
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ScriptGroupVerification {
+    pub script: Script,
+    pub group_type: ScriptGroupType,
+    pub placeholder_witness: bool,
+    pub cycles: Option<Uint64>,
+    pub error: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
 pub struct SudtIssuePayload {
     pub owner: String,
@@ -437,6 +630,7 @@ pub struct SudtIssuePayload {
     pub output_capacity_provider: Option<OutputCapacityProvider>,
     pub fee_rate: Option<Uint64>,
     pub since: Option<SinceConfig>,
+    pub verify: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
@@ -472,6 +666,9 @@ pub struct TransferPayload {
     pub pay_fee: Option<PayFee>,
     pub fee_rate: Option<Uint64>,
     pub since: Option<SinceConfig>,
+    pub multisig_configs: Option<Vec<MultisigConfig>>,
+    pub coin_selection: Option<CoinSelection>,
+    pub verify: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
@@ -496,6 +693,28 @@ pub enum PayFee {
     To,
 }
 
+/// The order in which the live cells of the items are taken as inputs. The default
+/// strategy pools the categories one by one, while the other strategies select from the
+/// loaded live cells of the items, up to a page at a time, by their spendable capacity
+/// for CKB or amount for UDT.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum CoinSelection {
+    /// Take the cells in the order they are indexed.
+    Default,
+    LargestFirst,
+    SmallestFirst,
+    OldestFirst,
+    /// Take the smallest cell covering the remaining required value, or the largest
+    /// cell if none of them covers it.
+    MinimizeChange,
+}
+
+impl Default for CoinSelection {
+    fn default() -> Self {
+        CoinSelection::Default
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
 pub struct SinceConfig {
     pub flag: SinceFlag,
@@ -503,6 +722,15 @@ pub struct SinceConfig {
     pub value: Uint64,
 }
 
+/// The config of a secp256k1 multisig lock, whose args is the blake160 hash of the
+/// serialized config.
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct MultisigConfig {
+    pub require_first_n: u8,
+    pub threshold: u8,
+    pub pubkey_hashes: Vec<H160>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
 pub struct SimpleTransferPayload {
     pub asset_info: AssetInfo,
@@ -510,6 +738,47 @@ pub struct SimpleTransferPayload {
     pub to: Vec<ToInfo>,
     pub fee_rate: Option<Uint64>,
     pub since: Option<SinceConfig>,
+    pub multisig_configs: Option<Vec<MultisigConfig>>,
+    pub coin_selection: Option<CoinSelection>,
+    pub verify: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct BatchPayoutPayload {
+    pub asset_info: AssetInfo,
+    pub from: Vec<JsonItem>,
+    pub to: Vec<ToInfo>,
+    pub output_capacity_provider: Option<OutputCapacityProvider>,
+    pub pay_fee: Option<PayFee>,
+    pub fee_rate: Option<Uint64>,
+    pub since: Option<SinceConfig>,
+    pub multisig_configs: Option<Vec<MultisigConfig>>,
+    pub max_outputs_per_tx: Option<Uint32>,
+    pub max_tx_size: Option<Uint64>,
+    pub coin_selection: Option<CoinSelection>,
+    pub verify: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct SweepPayload {
+    pub asset_info: AssetInfo,
+    pub from: Vec<JsonItem>,
+    pub to: String,
+    pub fee_rate: Option<Uint64>,
+    pub since: Option<SinceConfig>,
+    pub multisig_configs: Option<Vec<MultisigConfig>>,
+    pub verify: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ConsolidatePayload {
+    pub asset_info: AssetInfo,
+    pub item: JsonItem,
+    pub threshold: Option<Uint128>,
+    pub max_inputs: Option<Uint32>,
+    pub fee_rate: Option<Uint64>,
+    pub multisig_configs: Option<Vec<MultisigConfig>>,
+    pub verify: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
@@ -533,19 +802,219 @@ pub struct DaoDepositPayload {
     pub to: Option<String>,
     pub amount: Uint64,
     pub fee_rate: Option<Uint64>,
+    pub verify: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
 pub struct DaoWithdrawPayload {
     pub from: Vec<JsonItem>,
+    pub out_points: Option<Vec<OutPoint>>,
+    pub amount: Option<Uint64>,
     pub fee_rate: Option<Uint64>,
+    pub verify: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
 pub struct DaoClaimPayload {
     pub from: Vec<JsonItem>,
     pub to: Option<String>,
+    pub out_points: Option<Vec<OutPoint>>,
+    pub amount: Option<Uint64>,
     pub fee_rate: Option<Uint64>,
+    pub verify: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ChequeClaimPayload {
+    pub from: JsonItem,
+    pub out_points: Vec<OutPoint>,
+    pub claim_to: Option<ChequeClaimTarget>,
+    pub fee_rate: Option<Uint64>,
+    pub verify: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq, Copy)]
+pub enum ChequeClaimTarget {
+    #[serde(alias = "secp")]
+    Secp,
+    #[serde(alias = "acp")]
+    Acp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ChequeWithdrawPayload {
+    pub from: JsonItem,
+    pub out_points: Vec<OutPoint>,
+    pub fee_rate: Option<Uint64>,
+    pub verify: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ChequeTransactionResponse {
+    pub tx_view: TransactionView,
+    pub script_groups: Vec<ScriptGroup>,
+    pub cheque_cells: Vec<OutPoint>,
+    pub verification: Option<VerifyTransactionResponse>,
+}
+
+impl ChequeTransactionResponse {
+    pub fn new(response: TransactionCompletionResponse, cheque_cells: Vec<OutPoint>) -> Self {
+        ChequeTransactionResponse {
+            tx_view: response.tx_view,
+            script_groups: response.script_groups,
+            cheque_cells,
+            verification: response.verification,
+        }
+    }
+}
+
+/// The sweep transaction, and whether the items have more cells than one transaction
+/// can sweep.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SweepTransactionResponse {
+    pub tx_view: TransactionView,
+    pub script_groups: Vec<ScriptGroup>,
+    pub partial: bool,
+    pub verification: Option<VerifyTransactionResponse>,
+}
+
+impl SweepTransactionResponse {
+    pub fn new(response: TransactionCompletionResponse, partial: bool) -> Self {
+        SweepTransactionResponse {
+            tx_view: response.tx_view,
+            script_groups: response.script_groups,
+            partial,
+            verification: response.verification,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SendTransactionPayload {
+    pub transaction: Transaction,
+    pub outputs_validator: Option<OutputsValidator>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GetSubmittedTransactionsPayload {
+    pub tx_hashes: Vec<H256>,
+    pub statuses: Vec<TransactionStatus>,
+    pub pagination: PaginationRequest,
+}
+
+/// A transaction sent through mercury and its latest status. The timestamps are
+/// in milliseconds.
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct SubmittedTransaction {
+    pub tx_hash: H256,
+    pub status: TransactionStatus,
+    pub submitted_at: Uint64,
+    pub updated_at: Uint64,
+    pub block_number: Option<BlockNumber>,
+    pub block_hash: Option<H256>,
+    pub reason: Option<String>,
+}
+
+/// A label and free-form JSON metadata attached to an address or identity item. The
+/// timestamp is in milliseconds.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AddressLabel {
+    pub item: JsonItem,
+    pub label: String,
+    pub metadata: Option<serde_json::Value>,
+    pub updated_at: Uint64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SetAddressLabelPayload {
+    pub item: JsonItem,
+    pub label: Option<String>,
+    pub metadata: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SearchAddressLabelsPayload {
+    pub keyword: String,
+    pub pagination: PaginationRequest,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RegisterWebhookPayload {
+    pub url: String,
+    pub items: Vec<JsonItem>,
+    pub secret: String,
+}
+
+/// A webhook notified of the activities of the items. The timestamp is in milliseconds.
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Webhook {
+    pub id: Uint64,
+    pub url: String,
+    pub items: Vec<JsonItem>,
+    pub created_at: Uint64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum WebhookEventType {
+    BlockAppended,
+    BlockReverted,
+}
+
+/// The payload posted to a webhook with the records of the watched items in a block.
+/// A `BlockReverted` event compensates the `BlockAppended` event of the same block.
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct WebhookEvent {
+    pub webhook_id: Uint64,
+    pub event_type: WebhookEventType,
+    pub block_number: BlockNumber,
+    pub block_hash: H256,
+    pub transactions: Vec<WebhookTransaction>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct WebhookTransaction {
+    pub tx_hash: H256,
+    pub records: Vec<Record>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct GetChainEventsPayload {
+    pub cursor: Option<Uint64>,
+    pub limit: Option<Uint64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct GetChainEventsResponse {
+    pub events: Vec<BlockEvent>,
+    pub next_cursor: Uint64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum BlockEventType {
+    BlockApplied,
+    BlockReverted,
+}
+
+/// A `BlockReverted` event undoes the `BlockApplied` event of the same block, so the
+/// consumed cells are live again and the created cells are gone.
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct BlockEvent {
+    pub id: Uint64,
+    pub event_type: BlockEventType,
+    pub block_number: BlockNumber,
+    pub block_hash: H256,
+    pub parent_hash: H256,
+    pub created_cells: Vec<EventCell>,
+    pub consumed_cells: Vec<EventCell>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct EventCell {
+    pub out_point: OutPoint,
+    pub lock_hash: H256,
+    pub type_hash: Option<H256>,
+    pub capacity: Uint64,
+    pub data: Option<JsonBytes>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
@@ -589,6 +1058,31 @@ impl SyncProgress {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum ItemEventType {
+    Created,
+    Consumed,
+    CreationReverted,
+    ConsumptionReverted,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ItemEvent {
+    pub event_type: ItemEventType,
+    pub block_number: BlockNumber,
+    pub block_hash: H256,
+    pub tx_hash: H256,
+    pub cell: indexer::Cell,
+}
+
+/// The chain change pushed from the sync loop to the subscription server,
+/// emitted once per appended or rolled back block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainEvent {
+    pub tip: indexer::Tip,
+    pub item_events: Vec<ItemEvent>,
+}
+
 #[derive(Serialize, Deserialize, Default, Clone, Debug, Hash, PartialEq, Eq)]
 pub struct PaginationRequest {
     pub cursor: Option<Uint64>,
@@ -0,0 +1,138 @@
+use ckb_types::{packed, H256};
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// The inputs of the transactions built by mercury. A reserved cell is skipped
+/// when pooling cells until the reservation is released or expired, so that the
+/// concurrent build requests do not select the same inputs.
+#[derive(Default, Clone, Debug)]
+pub struct InputReservations {
+    cells: HashMap<packed::OutPoint, (H256, Instant)>,
+}
+
+impl InputReservations {
+    pub fn reserve<I: IntoIterator<Item = packed::OutPoint>>(
+        &mut self,
+        tx_hash: H256,
+        out_points: I,
+        ttl: Duration,
+    ) {
+        let now = Instant::now();
+        self.cells.retain(|_, (_, expires_at)| *expires_at > now);
+
+        let expires_at = now + ttl;
+        for out_point in out_points {
+            self.cells.insert(out_point, (tx_hash.clone(), expires_at));
+        }
+    }
+
+    /// Reserve the cell for the owner unless another owner has reserved it, in which
+    /// case `false` is returned.
+    pub fn try_reserve(
+        &mut self,
+        owner: &H256,
+        out_point: packed::OutPoint,
+        ttl: Duration,
+    ) -> bool {
+        let now = Instant::now();
+        match self.cells.get(&out_point) {
+            Some((hash, expires_at)) if hash != owner && *expires_at > now => false,
+            _ => {
+                self.cells.insert(out_point, (owner.clone(), now + ttl));
+                true
+            }
+        }
+    }
+
+    pub fn is_reserved(&self, out_point: &packed::OutPoint) -> bool {
+        self.cells
+            .get(out_point)
+            .map(|(_, expires_at)| *expires_at > Instant::now())
+            .unwrap_or(false)
+    }
+
+    /// Release the inputs reserved by the transaction and return them.
+    pub fn release_transaction(&mut self, tx_hash: &H256) -> Vec<packed::OutPoint> {
+        let out_points = self
+            .cells
+            .iter()
+            .filter(|(_, (hash, _))| hash == tx_hash)
+            .map(|(out_point, _)| out_point.clone())
+            .collect::<Vec<_>>();
+        for out_point in out_points.iter() {
+            self.cells.remove(out_point);
+        }
+        out_points
+    }
+
+    pub fn release_out_points<'a, I: IntoIterator<Item = &'a packed::OutPoint>>(
+        &mut self,
+        out_points: I,
+    ) {
+        if self.cells.is_empty() {
+            return;
+        }
+
+        for out_point in out_points {
+            self.cells.remove(out_point);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ckb_types::prelude::*;
+
+    fn out_point(index: u32) -> packed::OutPoint {
+        packed::OutPoint::new_builder().index(index.pack()).build()
+    }
+
+    #[test]
+    fn test_reserve_and_release() {
+        let mut reservations = InputReservations::default();
+        let tx_hash = H256([1u8; 32]);
+        reservations.reserve(
+            tx_hash.clone(),
+            vec![out_point(0), out_point(1)],
+            Duration::from_secs(60),
+        );
+        assert!(reservations.is_reserved(&out_point(0)));
+        assert!(!reservations.is_reserved(&out_point(2)));
+
+        reservations.release_out_points(vec![out_point(0)].iter());
+        assert!(!reservations.is_reserved(&out_point(0)));
+        assert_eq!(
+            reservations.release_transaction(&tx_hash),
+            vec![out_point(1)]
+        );
+        assert!(!reservations.is_reserved(&out_point(1)));
+    }
+
+    #[test]
+    fn test_try_reserve() {
+        let mut reservations = InputReservations::default();
+        let (owner, other) = (H256([1u8; 32]), H256([2u8; 32]));
+        let ttl = Duration::from_secs(60);
+        assert!(reservations.try_reserve(&owner, out_point(0), ttl));
+        assert!(reservations.try_reserve(&owner, out_point(0), ttl));
+        assert!(!reservations.try_reserve(&other, out_point(0), ttl));
+
+        // the built transaction takes over the cell from the build
+        reservations.reserve(other.clone(), vec![out_point(0)], ttl);
+        assert!(reservations.release_transaction(&owner).is_empty());
+        assert!(!reservations.try_reserve(&owner, out_point(0), ttl));
+
+        reservations.try_reserve(&other, out_point(1), Duration::from_secs(0));
+        assert!(reservations.try_reserve(&owner, out_point(1), ttl));
+    }
+
+    #[test]
+    fn test_reservation_expired() {
+        let mut reservations = InputReservations::default();
+        reservations.reserve(H256([1u8; 32]), vec![out_point(0)], Duration::from_secs(0));
+        assert!(!reservations.is_reserved(&out_point(0)));
+    }
+}
@@ -0,0 +1,104 @@
+use ckb_jsonrpc_types::Status;
+use ckb_types::{bytes::Bytes, core::TransactionView, packed, prelude::*, H256};
+
+use std::collections::HashMap;
+
+/// A cell consumed or created by a transaction in the tx pool.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolCell {
+    pub out_point: packed::OutPoint,
+    pub output: packed::CellOutput,
+    pub data: Bytes,
+}
+
+/// A transaction in the tx pool of the node, with the cells it consumes. The
+/// inputs which cannot be resolved, such as the ones of unknown cells, are absent
+/// from `input_cells`.
+#[derive(Clone, Debug)]
+pub struct PoolTransaction {
+    pub transaction: TransactionView,
+    pub status: Status,
+    pub input_cells: Vec<PoolCell>,
+}
+
+impl PoolTransaction {
+    pub fn output_cells(&self) -> Vec<PoolCell> {
+        output_cells(&self.transaction)
+    }
+}
+
+pub fn output_cells(transaction: &TransactionView) -> Vec<PoolCell> {
+    transaction
+        .outputs_with_data_iter()
+        .enumerate()
+        .map(|(index, (output, data))| PoolCell {
+            out_point: packed::OutPoint::new(transaction.hash(), index as u32),
+            output,
+            data,
+        })
+        .collect()
+}
+
+/// The pending and proposed transactions in the tx pool of the node.
+#[derive(Default, Clone, Debug)]
+pub struct TxPool {
+    transactions: HashMap<H256, PoolTransaction>,
+}
+
+impl TxPool {
+    pub fn new(transactions: Vec<PoolTransaction>) -> Self {
+        TxPool {
+            transactions: transactions
+                .into_iter()
+                .map(|tx| (tx.transaction.hash().unpack(), tx))
+                .collect(),
+        }
+    }
+
+    pub fn get(&self, tx_hash: &H256) -> Option<&PoolTransaction> {
+        self.transactions.get(tx_hash)
+    }
+
+    pub fn transactions(&self) -> impl Iterator<Item = &PoolTransaction> {
+        self.transactions.values()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ckb_types::core::{capacity_bytes, Capacity, TransactionBuilder};
+
+    #[test]
+    fn test_pool_transaction_output_cells() {
+        let output = packed::CellOutput::new_builder()
+            .capacity(capacity_bytes!(100).pack())
+            .build();
+        let transaction = TransactionBuilder::default()
+            .output(output.clone())
+            .output_data(Bytes::from(vec![1u8]).pack())
+            .build();
+        let tx_hash: H256 = transaction.hash().unpack();
+        let pool = TxPool::new(vec![PoolTransaction {
+            transaction: transaction.clone(),
+            status: Status::Pending,
+            input_cells: vec![],
+        }]);
+
+        let pool_tx = pool.get(&tx_hash).unwrap();
+        assert_eq!(
+            pool_tx.output_cells(),
+            vec![PoolCell {
+                out_point: packed::OutPoint::new(transaction.hash(), 0),
+                output,
+                data: Bytes::from(vec![1u8]),
+            }]
+        );
+        assert!(pool.get(&H256::default()).is_none());
+    }
+}
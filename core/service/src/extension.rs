@@ -0,0 +1,183 @@
+mod block_stats;
+
+use common::{derive_more::Display, utils::ScriptInfo, MercuryError, Result};
+use core_storage::Extension;
+use db_sqlx::SQLXPool;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub use block_stats::{BlockStats, BlockStatsExtension, BLOCK_STATS};
+
+type ExtensionBuilder =
+    fn(serde_json::Value, &HashMap<String, ScriptInfo>, SQLXPool) -> Result<Arc<dyn Extension>>;
+
+/// The extensions which can be enabled by `extension_name` in the config. Each
+/// builder receives the JSON config of the extension, the builtin scripts and the
+/// database pool serving its RPC methods.
+const EXTENSION_BUILDERS: &[(&str, ExtensionBuilder)] =
+    &[(BLOCK_STATS, BlockStatsExtension::build)];
+
+#[derive(Clone, Debug, Display)]
+pub enum ExtensionError {
+    #[display(fmt = "Unknown extension {}", _0)]
+    UnknownExtension(String),
+
+    #[display(fmt = "Extension {} is enabled more than once", _0)]
+    DuplicateExtension(String),
+
+    #[display(fmt = "Invalid config of extension {}: {}", _0, _1)]
+    InvalidConfig(String, String),
+}
+
+impl std::error::Error for ExtensionError {}
+
+pub fn build_extensions(
+    extensions_config: Vec<(String, String)>,
+    builtin_scripts: &HashMap<String, ScriptInfo>,
+    pool: SQLXPool,
+) -> Result<Vec<Arc<dyn Extension>>> {
+    let mut extensions: Vec<Arc<dyn Extension>> = vec![];
+
+    for (name, config) in extensions_config {
+        if extensions.iter().any(|extension| extension.name() == name) {
+            return Err(MercuryError::extension(ExtensionError::DuplicateExtension(name)).into());
+        }
+
+        let builder = EXTENSION_BUILDERS
+            .iter()
+            .find(|(builder_name, _)| *builder_name == name)
+            .map(|(_, builder)| builder)
+            .ok_or_else(|| {
+                MercuryError::extension(ExtensionError::UnknownExtension(name.clone()))
+            })?;
+        let config = serde_json::from_str(&config).map_err(|e| {
+            MercuryError::extension(ExtensionError::InvalidConfig(name.clone(), e.to_string()))
+        })?;
+
+        extensions.push(builder(config, builtin_scripts, pool.clone())?);
+    }
+
+    Ok(extensions)
+}
+
+pub fn to_extension_info(extension: &dyn Extension) -> core_rpc_types::Extension {
+    let (scripts, cell_deps) = extension
+        .script_infos()
+        .into_iter()
+        .map(|info| (info.script.into(), info.cell_dep.into()))
+        .unzip();
+
+    core_rpc_types::Extension {
+        name: extension.name().to_string(),
+        scripts,
+        cell_deps,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core_storage::{DBDriver, RelationalStorage, Storage};
+
+    use ckb_jsonrpc_types::BlockNumber;
+    use ckb_types::core::{
+        capacity_bytes, BlockBuilder, Capacity, HeaderBuilder, TransactionBuilder,
+    };
+    use ckb_types::{packed, prelude::*, H256};
+
+    #[tokio::test]
+    async fn test_block_stats_extension() {
+        let mut store = RelationalStorage::new(0, 0, 100, 0, 60, 1800, 30);
+        let extensions = build_extensions(
+            vec![(BLOCK_STATS.to_string(), "{}".to_string())],
+            &HashMap::new(),
+            store.get_pool(),
+        )
+        .unwrap();
+        store.register_extensions(extensions.clone());
+        store
+            .connect(DBDriver::SQLite, ":memory:", "", 0, "", "")
+            .await
+            .unwrap();
+        let tx = store.sqlx_pool.transaction().await.unwrap();
+        xsql_test::create_tables(tx).await.unwrap();
+        store.create_extension_tables().await.unwrap();
+        // the tables are created every time mercury starts
+        store.create_extension_tables().await.unwrap();
+
+        let output = |capacity: Capacity| {
+            packed::CellOutputBuilder::default()
+                .capacity(capacity.pack())
+                .build()
+        };
+        let cellbase = TransactionBuilder::default()
+            .input(packed::CellInput::new_cellbase_input(0))
+            .output(output(capacity_bytes!(1000)))
+            .output_data(Default::default())
+            .build();
+        let transfer = TransactionBuilder::default()
+            .input(packed::CellInput::new(
+                packed::OutPoint::new(cellbase.hash(), 0),
+                0,
+            ))
+            .outputs(vec![
+                output(capacity_bytes!(600)),
+                output(capacity_bytes!(300)),
+            ])
+            .outputs_data(vec![Default::default(), Default::default()])
+            .build();
+        let block = BlockBuilder::default()
+            .transactions(vec![cellbase, transfer])
+            .header(HeaderBuilder::default().number(0.pack()).build())
+            .build();
+        store.append_block(block.clone()).await.unwrap();
+
+        let methods = extensions[0].rpc_methods();
+        let stats: Option<BlockStats> = methods
+            .call("get_block_stats", [BlockNumber::from(0)])
+            .await
+            .unwrap();
+        assert_eq!(
+            Some(BlockStats {
+                block_number: 0.into(),
+                block_hash: block.hash().unpack(),
+                transaction_count: 2u32.into(),
+                input_count: 1u32.into(),
+                output_count: 3u32.into(),
+                output_capacity: capacity_bytes!(1900).as_u64().into(),
+            }),
+            stats
+        );
+
+        let block_hash: H256 = block.hash().unpack();
+        store.rollback_block(0, block_hash).await.unwrap();
+        let stats: Option<BlockStats> = methods
+            .call("get_block_stats", [BlockNumber::from(0)])
+            .await
+            .unwrap();
+        assert_eq!(None, stats);
+    }
+
+    #[test]
+    fn test_build_extensions() {
+        let pool = RelationalStorage::new(0, 0, 100, 0, 60, 1800, 30).get_pool();
+        let build = |configs: Vec<(&str, &str)>| {
+            build_extensions(
+                configs
+                    .into_iter()
+                    .map(|(name, config)| (name.to_string(), config.to_string()))
+                    .collect(),
+                &HashMap::new(),
+                pool.clone(),
+            )
+        };
+
+        let extensions = build(vec![(BLOCK_STATS, "{}")]).unwrap();
+        assert_eq!(BLOCK_STATS, to_extension_info(extensions[0].as_ref()).name);
+        assert!(build(vec![("unknown", "{}")]).is_err());
+        assert!(build(vec![(BLOCK_STATS, "invalid")]).is_err());
+        assert!(build(vec![(BLOCK_STATS, "{}"), (BLOCK_STATS, "{}")]).is_err());
+    }
+}
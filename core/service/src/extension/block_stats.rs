@@ -0,0 +1,177 @@
+use common::{async_trait, utils::ScriptInfo, Result};
+use core_storage::Extension;
+use db_sqlx::SQLXPool;
+
+use ckb_jsonrpc_types::{BlockNumber, Capacity, Uint32};
+use ckb_types::core::{self, BlockView};
+use ckb_types::{prelude::*, H256};
+use jsonrpsee_core::server::rpc_module::{Methods, RpcModule};
+use jsonrpsee_core::Error;
+use serde::{Deserialize, Serialize};
+use sqlx::{Any, Row, Transaction};
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub const BLOCK_STATS: &str = "block_stats";
+
+/// The statistics of a block, served by the `get_block_stats` RPC method.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BlockStats {
+    pub block_number: BlockNumber,
+    pub block_hash: H256,
+    pub transaction_count: Uint32,
+    pub input_count: Uint32,
+    pub output_count: Uint32,
+    pub output_capacity: Capacity,
+}
+
+impl BlockStats {
+    fn new(block: &BlockView) -> Self {
+        let transactions = block.transactions();
+        let output_capacity = transactions
+            .iter()
+            .flat_map(|tx| tx.outputs().into_iter())
+            .map(|output| Unpack::<u64>::unpack(&output.capacity()))
+            .sum::<u64>();
+
+        BlockStats {
+            block_number: block.number().into(),
+            block_hash: block.hash().unpack(),
+            transaction_count: (transactions.len() as u32).into(),
+            // the input of the cellbase does not spend any cell
+            input_count: (transactions
+                .iter()
+                .skip(1)
+                .map(|tx| tx.inputs().len())
+                .sum::<usize>() as u32)
+                .into(),
+            output_count: (transactions
+                .iter()
+                .map(|tx| tx.outputs().len())
+                .sum::<usize>() as u32)
+                .into(),
+            output_capacity: output_capacity.into(),
+        }
+    }
+}
+
+/// Counts the transactions, inputs and outputs of each block. The config is `{}`.
+#[derive(Debug)]
+pub struct BlockStatsExtension {
+    pool: SQLXPool,
+}
+
+impl BlockStatsExtension {
+    pub fn build(
+        _config: serde_json::Value,
+        _builtin_scripts: &HashMap<String, ScriptInfo>,
+        pool: SQLXPool,
+    ) -> Result<Arc<dyn Extension>> {
+        Ok(Arc::new(BlockStatsExtension { pool }))
+    }
+}
+
+#[async_trait]
+impl Extension for BlockStatsExtension {
+    fn name(&self) -> &str {
+        BLOCK_STATS
+    }
+
+    fn script_infos(&self) -> Vec<ScriptInfo> {
+        vec![]
+    }
+
+    fn table_schemas(&self) -> Vec<String> {
+        vec![
+            "CREATE TABLE IF NOT EXISTS mercury_ext_block_stats(
+            block_hash bytea PRIMARY KEY,
+            block_number int NOT NULL,
+            transaction_count int NOT NULL,
+            input_count int NOT NULL,
+            output_count int NOT NULL,
+            output_capacity bigint NOT NULL
+            )"
+            .to_string(),
+            "CREATE INDEX IF NOT EXISTS \"index_ext_block_stats_table_block_number\"
+            ON \"mercury_ext_block_stats\" (\"block_number\")"
+                .to_string(),
+        ]
+    }
+
+    fn rpc_methods(&self) -> Methods {
+        let mut module = RpcModule::new(self.pool.clone());
+        module
+            .register_async_method("get_block_stats", |params, pool| async move {
+                let block_number: BlockNumber = params.one()?;
+                query_block_stats(&pool, block_number.into())
+                    .await
+                    .map_err(|e| Error::Custom(e.to_string()))
+            })
+            .expect("register get_block_stats");
+        module.into()
+    }
+
+    async fn append_block(&self, block: &BlockView, tx: &mut Transaction<'_, Any>) -> Result<()> {
+        let stats = BlockStats::new(block);
+        let output_capacity: u64 = stats.output_capacity.into();
+        SQLXPool::new_query(
+            r#"
+            INSERT INTO mercury_ext_block_stats(block_hash, block_number, transaction_count,
+            input_count, output_count, output_capacity)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(stats.block_hash.as_bytes())
+        .bind(block.number() as i32)
+        .bind(stats.transaction_count.value() as i32)
+        .bind(stats.input_count.value() as i32)
+        .bind(stats.output_count.value() as i32)
+        .bind(output_capacity as i64)
+        .execute(&mut *tx)
+        .await?;
+        Ok(())
+    }
+
+    async fn rollback_block(
+        &self,
+        _block_number: core::BlockNumber,
+        block_hash: &H256,
+        tx: &mut Transaction<'_, Any>,
+    ) -> Result<()> {
+        SQLXPool::new_query(
+            r#"
+            DELETE FROM mercury_ext_block_stats
+            WHERE block_hash = $1
+            "#,
+        )
+        .bind(block_hash.as_bytes())
+        .execute(&mut *tx)
+        .await?;
+        Ok(())
+    }
+}
+
+async fn query_block_stats(
+    pool: &SQLXPool,
+    block_number: core::BlockNumber,
+) -> Result<Option<BlockStats>> {
+    let query = SQLXPool::new_query(
+        r#"
+        SELECT block_hash, block_number, transaction_count, input_count, output_count,
+        output_capacity
+        FROM mercury_ext_block_stats
+        WHERE block_number = $1
+        "#,
+    )
+    .bind(block_number as i32);
+    let row = pool.fetch_optional(query).await?;
+    Ok(row.map(|row| BlockStats {
+        block_number: (row.get::<i32, _>("block_number") as u64).into(),
+        block_hash: H256::from_slice(&row.get::<Vec<u8>, _>("block_hash")).expect("get block hash"),
+        transaction_count: (row.get::<i32, _>("transaction_count") as u32).into(),
+        input_count: (row.get::<i32, _>("input_count") as u32).into(),
+        output_count: (row.get::<i32, _>("output_count") as u32).into(),
+        output_capacity: (row.get::<i64, _>("output_capacity") as u64).into(),
+    }))
+}
@@ -1,19 +1,35 @@
 #![allow(clippy::mutable_key_type, dead_code)]
 
-use common::{anyhow::anyhow, utils::ScriptInfo, NetworkType, Result};
+mod extension;
+
+use common::utils::{unix_timestamp_millis, ScriptInfo};
+use common::{anyhow::anyhow, DetailedCell, NetworkType, PaginationRequest, Result};
 use core_ckb_client::{CkbRpc, CkbRpcClient};
-use core_rpc::{MercuryRpcImpl, MercuryRpcServer};
-use core_rpc_types::lazy::{CURRENT_BLOCK_NUMBER, CURRENT_EPOCH_NUMBER, TX_POOL_CACHE};
-use core_rpc_types::{SyncProgress, SyncState};
-use core_storage::{DBDriver, RelationalStorage, Storage};
+use core_rpc::{
+    MercuryRpcImpl, MercuryRpcServer, MercurySubscriptionImpl, MercurySubscriptionServer,
+};
+use core_rpc_types::lazy::{
+    CURRENT_BLOCK_NUMBER, CURRENT_EPOCH_NUMBER, INPUT_RESERVATIONS, TX_POOL, TX_POOL_CACHE,
+};
+use core_rpc_types::tx_pool::{output_cells, PoolCell, PoolTransaction, TxPool};
+use core_rpc_types::{
+    indexer, ChainEvent, ItemEvent, ItemEventType, SyncProgress, SyncState, TransactionStatus,
+    WebhookEventType,
+};
+use core_storage::{DBDriver, Extension, RelationalStorage, Storage};
 use core_synchronization::{Synchronization, TASK_LEN};
+use extension::{build_extensions, to_extension_info};
 
-use ckb_jsonrpc_types::{RawTxPool, TransactionWithStatus};
-use ckb_types::core::{BlockNumber, BlockView, EpochNumberWithFraction, RationalU256};
-use ckb_types::{packed, H256};
+use ckb_jsonrpc_types::{JsonBytes, RawTxPool, Status, TransactionWithStatus};
+use ckb_types::core::{
+    BlockNumber, BlockView, EpochNumberWithFraction, RationalU256, TransactionView,
+};
+use ckb_types::{packed, prelude::*, H256};
 use jsonrpsee_http_server::{HttpServerBuilder, HttpServerHandle};
+use jsonrpsee_ws_server::{WsServerBuilder, WsServerHandle};
 use log::{error, info, warn};
 use parking_lot::RwLock;
+use tokio::sync::broadcast;
 use tokio::time::{sleep, Duration};
 
 use std::collections::{HashMap, HashSet};
@@ -21,6 +37,9 @@ use std::net::ToSocketAddrs;
 use std::sync::Arc;
 use std::time::Instant;
 
+const CHAIN_EVENT_CHANNEL_SIZE: usize = 128;
+const WEBHOOK_DELIVERY_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(Clone, Debug)]
 pub struct Service {
     store: RelationalStorage,
@@ -35,6 +54,9 @@ pub struct Service {
     sync_state: Arc<RwLock<SyncState>>,
     pool_cache_size: u16,
     is_pprof_enabled: bool,
+    input_reservation_ttl: u64,
+    chain_event_sender: broadcast::Sender<ChainEvent>,
+    extensions: Vec<Arc<dyn Extension>>,
 }
 
 impl Service {
@@ -57,9 +79,11 @@ impl Service {
         cheque_since: u64,
         pool_cache_size: u16,
         is_pprof_enabled: bool,
+        extensions_config: Vec<(String, String)>,
+        input_reservation_ttl: u64,
     ) -> Self {
         let ckb_client = CkbRpcClient::new(ckb_uri);
-        let store = RelationalStorage::new(
+        let mut store = RelationalStorage::new(
             center_id,
             machine_id,
             max_connections,
@@ -72,6 +96,10 @@ impl Service {
         let cellbase_maturity = RationalU256::from_u256(cellbase_maturity.into());
         let cheque_since = RationalU256::from_u256(cheque_since.into());
         let sync_state = Arc::new(RwLock::new(SyncState::ReadOnly));
+        let (chain_event_sender, _) = broadcast::channel(CHAIN_EVENT_CHANNEL_SIZE);
+        let extensions = build_extensions(extensions_config, &builtin_scripts, store.get_pool())
+            .expect("build extensions");
+        store.register_extensions(extensions.clone());
 
         info!("Mercury running in CKB {:?}", network_type);
 
@@ -88,19 +116,23 @@ impl Service {
             sync_state,
             pool_cache_size,
             is_pprof_enabled,
+            input_reservation_ttl,
+            chain_event_sender,
+            extensions,
         }
     }
 
     pub async fn init(
         &mut self,
         listen_address: String,
+        ws_listen_address: Option<String>,
         db_driver: String,
         db_name: String,
         host: String,
         port: u16,
         user: String,
         password: String,
-    ) -> HttpServerHandle {
+    ) -> (HttpServerHandle, Option<WsServerHandle>) {
         self.store
             .connect(
                 DBDriver::from_str(&db_driver),
@@ -112,6 +144,10 @@ impl Service {
             )
             .await
             .expect("connect database");
+        self.store
+            .create_extension_tables()
+            .await
+            .expect("create extension tables");
 
         let server = HttpServerBuilder::default()
             .max_response_body_size(u32::MAX)
@@ -125,7 +161,28 @@ impl Service {
             .await
             .expect("build server");
 
-        let mercury_rpc_impl = MercuryRpcImpl::new(
+        let mut rpc_module = self.build_rpc_impl().into_rpc();
+        for extension in self.extensions.iter() {
+            info!("Extension {} enabled", extension.name());
+            rpc_module
+                .merge(extension.rpc_methods())
+                .expect("merge extension rpc methods");
+        }
+
+        let ws_server_handle = match ws_listen_address {
+            Some(ws_listen_address) => Some(self.start_ws_server(ws_listen_address).await),
+            None => None,
+        };
+
+        info!("Mercury Running!");
+
+        let http_server_handle = server.start(rpc_module).expect("Start jsonrpc http server");
+
+        (http_server_handle, ws_server_handle)
+    }
+
+    fn build_rpc_impl(&self) -> MercuryRpcImpl<CkbRpcClient> {
+        MercuryRpcImpl::new(
             self.store.clone(),
             self.builtin_scripts.clone(),
             self.ckb_client.clone(),
@@ -135,13 +192,37 @@ impl Service {
             Arc::clone(&self.sync_state),
             self.pool_cache_size,
             self.is_pprof_enabled,
-        );
+            self.extensions
+                .iter()
+                .map(|extension| to_extension_info(extension.as_ref()))
+                .collect(),
+            self.input_reservation_ttl,
+        )
+    }
 
-        info!("Mercury Running!");
+    async fn start_ws_server(&self, ws_listen_address: String) -> WsServerHandle {
+        let server = WsServerBuilder::default()
+            .max_response_body_size(u32::MAX)
+            .build(
+                ws_listen_address
+                    .to_socket_addrs()
+                    .expect("config ws_listen_address parsed")
+                    .next()
+                    .expect("ws_listen_address parsed"),
+            )
+            .await
+            .expect("build ws server");
+
+        let subscription_impl = MercurySubscriptionImpl::new(self.chain_event_sender.clone());
+
+        info!(
+            "Mercury subscription server listening on {}",
+            ws_listen_address
+        );
 
         server
-            .start(mercury_rpc_impl.into_rpc())
-            .expect("Start jsonrpc http server")
+            .start(subscription_impl.into_rpc())
+            .expect("Start jsonrpc ws server")
     }
 
     pub async fn do_sync(&mut self, max_task_number: usize) -> Result<()> {
@@ -159,6 +240,7 @@ impl Service {
             max_task_number,
             node_tip,
             Arc::clone(&self.sync_state),
+            self.extensions.clone(),
         );
 
         if (!sync_handler.is_previous_in_update().await?)
@@ -173,6 +255,7 @@ impl Service {
                 max_task_number,
                 db_tip,
                 Arc::clone(&self.sync_state),
+                self.extensions.clone(),
             )
             .build_indexer_cell_table()
             .await;
@@ -188,17 +271,29 @@ impl Service {
 
     pub async fn start(&mut self, flush_pool_interval: u64) {
         let client_clone = self.ckb_client.clone();
+        let store_clone = self.store.clone();
+        let use_tx_pool_cache = self.use_tx_pool_cache;
+
+        tokio::spawn(async move {
+            poll_tx_pool(
+                client_clone,
+                store_clone,
+                flush_pool_interval,
+                use_tx_pool_cache,
+            )
+            .await;
+        });
 
-        if self.use_tx_pool_cache {
-            tokio::spawn(async move {
-                update_tx_pool_cache(client_clone, flush_pool_interval).await;
-            });
-        }
+        let webhook_rpc = Arc::new(self.build_rpc_impl());
+        let webhook_rpc_clone = Arc::clone(&webhook_rpc);
+        tokio::spawn(async move {
+            deliver_webhook_events(webhook_rpc_clone).await;
+        });
 
-        self.run().await;
+        self.run(webhook_rpc).await;
     }
 
-    async fn run(&mut self) {
+    async fn run(&mut self, webhook_rpc: Arc<MercuryRpcImpl<CkbRpcClient>>) {
         let mut tip = 0;
 
         if let Some(mut state) = self.sync_state.try_write() {
@@ -219,19 +314,71 @@ impl Service {
                             let block_number = block.number();
                             log::info!("append {}, {}", block_number, block.hash());
                             let start = Instant::now();
-                            self.store.append_block(block).await.expect("append block");
+                            let deliveries = match self
+                                .build_webhook_deliveries(
+                                    &webhook_rpc,
+                                    &block,
+                                    WebhookEventType::BlockAppended,
+                                )
+                                .await
+                            {
+                                Some(deliveries) => deliveries,
+                                None => continue,
+                            };
+                            self.store
+                                .append_block_with_webhook_deliveries(
+                                    block.clone(),
+                                    deliveries,
+                                    unix_timestamp_millis(),
+                                )
+                                .await
+                                .expect("append block");
+                            release_block_inputs(&block);
                             let duration = start.elapsed();
                             log::info!(
                                 "append {} time elapsed is: {:?} ms",
                                 block_number,
                                 duration.as_millis()
                             );
+                            self.publish_block_appended(&block).await;
                         } else {
                             info!("rollback {}, {}", tip_number, tip_hash);
+                            let block = match self
+                                .store
+                                .get_block(Some(tip_hash.clone()), Some(tip_number))
+                                .await
+                            {
+                                Ok(block) => block,
+                                Err(e) => {
+                                    error!("get rollback block {} error {:?}", tip_number, e);
+                                    sleep(self.poll_interval).await;
+                                    continue;
+                                }
+                            };
+                            let deliveries = match self
+                                .build_webhook_deliveries(
+                                    &webhook_rpc,
+                                    &block,
+                                    WebhookEventType::BlockReverted,
+                                )
+                                .await
+                            {
+                                Some(deliveries) => deliveries,
+                                None => continue,
+                            };
+                            let rollback_events = self
+                                .collect_rollback_events(tip_number, tip_hash.clone())
+                                .await;
                             self.store
-                                .rollback_block(tip_number, tip_hash)
+                                .rollback_block_with_webhook_deliveries(
+                                    tip_number,
+                                    tip_hash,
+                                    deliveries,
+                                    unix_timestamp_millis(),
+                                )
                                 .await
                                 .expect("rollback block");
+                            self.publish_block_reverted(rollback_events).await;
                         }
                     }
 
@@ -250,7 +397,27 @@ impl Service {
                     Ok(Some(block)) => {
                         log::info!("append {} block", 0);
                         self.change_current_epoch(block.epoch().to_rational());
-                        self.store.append_block(block).await.expect("append block");
+                        let deliveries = match self
+                            .build_webhook_deliveries(
+                                &webhook_rpc,
+                                &block,
+                                WebhookEventType::BlockAppended,
+                            )
+                            .await
+                        {
+                            Some(deliveries) => deliveries,
+                            None => continue,
+                        };
+                        self.store
+                            .append_block_with_webhook_deliveries(
+                                block.clone(),
+                                deliveries,
+                                unix_timestamp_millis(),
+                            )
+                            .await
+                            .expect("append block");
+                        release_block_inputs(&block);
+                        self.publish_block_appended(&block).await;
                     }
 
                     Ok(None) => {
@@ -268,6 +435,176 @@ impl Service {
         }
     }
 
+    async fn publish_block_appended(&self, block: &BlockView) {
+        if self.chain_event_sender.receiver_count() == 0 {
+            return;
+        }
+
+        match self.collect_item_events(block, false).await {
+            Ok(item_events) => {
+                let tip = indexer::Tip {
+                    block_hash: block.hash().unpack(),
+                    block_number: block.number().into(),
+                };
+                let _ = self
+                    .chain_event_sender
+                    .send(ChainEvent { tip, item_events });
+            }
+            Err(e) => error!(
+                "collect item events of block {} error {:?}",
+                block.number(),
+                e
+            ),
+        }
+    }
+
+    /// The reverted cells must be collected before the block is removed from
+    /// the database, otherwise the consumed cells cannot be loaded any more.
+    async fn collect_rollback_events(
+        &self,
+        block_number: BlockNumber,
+        block_hash: H256,
+    ) -> Option<Vec<ItemEvent>> {
+        if self.chain_event_sender.receiver_count() == 0 {
+            return None;
+        }
+
+        let block = match self
+            .store
+            .get_block(Some(block_hash), Some(block_number))
+            .await
+        {
+            Ok(block) => block,
+            Err(e) => {
+                error!("get rollback block {} error {:?}", block_number, e);
+                return None;
+            }
+        };
+
+        match self.collect_item_events(&block, true).await {
+            Ok(item_events) => Some(item_events),
+            Err(e) => {
+                error!(
+                    "collect item events of block {} error {:?}",
+                    block_number, e
+                );
+                None
+            }
+        }
+    }
+
+    async fn publish_block_reverted(&self, item_events: Option<Vec<ItemEvent>>) {
+        let item_events = match item_events {
+            Some(item_events) => item_events,
+            None => return,
+        };
+
+        match self.store.get_tip().await {
+            Ok(Some((block_number, block_hash))) => {
+                let tip = indexer::Tip {
+                    block_hash,
+                    block_number: block_number.into(),
+                };
+                let _ = self
+                    .chain_event_sender
+                    .send(ChainEvent { tip, item_events });
+            }
+            Ok(None) => (),
+            Err(e) => error!("get tip after rollback error {:?}", e),
+        }
+    }
+
+    async fn collect_item_events(
+        &self,
+        block: &BlockView,
+        is_rollback: bool,
+    ) -> Result<Vec<ItemEvent>> {
+        let (created, consumed) = if is_rollback {
+            (
+                ItemEventType::CreationReverted,
+                ItemEventType::ConsumptionReverted,
+            )
+        } else {
+            (ItemEventType::Created, ItemEventType::Consumed)
+        };
+        let block_number = block.number();
+        let block_hash: H256 = block.hash().unpack();
+        let mut item_events = vec![];
+
+        let input_out_points = block
+            .transactions()
+            .iter()
+            .filter(|tx| !tx.is_cellbase())
+            .flat_map(|tx| tx.input_pts_iter())
+            .collect::<Vec<_>>();
+        let mut input_cells: HashMap<packed::OutPoint, DetailedCell> = self
+            .store
+            .get_cells_by_out_points(input_out_points)
+            .await?
+            .into_iter()
+            .map(|cell| (cell.out_point.clone(), cell))
+            .collect();
+
+        for (tx_index, tx) in block.transactions().iter().enumerate() {
+            let tx_hash: H256 = tx.hash().unpack();
+
+            if !tx.is_cellbase() {
+                for out_point in tx.input_pts_iter() {
+                    if let Some(cell) = input_cells.remove(&out_point) {
+                        item_events.push(ItemEvent {
+                            event_type: consumed.clone(),
+                            block_number: block_number.into(),
+                            block_hash: block_hash.clone(),
+                            tx_hash: tx_hash.clone(),
+                            cell: cell.into(),
+                        });
+                    }
+                }
+            }
+
+            for (index, (output, data)) in tx.outputs_with_data_iter().enumerate() {
+                let cell = indexer::Cell {
+                    output: output.into(),
+                    output_data: JsonBytes::from_bytes(data),
+                    out_point: packed::OutPoint::new(tx.hash(), index as u32).into(),
+                    block_number: block_number.into(),
+                    tx_index: (tx_index as u32).into(),
+                };
+                item_events.push(ItemEvent {
+                    event_type: created.clone(),
+                    block_number: block_number.into(),
+                    block_hash: block_hash.clone(),
+                    tx_hash: tx_hash.clone(),
+                    cell,
+                });
+            }
+        }
+
+        Ok(item_events)
+    }
+
+    /// Build the webhook events of the block. If it fails, the block is not synced and is
+    /// retried after the poll interval, so that no events of the webhooks are lost.
+    async fn build_webhook_deliveries(
+        &self,
+        rpc: &MercuryRpcImpl<CkbRpcClient>,
+        block: &BlockView,
+        event_type: WebhookEventType,
+    ) -> Option<Vec<(u64, String)>> {
+        match rpc.build_webhook_deliveries(block, event_type).await {
+            Ok(deliveries) => Some(deliveries),
+            Err(e) => {
+                error!(
+                    "build webhook events of block {} error {:?}",
+                    block.number(),
+                    e
+                );
+                sleep(self.poll_interval).await;
+                None
+            }
+        }
+    }
+
     async fn get_block_by_number(&self, block_number: BlockNumber) -> Result<Option<BlockView>> {
         log::info!("get block number {}", block_number);
         let start = Instant::now();
@@ -330,10 +667,27 @@ impl Service {
     }
 }
 
-async fn update_tx_pool_cache(ckb_client: CkbRpcClient, flush_cache_interval: u64) {
+/// Poll the tx pool to track the submitted transactions and to index the pool
+/// transactions, and to refresh the tx pool cache if it is used.
+async fn poll_tx_pool(
+    ckb_client: CkbRpcClient,
+    store: RelationalStorage,
+    flush_cache_interval: u64,
+    use_tx_pool_cache: bool,
+) {
     loop {
         match ckb_client.get_raw_tx_pool(Some(true)).await {
-            Ok(raw_pool) => handle_raw_tx_pool(&ckb_client, raw_pool).await,
+            Ok(raw_pool) => {
+                let (pending, proposed) = pool_tx_hashes(raw_pool);
+                release_pool_transactions(pending.iter().chain(proposed.iter()));
+                let hashes = pending.iter().chain(proposed.iter()).cloned().collect();
+                handle_raw_tx_pool(&ckb_client, &store, hashes, use_tx_pool_cache).await;
+                if let Err(e) =
+                    update_submitted_transactions(&ckb_client, &store, pending, proposed).await
+                {
+                    error!("update submitted transactions error {:?}", e);
+                }
+            }
             Err(e) => error!("get raw tx pool error {:?}", e),
         }
 
@@ -341,20 +695,33 @@ async fn update_tx_pool_cache(ckb_client: CkbRpcClient, flush_cache_interval: u6
     }
 }
 
-async fn handle_raw_tx_pool(ckb_client: &CkbRpcClient, raw_pool: RawTxPool) {
+/// Index the pool transactions with their input and output cells, which are reported as
+/// pending. The consumed out points are only cached to be skipped when building
+/// transactions if `use_tx_pool_cache` is enabled.
+async fn handle_raw_tx_pool(
+    ckb_client: &CkbRpcClient,
+    store: &RelationalStorage,
+    hashes: Vec<H256>,
+    use_tx_pool_cache: bool,
+) {
     let mut input_set: HashSet<packed::OutPoint> = HashSet::new();
-    let hashes = tx_hash_list(raw_pool);
+    let mut pool_txs = vec![];
 
     if let Ok(res) = ckb_client.get_transactions(hashes).await {
         for item in res.iter() {
             match item {
                 Some(TransactionWithStatus {
                     transaction: Some(tx_view),
+                    tx_status,
                     ..
                 }) => {
                     tx_view.inner.inputs.iter().for_each(|input| {
                         input_set.insert(input.previous_output.clone().into());
                     });
+                    pool_txs.push((
+                        packed::Transaction::from(tx_view.inner.clone()).into_view(),
+                        tx_status.status.clone(),
+                    ));
                 }
 
                 _ => warn!("Get transaction from pool failed"),
@@ -362,28 +729,200 @@ async fn handle_raw_tx_pool(ckb_client: &CkbRpcClient, raw_pool: RawTxPool) {
         }
     }
 
-    let mut pool_cache = TX_POOL_CACHE.write();
-    *pool_cache = input_set;
+    if use_tx_pool_cache {
+        INPUT_RESERVATIONS
+            .write()
+            .release_out_points(input_set.iter());
+        let mut pool_cache = TX_POOL_CACHE.write();
+        *pool_cache = input_set;
+    }
+
+    let pool = TxPool::new(index_pool_transactions(store, pool_txs).await);
+    *TX_POOL.write() = pool;
 }
 
-fn tx_hash_list(raw_pool: RawTxPool) -> Vec<H256> {
-    match raw_pool {
-        RawTxPool::Ids(mut ids) => {
-            let mut ret = ids.pending;
-            ret.append(&mut ids.proposed);
-            ret
-        }
-        RawTxPool::Verbose(map) => {
-            let mut ret = map.pending.into_iter().map(|(k, _v)| k).collect::<Vec<_>>();
-            let mut proposed = map
-                .proposed
+/// Resolve the cells consumed by the pool transactions, from the outputs of the other
+/// pool transactions or from the database. The inputs of a transaction which is
+/// already indexed are not resolved again.
+async fn index_pool_transactions(
+    store: &RelationalStorage,
+    pool_txs: Vec<(TransactionView, Status)>,
+) -> Vec<PoolTransaction> {
+    let pool_outputs: HashMap<packed::OutPoint, PoolCell> = pool_txs
+        .iter()
+        .flat_map(|(tx, _)| output_cells(tx))
+        .map(|cell| (cell.out_point.clone(), cell))
+        .collect();
+
+    let indexed_cells: HashMap<H256, Vec<PoolCell>> = {
+        let tx_pool = TX_POOL.read();
+        pool_txs
+            .iter()
+            .filter_map(|(tx, _)| {
+                let tx_hash: H256 = tx.hash().unpack();
+                tx_pool
+                    .get(&tx_hash)
+                    .map(|pool_tx| (tx_hash, pool_tx.input_cells.clone()))
+            })
+            .collect()
+    };
+    let stored_out_points = pool_txs
+        .iter()
+        .filter(|(tx, _)| !indexed_cells.contains_key(&tx.hash().unpack()))
+        .flat_map(|(tx, _)| tx.input_pts_iter())
+        .filter(|out_point| !pool_outputs.contains_key(out_point))
+        .collect::<Vec<_>>();
+    let stored_cells: HashMap<packed::OutPoint, PoolCell> =
+        match store.get_cells_by_out_points(stored_out_points).await {
+            Ok(cells) => cells
                 .into_iter()
-                .map(|(k, _v)| k)
-                .collect::<Vec<_>>();
+                .map(|cell| {
+                    let pool_cell = PoolCell {
+                        out_point: cell.out_point,
+                        output: cell.cell_output,
+                        data: cell.cell_data,
+                    };
+                    (pool_cell.out_point.clone(), pool_cell)
+                })
+                .collect(),
+            Err(e) => {
+                error!("get input cells of pool transactions error {:?}", e);
+                HashMap::new()
+            }
+        };
+
+    let mut ret = Vec::with_capacity(pool_txs.len());
+    for (tx, status) in pool_txs.into_iter() {
+        let tx_hash: H256 = tx.hash().unpack();
+        let input_cells = match indexed_cells.get(&tx_hash) {
+            Some(input_cells) => input_cells.clone(),
+            None => tx
+                .input_pts_iter()
+                .filter_map(|out_point| {
+                    pool_outputs
+                        .get(&out_point)
+                        .or_else(|| stored_cells.get(&out_point))
+                        .cloned()
+                })
+                .collect(),
+        };
+        ret.push(PoolTransaction {
+            transaction: tx,
+            status,
+            input_cells,
+        });
+    }
+    ret
+}
 
-            ret.append(&mut proposed);
-            ret
+async fn deliver_webhook_events(rpc: Arc<MercuryRpcImpl<CkbRpcClient>>) {
+    loop {
+        if let Err(e) = rpc.deliver_webhook_events().await {
+            error!("deliver webhook events error {:?}", e);
         }
+
+        sleep(WEBHOOK_DELIVERY_INTERVAL).await;
+    }
+}
+
+/// Release the inputs reserved by the transactions in the tx pool. The inputs of a
+/// transaction changed after it is built are released by the tx pool cache.
+fn release_pool_transactions<'a, I: Iterator<Item = &'a H256>>(tx_hashes: I) {
+    let mut reservations = INPUT_RESERVATIONS.write();
+    for tx_hash in tx_hashes {
+        reservations.release_transaction(tx_hash);
+    }
+}
+
+fn release_block_inputs(block: &BlockView) {
+    let inputs = block
+        .transactions()
+        .iter()
+        .skip(1)
+        .flat_map(|tx| tx.input_pts_iter())
+        .collect::<Vec<_>>();
+    INPUT_RESERVATIONS.write().release_out_points(inputs.iter());
+}
+
+/// Move the tracked transactions between pending and proposed according to the tx
+/// pool. A tracked transaction which is neither in the tx pool nor committed is
+/// rejected, unless it is unknown since the node was unreachable when it was sent.
+/// The committed ones are updated when their blocks are appended.
+async fn update_submitted_transactions(
+    ckb_client: &CkbRpcClient,
+    store: &RelationalStorage,
+    pending: Vec<H256>,
+    proposed: Vec<H256>,
+) -> Result<()> {
+    let tracked = store
+        .get_submitted_transactions(
+            vec![],
+            vec![
+                TransactionStatus::Pending,
+                TransactionStatus::Proposed,
+                TransactionStatus::Unknown,
+            ],
+            PaginationRequest::default(),
+        )
+        .await?
+        .response;
+    if tracked.is_empty() {
+        return Ok(());
+    }
+
+    let pending: HashSet<H256> = pending.into_iter().collect();
+    let proposed: HashSet<H256> = proposed.into_iter().collect();
+    let (mut to_pending, mut to_proposed, mut not_in_pool) = (vec![], vec![], vec![]);
+    for tx in tracked.into_iter() {
+        if proposed.contains(&tx.tx_hash) {
+            if tx.status != TransactionStatus::Proposed {
+                to_proposed.push(tx.tx_hash);
+            }
+        } else if pending.contains(&tx.tx_hash) {
+            if tx.status != TransactionStatus::Pending {
+                to_pending.push(tx.tx_hash);
+            }
+        } else if tx.status != TransactionStatus::Unknown {
+            not_in_pool.push(tx.tx_hash);
+        }
+    }
+
+    // the transactions may be committed before their blocks are appended
+    let mut rejected = vec![];
+    if !not_in_pool.is_empty() {
+        let txs = ckb_client.get_transactions(not_in_pool.clone()).await?;
+        for (tx_hash, tx) in not_in_pool.into_iter().zip(txs.into_iter()) {
+            match tx.map(|tx| tx.tx_status.status) {
+                Some(Status::Pending) | Some(Status::Proposed) | Some(Status::Committed) => (),
+                _ => rejected.push(tx_hash),
+            }
+        }
+    }
+
+    let now = unix_timestamp_millis();
+    store
+        .update_submitted_transactions(to_pending, TransactionStatus::Pending, None, now)
+        .await?;
+    store
+        .update_submitted_transactions(to_proposed, TransactionStatus::Proposed, None, now)
+        .await?;
+    store
+        .update_submitted_transactions(
+            rejected,
+            TransactionStatus::Rejected,
+            Some("dropped from the tx pool".to_string()),
+            now,
+        )
+        .await
+}
+
+fn pool_tx_hashes(raw_pool: RawTxPool) -> (Vec<H256>, Vec<H256>) {
+    match raw_pool {
+        RawTxPool::Ids(ids) => (ids.pending, ids.proposed),
+        RawTxPool::Verbose(map) => (
+            map.pending.into_iter().map(|(k, _v)| k).collect(),
+            map.proposed.into_iter().map(|(k, _v)| k).collect(),
+        ),
     }
 }
 
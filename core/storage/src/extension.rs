@@ -0,0 +1,48 @@
+use common::{async_trait, utils::ScriptInfo, Result};
+
+use ckb_types::core::{BlockNumber, BlockView};
+use ckb_types::H256;
+use jsonrpsee_core::server::rpc_module::Methods;
+use sqlx::{Any, Transaction};
+
+use std::fmt::Debug;
+
+/// An extension indexes extra data of the chain into its own tables and serves
+/// them through its own RPC methods.
+///
+/// The block hooks run in the same database transaction as the one appending or
+/// rolling back the block, so the extension tables are always consistent with the
+/// mercury tables. During the parallel synchronization, `append_block` is called in
+/// the transaction inserting a batch of blocks, and the batches are not in the order
+/// of the block number.
+#[async_trait]
+pub trait Extension: Debug + Send + Sync {
+    /// The name of the extension, which is the `extension_name` in the config.
+    fn name(&self) -> &str;
+
+    /// The scripts and their cell deps the extension works with.
+    fn script_infos(&self) -> Vec<ScriptInfo>;
+
+    /// The statements to create the tables of the extension. They are executed
+    /// every time mercury starts, so they should be idempotent.
+    fn table_schemas(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// The RPC methods provided by the extension, which are merged into the
+    /// mercury RPC server.
+    fn rpc_methods(&self) -> Methods {
+        Methods::default()
+    }
+
+    /// Called after the block is inserted into the mercury tables.
+    async fn append_block(&self, block: &BlockView, tx: &mut Transaction<'_, Any>) -> Result<()>;
+
+    /// Called before the block is removed from the mercury tables.
+    async fn rollback_block(
+        &self,
+        block_number: BlockNumber,
+        block_hash: &H256,
+        tx: &mut Transaction<'_, Any>,
+    ) -> Result<()>;
+}
@@ -1,24 +1,51 @@
 #![allow(clippy::mutable_key_type)]
 
 pub mod error;
+pub mod extension;
 pub mod relational;
 
+pub use extension::Extension;
 pub use relational::RelationalStorage;
 
 use ckb_types::core::{BlockNumber, BlockView, HeaderView};
 use ckb_types::{bytes::Bytes, packed, H160, H256};
 use common::{async_trait, DetailedCell, PaginationRequest, PaginationResponse, Range, Result};
-use core_rpc_types::indexer::Transaction;
-pub use protocol::db::{DBDriver, DBInfo, SimpleBlock, SimpleTransaction, TransactionWrapper};
+use core_rpc_types::{
+    indexer::Transaction, AddressLabel, BlockEvent, JsonItem, SubmittedTransaction,
+    TransactionStatus, Webhook,
+};
+pub use protocol::db::{
+    DBDriver, DBInfo, SimpleBlock, SimpleTransaction, TransactionDirection, TransactionFilter,
+    TransactionWrapper, WebhookDelivery,
+};
 
 #[async_trait]
 pub trait Storage {
     /// Append the given block to the database.
     async fn append_block(&self, block: BlockView) -> Result<()>;
 
+    /// Append the given block and queue the events of the webhooks in the same database
+    /// transaction. Each event is the webhook id and the payload.
+    async fn append_block_with_webhook_deliveries(
+        &self,
+        block: BlockView,
+        deliveries: Vec<(u64, String)>,
+        created_at: u64,
+    ) -> Result<()>;
+
     /// Rollback a block by block hash and block number from the database.
     async fn rollback_block(&self, block_number: BlockNumber, block_hash: H256) -> Result<()>;
 
+    /// Rollback a block and queue the events of the webhooks in the same database
+    /// transaction. Each event is the webhook id and the payload.
+    async fn rollback_block_with_webhook_deliveries(
+        &self,
+        block_number: BlockNumber,
+        block_hash: H256,
+        deliveries: Vec<(u64, String)>,
+        created_at: u64,
+    ) -> Result<()>;
+
     /// Get live cells from the database according to the given arguments.
     async fn get_live_cells(
         &self,
@@ -51,6 +78,13 @@ pub trait Storage {
         pagination: PaginationRequest,
     ) -> Result<PaginationResponse<DetailedCell>>;
 
+    /// Get the cells of the out points, consumed or not, in one query. The out points
+    /// which are not indexed are skipped.
+    async fn get_cells_by_out_points(
+        &self,
+        out_points: Vec<packed::OutPoint>,
+    ) -> Result<Vec<DetailedCell>>;
+
     /// Get transactions from the database according to the given arguments.
     async fn get_transactions(
         &self,
@@ -75,6 +109,7 @@ pub trait Storage {
         type_hashes: Vec<H256>,
         block_range: Option<Range>,
         limit_cellbase: bool,
+        filter: TransactionFilter,
         pagination: PaginationRequest,
     ) -> Result<PaginationResponse<TransactionWrapper>>;
 
@@ -140,6 +175,90 @@ pub trait Storage {
     /// Register address
     async fn register_addresses(&self, addresses: Vec<(H160, String)>) -> Result<Vec<H160>>;
 
+    /// Track a transaction sent through mercury. The status of a transaction already
+    /// tracked is only updated if it was rejected.
+    async fn insert_submitted_transaction(
+        &self,
+        tx_hash: H256,
+        status: TransactionStatus,
+        reason: Option<String>,
+        submitted_at: u64,
+    ) -> Result<()>;
+
+    /// Update the status of the tracked transactions.
+    async fn update_submitted_transactions(
+        &self,
+        tx_hashes: Vec<H256>,
+        status: TransactionStatus,
+        reason: Option<String>,
+        updated_at: u64,
+    ) -> Result<()>;
+
+    /// Get the tracked transactions according to the given arguments.
+    async fn get_submitted_transactions(
+        &self,
+        tx_hashes: Vec<H256>,
+        statuses: Vec<TransactionStatus>,
+        pagination: PaginationRequest,
+    ) -> Result<PaginationResponse<SubmittedTransaction>>;
+
+    /// Attach the labels and metadata to the items, replacing the existing ones, and
+    /// remove the labels and metadata of the removed items, all at once.
+    async fn set_address_labels(
+        &self,
+        labels: Vec<AddressLabel>,
+        removed_items: Vec<JsonItem>,
+    ) -> Result<()>;
+
+    /// Get the labels of the items, the items without a label are skipped.
+    async fn get_address_labels(&self, items: Vec<JsonItem>) -> Result<Vec<AddressLabel>>;
+
+    /// Get the labels containing the keyword, ignoring case.
+    async fn search_address_labels(
+        &self,
+        keyword: String,
+        pagination: PaginationRequest,
+    ) -> Result<PaginationResponse<AddressLabel>>;
+
+    /// Register a webhook notified of the activities of the items, and return its id.
+    async fn register_webhook(
+        &self,
+        url: String,
+        items: Vec<JsonItem>,
+        secret: String,
+        created_at: u64,
+    ) -> Result<u64>;
+
+    /// Get all the registered webhooks.
+    async fn get_webhooks(&self) -> Result<Vec<Webhook>>;
+
+    /// Remove the webhook and its undelivered events, and return whether it exists.
+    async fn remove_webhook(&self, id: u64) -> Result<bool>;
+
+    /// Get the first undelivered event in the queue of each webhook, if it is not given up
+    /// and is due at `now`, in the order they are queued.
+    async fn get_pending_webhook_deliveries(
+        &self,
+        now: u64,
+        limit: u64,
+    ) -> Result<Vec<WebhookDelivery>>;
+
+    /// Remove the delivered event from the queue.
+    async fn complete_webhook_delivery(&self, id: u64) -> Result<()>;
+
+    /// Record a failed attempt of the delivery. It is retried at `next_attempt_at`, or
+    /// given up if that is `None`.
+    async fn fail_webhook_delivery(
+        &self,
+        id: u64,
+        attempts: u32,
+        next_attempt_at: Option<u64>,
+        error: String,
+    ) -> Result<()>;
+
+    /// Get the chain events after the cursor, in the order they are recorded.
+    async fn get_chain_events(&self, cursor: u64, limit: u64) -> Result<Vec<BlockEvent>>;
+
     /// Get the database information.
     fn get_db_info(&self) -> Result<DBInfo>;
 
@@ -150,6 +269,12 @@ pub trait Storage {
         block_number: Option<BlockNumber>,
     ) -> Result<SimpleBlock>;
 
+    /// Get the blocks in the block range in ascending order, at most `limit` blocks.
+    async fn get_simple_blocks(&self, block_range: Range, limit: u64) -> Result<Vec<SimpleBlock>>;
+
+    /// Get the number of the last block whose timestamp is not later than the given one.
+    async fn get_block_number_by_timestamp(&self, timestamp: u64) -> Result<Option<BlockNumber>>;
+
     /// Get the cells for indexer API.
     async fn get_indexer_transactions(
         &self,
@@ -1,15 +1,25 @@
 use crate::error::DBError;
+use crate::relational::insert::{
+    encode_label_item, encode_transaction_status, BATCH_SIZE_THRESHOLD, CHAIN_EVENT_BLOCK_APPLIED,
+    IO_TYPE_INPUT, WEBHOOK_DELIVERY_PENDING,
+};
 use crate::relational::RelationalStorage;
 
 use common::{
     utils, utils::to_fixed_array, DetailedCell, Order, PaginationRequest, PaginationResponse,
     Range, Result,
 };
-use core_rpc_types::{indexer::Transaction, IOType};
+use core_rpc_types::{
+    indexer::Transaction, AddressLabel, BlockEvent, BlockEventType, EventCell, IOType, JsonItem,
+    SubmittedTransaction, TransactionStatus, Webhook,
+};
 use db_sqlx::{build_query_page_sql, SQLXPool};
-use protocol::db::{SimpleBlock, SimpleTransaction, TransactionWrapper};
+use protocol::db::{
+    SimpleBlock, SimpleTransaction, TransactionDirection, TransactionFilter, TransactionWrapper,
+    WebhookDelivery,
+};
 
-use ckb_jsonrpc_types::TransactionWithStatus;
+use ckb_jsonrpc_types::{JsonBytes, TransactionWithStatus};
 use ckb_types::bytes::Bytes;
 use ckb_types::core::{
     BlockBuilder, BlockNumber, BlockView, EpochNumberWithFraction, HeaderBuilder, HeaderView,
@@ -300,6 +310,46 @@ impl RelationalStorage {
             .await
     }
 
+    pub(crate) async fn get_simple_blocks_by_range(
+        &self,
+        block_range: Range,
+        limit: u64,
+    ) -> Result<Vec<SimpleBlock>> {
+        let blocks = self
+            .query_simple_blocks_by_range(block_range, limit)
+            .await?;
+        let (from, to) = match (blocks.first(), blocks.last()) {
+            (Some(first), Some(last)) => (first.1, last.1),
+            _ => return Ok(vec![]),
+        };
+
+        let mut tx_hashes_group_by_block_hash: HashMap<H256, Vec<H256>> = HashMap::new();
+        for (block_hash, tx_hash) in self
+            .query_transaction_hashes_by_block_range(Range::new(from, to))
+            .await?
+        {
+            tx_hashes_group_by_block_hash
+                .entry(block_hash)
+                .or_insert_with(Vec::new)
+                .push(tx_hash);
+        }
+
+        Ok(blocks
+            .into_iter()
+            .map(
+                |(block_hash, block_number, parent_hash, timestamp)| SimpleBlock {
+                    transactions: tx_hashes_group_by_block_hash
+                        .remove(&block_hash)
+                        .unwrap_or_default(),
+                    block_number,
+                    block_hash,
+                    parent_hash,
+                    timestamp,
+                },
+            )
+            .collect())
+    }
+
     async fn get_simple_block(
         &self,
         block_hash: H256,
@@ -568,6 +618,41 @@ impl RelationalStorage {
         })
     }
 
+    pub(crate) async fn query_cells_by_out_points(
+        &self,
+        out_points: &[packed::OutPoint],
+    ) -> Result<Vec<DetailedCell>> {
+        let mut cells = Vec::with_capacity(out_points.len());
+        for chunk in out_points.chunks(BATCH_SIZE_THRESHOLD / 2) {
+            let conditions = (0..chunk.len())
+                .map(|i| {
+                    format!(
+                        "(tx_hash = ${} AND output_index = ${})",
+                        2 * i + 1,
+                        2 * i + 2
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(" OR ");
+            let sql = format!("SELECT * FROM mercury_cell WHERE {}", conditions);
+
+            // bind
+            let mut query = SQLXPool::new_query(&sql);
+            for out_point in chunk.iter() {
+                let output_index: u32 = out_point.index().unpack();
+                query = query
+                    .bind(out_point.tx_hash().raw_data().to_vec())
+                    .bind(i32::try_from(output_index)?);
+            }
+
+            // fetch
+            for row in self.sqlx_pool.fetch(query).await? {
+                cells.push(build_detailed_cell(row)?);
+            }
+        }
+        Ok(cells)
+    }
+
     pub(crate) async fn query_cells(
         &self,
         out_point: Option<packed::OutPoint>,
@@ -863,6 +948,52 @@ impl RelationalStorage {
         self.sqlx_pool.fetch_one(query).await.map(to_simple_block)
     }
 
+    async fn query_simple_blocks_by_range(
+        &self,
+        block_range: Range,
+        limit: u64,
+    ) -> Result<Vec<(H256, BlockNumber, H256, u64)>> {
+        let query = SQLXPool::new_query(
+            r#"
+            SELECT block_hash, block_number, parent_hash, block_timestamp 
+            FROM mercury_block
+            WHERE block_number BETWEEN $1 AND $2
+            ORDER BY block_number
+            ASC
+            LIMIT $3
+            "#,
+        )
+        .bind(block_range.from.min(i32::MAX as u64) as i64)
+        .bind(block_range.to.min(i32::MAX as u64) as i64)
+        .bind(i64::try_from(limit)?);
+        self.sqlx_pool
+            .fetch_all(query)
+            .await
+            .map(|blocks| blocks.into_iter().map(to_simple_block).collect())
+    }
+
+    pub(crate) async fn query_block_number_by_timestamp(
+        &self,
+        timestamp: u64,
+    ) -> Result<Option<BlockNumber>> {
+        let query = SQLXPool::new_query(
+            r#"
+            SELECT block_number FROM mercury_block
+            WHERE block_timestamp <= $1
+            ORDER BY block_number
+            DESC LIMIT 1
+            "#,
+        )
+        .bind(timestamp.min(i64::MAX as u64) as i64);
+        self.sqlx_pool.fetch_optional(query).await.map(|res| {
+            res.map(|row| {
+                row.get::<i32, _>("block_number")
+                    .try_into()
+                    .expect("i32 to u64")
+            })
+        })
+    }
+
     pub(crate) async fn query_indexer_transactions(
         &self,
         lock_hashes: Vec<H256>,
@@ -966,6 +1097,32 @@ impl RelationalStorage {
         })
     }
 
+    async fn query_transaction_hashes_by_block_range(
+        &self,
+        block_range: Range,
+    ) -> Result<Vec<(H256, H256)>> {
+        let query = SQLXPool::new_query(
+            r#"
+            SELECT block_hash, tx_hash FROM mercury_transaction
+            WHERE block_number BETWEEN $1 AND $2
+            ORDER BY block_number, tx_index
+            ASC
+            "#,
+        )
+        .bind(i32::try_from(block_range.from)?)
+        .bind(i32::try_from(block_range.to)?);
+        self.sqlx_pool.fetch_all(query).await.map(|txs| {
+            txs.into_iter()
+                .map(|tx| {
+                    (
+                        bytes_to_h256(tx.get("block_hash")),
+                        bytes_to_h256(tx.get("tx_hash")),
+                    )
+                })
+                .collect()
+        })
+    }
+
     pub(crate) async fn query_transactions(
         &self,
         tx_hashes: Vec<H256>,
@@ -1040,6 +1197,8 @@ impl RelationalStorage {
         type_hashes: &[H256],
         block_range: &Option<Range>,
         limit_cellbase: bool,
+        filter: &TransactionFilter,
+        udt_amount_change_tx_hashes: &[H256],
         pagination: &PaginationRequest,
     ) -> Result<Vec<(H256, u64)>> {
         if lock_hashes.is_empty() && type_hashes.is_empty() && block_range.is_none() {
@@ -1051,7 +1210,12 @@ impl RelationalStorage {
 
         // query str
         let mut query_builder = SqlBuilder::select_from("mercury_indexer_cell");
-        query_builder.field("DISTINCT ON (tx_hash) tx_hash, id");
+        // a transaction is paged by its last cell in the order, so that it is not repeated
+        // in the next page
+        match pagination.order {
+            Order::Asc => query_builder.field("tx_hash, MAX(id) AS id"),
+            Order::Desc => query_builder.field("tx_hash, MIN(id) AS id"),
+        };
         if !lock_hashes.is_empty() {
             query_builder
                 .and_where_in("lock_hash", &sqlx_param_placeholders(1..lock_hashes.len())?);
@@ -1074,6 +1238,14 @@ impl RelationalStorage {
                 range.to.min(i32::MAX as u64),
             );
         }
+        let filter_hashes = push_transaction_filter(
+            &mut query_builder,
+            lock_hashes,
+            block_range,
+            filter,
+            udt_amount_change_tx_hashes,
+            lock_hashes.len() + type_hashes.len(),
+        )?;
         if let Some(id) = pagination.cursor {
             let id = i64::try_from(id).unwrap_or(i64::MAX);
             match pagination.order {
@@ -1081,10 +1253,7 @@ impl RelationalStorage {
                 Order::Desc => query_builder.and_where_lt("id", id),
             };
         }
-        match pagination.order {
-            Order::Asc => query_builder.order_by("tx_hash, id", true),
-            Order::Desc => query_builder.order_by("tx_hash, id", false),
-        };
+        query_builder.group_by("tx_hash");
         let sql_sub_query = query_builder.subquery()?;
 
         let mut query_builder = SqlBuilder::select_from(&format!("{} res", sql_sub_query));
@@ -1099,10 +1268,7 @@ impl RelationalStorage {
         // bind
         let bind = |sql| {
             let mut query = SQLXPool::new_query(sql);
-            for hash in lock_hashes {
-                query = query.bind(hash.as_bytes());
-            }
-            for hash in type_hashes {
+            for hash in lock_hashes.iter().chain(type_hashes).chain(&filter_hashes) {
                 query = query.bind(hash.as_bytes());
             }
             query
@@ -1128,6 +1294,8 @@ impl RelationalStorage {
         type_hashes: &[H256],
         block_range: &Option<Range>,
         limit_cellbase: bool,
+        filter: &TransactionFilter,
+        udt_amount_change_tx_hashes: &[H256],
     ) -> Result<u64> {
         if lock_hashes.is_empty() && type_hashes.is_empty() && block_range.is_none() {
             return Err(DBError::InvalidParameter(
@@ -1161,15 +1329,20 @@ impl RelationalStorage {
                 range.to.min(i32::MAX as u64),
             );
         }
+        let filter_hashes = push_transaction_filter(
+            &mut query_builder,
+            lock_hashes,
+            block_range,
+            filter,
+            udt_amount_change_tx_hashes,
+            lock_hashes.len() + type_hashes.len(),
+        )?;
         let sql = query_builder.sql()?.trim_end_matches(';').to_string();
 
         // bind
         let bind = |sql| {
             let mut query = SQLXPool::new_query(sql);
-            for hash in lock_hashes {
-                query = query.bind(hash.as_bytes());
-            }
-            for hash in type_hashes {
+            for hash in lock_hashes.iter().chain(type_hashes).chain(&filter_hashes) {
                 query = query.bind(hash.as_bytes());
             }
             query
@@ -1183,6 +1356,91 @@ impl RelationalStorage {
             .map(|row| row.get::<i64, _>("count") as u64)
     }
 
+    /// The transactions whose absolute net change of the amount of the UDT of the type
+    /// hash, held by the lock scripts in the blocks of the range, is in `amount_range`.
+    /// The amounts are encoded in the cell data, so they are summed here rather than in
+    /// SQL.
+    pub(crate) async fn query_udt_amount_change_tx_hashes(
+        &self,
+        lock_hashes: &[H256],
+        type_hash: &H256,
+        block_range: &Option<Range>,
+        amount_range: &Range,
+    ) -> Result<Vec<H256>> {
+        if lock_hashes.is_empty() {
+            return Err(DBError::InvalidParameter(
+                "the UDT amount change filter needs lock hashes".to_owned(),
+            )
+            .into());
+        }
+
+        // query str
+        let mut query_builder = SqlBuilder::select_from("mercury_cell");
+        query_builder
+            .field("tx_hash, block_number, consumed_tx_hash, consumed_block_number, data")
+            .and_where_in("lock_hash", &sqlx_param_placeholders(1..lock_hashes.len())?)
+            .and_where_eq("type_hash", format!("${}", lock_hashes.len() + 1));
+        if let Some(ref range) = block_range {
+            query_builder.and_where(format!(
+                "(block_number BETWEEN {} AND {} OR consumed_block_number BETWEEN {} AND {})",
+                range.from.min(i32::MAX as u64),
+                range.to.min(i32::MAX as u64),
+                range.from.min(i64::MAX as u64),
+                range.to.min(i64::MAX as u64),
+            ));
+        }
+        let sql = query_builder.sql()?.trim_end_matches(';').to_string();
+
+        // bind
+        let mut query = SQLXPool::new_query(&sql);
+        for hash in lock_hashes.iter().chain(std::iter::once(type_hash)) {
+            query = query.bind(hash.as_bytes());
+        }
+
+        // fetch
+        let rows = self.sqlx_pool.fetch(query).await?;
+        let in_block_range = |block_number: u64| {
+            block_range
+                .as_ref()
+                .map_or(true, |range| range.is_in(block_number))
+        };
+        let mut changes: HashMap<H256, (u128, u128)> = HashMap::new();
+        for row in rows {
+            let amount = utils::decode_udt_amount(&row.get::<Vec<u8>, _>("data")).unwrap_or(0);
+            if in_block_range(row.get::<i32, _>("block_number") as u64) {
+                let change = changes
+                    .entry(bytes_to_h256(row.get("tx_hash")))
+                    .or_default();
+                change.0 = change.0.saturating_add(amount);
+            }
+            let consumed_tx_hash = row
+                .try_get::<Option<Vec<u8>>, _>("consumed_tx_hash")
+                .unwrap_or(None)
+                .filter(|hash| !hash.is_empty());
+            let consumed_block_number = row
+                .try_get::<Option<i64>, _>("consumed_block_number")
+                .unwrap_or(None);
+            if let (Some(consumed_tx_hash), Some(consumed_block_number)) =
+                (consumed_tx_hash, consumed_block_number)
+            {
+                if in_block_range(consumed_block_number as u64) {
+                    let change = changes.entry(bytes_to_h256(&consumed_tx_hash)).or_default();
+                    change.1 = change.1.saturating_add(amount);
+                }
+            }
+        }
+        let mut tx_hashes = changes
+            .into_iter()
+            .filter(|(_, (created, consumed))| {
+                let change = created.abs_diff(*consumed);
+                change >= amount_range.from as u128 && change <= amount_range.to as u128
+            })
+            .map(|(tx_hash, _)| tx_hash)
+            .collect::<Vec<_>>();
+        tx_hashes.sort();
+        Ok(tx_hashes)
+    }
+
     async fn query_txs_input_cells(&self, tx_hashes: &[Vec<u8>]) -> Result<Vec<AnyRow>> {
         if tx_hashes.is_empty() {
             return Ok(Vec::new());
@@ -1226,6 +1484,269 @@ impl RelationalStorage {
             .await
             .map(|row| row.map(|row| row.get::<String, _>("address")))
     }
+
+    pub(crate) async fn query_submitted_transactions(
+        &self,
+        tx_hashes: Vec<H256>,
+        statuses: Vec<TransactionStatus>,
+        pagination: PaginationRequest,
+    ) -> Result<PaginationResponse<SubmittedTransaction>> {
+        let mut query_builder = SqlBuilder::select_from("mercury_submitted_transaction");
+        query_builder.field(
+            "id, tx_hash, status, submitted_at, updated_at, block_number, block_hash, reason",
+        );
+        if !tx_hashes.is_empty() {
+            query_builder.and_where_in("tx_hash", &sqlx_param_placeholders(1..tx_hashes.len())?);
+        }
+        if !statuses.is_empty() {
+            let statuses = statuses
+                .iter()
+                .map(encode_transaction_status)
+                .collect::<Vec<i16>>();
+            query_builder.and_where_in("status", &statuses);
+        }
+        let (sql, sql_for_total) = build_query_page_sql(query_builder, &pagination)?;
+
+        // bind
+        let bind = |sql| {
+            let mut query = SQLXPool::new_query(sql);
+            for hash in &tx_hashes {
+                query = query.bind(hash.as_bytes());
+            }
+            query
+        };
+        let query = bind(&sql);
+        let query_total = bind(&sql_for_total);
+
+        // fetch
+        let page = self
+            .sqlx_pool
+            .fetch_page(query, query_total, &pagination)
+            .await?;
+        let mut txs = vec![];
+        for row in page.response {
+            txs.push(build_submitted_transaction(row)?);
+        }
+        Ok(PaginationResponse {
+            response: txs,
+            next_cursor: page.next_cursor,
+            count: page.count,
+        })
+    }
+
+    pub(crate) async fn query_address_labels(
+        &self,
+        items: Vec<JsonItem>,
+    ) -> Result<Vec<AddressLabel>> {
+        let mut labels = vec![];
+        for chunk in items.chunks(BATCH_SIZE_THRESHOLD) {
+            let sql = format!(
+                "SELECT id, item_type, item, label, metadata, updated_at
+                FROM mercury_address_label
+                WHERE item IN ({})",
+                sqlx_param_placeholders(1..chunk.len())?.join(", ")
+            );
+            let mut query = SQLXPool::new_query(&sql);
+            for item in chunk.iter() {
+                query = query.bind(encode_label_item(item)?.1);
+            }
+            for row in self.sqlx_pool.fetch(query).await? {
+                labels.push(build_address_label(row)?);
+            }
+        }
+        Ok(labels)
+    }
+
+    pub(crate) async fn query_address_labels_by_keyword(
+        &self,
+        keyword: String,
+        pagination: PaginationRequest,
+    ) -> Result<PaginationResponse<AddressLabel>> {
+        let mut query_builder = SqlBuilder::select_from("mercury_address_label");
+        query_builder
+            .field("id, item_type, item, label, metadata, updated_at")
+            .and_where(r#"LOWER(label) LIKE $1 ESCAPE '\'"#);
+        let (sql, sql_for_total) = build_query_page_sql(query_builder, &pagination)?;
+
+        // escape the wildcards so that the keyword is matched literally
+        let pattern = format!(
+            "%{}%",
+            keyword
+                .to_lowercase()
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        );
+        let query = SQLXPool::new_query(&sql).bind(&pattern);
+        let query_total = SQLXPool::new_query(&sql_for_total).bind(&pattern);
+
+        // fetch
+        let page = self
+            .sqlx_pool
+            .fetch_page(query, query_total, &pagination)
+            .await?;
+        let mut labels = vec![];
+        for row in page.response {
+            labels.push(build_address_label(row)?);
+        }
+        Ok(PaginationResponse {
+            response: labels,
+            next_cursor: page.next_cursor,
+            count: page.count,
+        })
+    }
+
+    pub(crate) async fn query_webhooks(&self) -> Result<Vec<Webhook>> {
+        let query = SQLXPool::new_query(
+            r#"
+            SELECT id, url, items, created_at
+            FROM mercury_webhook
+            ORDER BY id
+            "#,
+        );
+        let mut webhooks = vec![];
+        for row in self.sqlx_pool.fetch(query).await? {
+            webhooks.push(Webhook {
+                id: u64::try_from(row.get::<i64, _>("id"))?.into(),
+                url: row.get("url"),
+                items: serde_json::from_str(&row.get::<String, _>("items"))?,
+                created_at: u64::try_from(row.get::<i64, _>("created_at"))?.into(),
+            });
+        }
+        Ok(webhooks)
+    }
+
+    /// The head of the queue of each webhook is its pending delivery of the smallest id,
+    /// which is the one queued first.
+    pub(crate) async fn query_pending_webhook_deliveries(
+        &self,
+        now: u64,
+        limit: u64,
+    ) -> Result<Vec<WebhookDelivery>> {
+        let query = SQLXPool::new_query(
+            r#"
+            SELECT mercury_webhook_delivery.id, webhook_id, url, secret, payload, attempts, next_attempt_at
+            FROM mercury_webhook_delivery
+            JOIN mercury_webhook
+            ON mercury_webhook_delivery.webhook_id = mercury_webhook.id
+            WHERE mercury_webhook_delivery.id IN (
+                SELECT MIN(id) FROM mercury_webhook_delivery
+                WHERE status = $1
+                GROUP BY webhook_id
+            )
+            AND next_attempt_at <= $2
+            ORDER BY mercury_webhook_delivery.id
+            LIMIT $3
+            "#,
+        )
+        .bind(WEBHOOK_DELIVERY_PENDING)
+        .bind(i64::try_from(now)?)
+        .bind(i64::try_from(limit)?);
+        let mut deliveries = vec![];
+        for row in self.sqlx_pool.fetch(query).await? {
+            deliveries.push(WebhookDelivery {
+                id: u64::try_from(row.get::<i64, _>("id"))?,
+                webhook_id: u64::try_from(row.get::<i64, _>("webhook_id"))?,
+                url: row.get("url"),
+                secret: row.get("secret"),
+                payload: row.get("payload"),
+                attempts: u32::try_from(row.get::<i32, _>("attempts"))?,
+                next_attempt_at: u64::try_from(row.get::<i64, _>("next_attempt_at"))?,
+            });
+        }
+        Ok(deliveries)
+    }
+
+    pub(crate) async fn query_chain_events(
+        &self,
+        cursor: u64,
+        limit: u64,
+    ) -> Result<Vec<BlockEvent>> {
+        let query = SQLXPool::new_query(
+            r#"
+            SELECT id, event_type, block_number, block_hash, parent_hash
+            FROM mercury_chain_event
+            WHERE id > $1
+            ORDER BY id
+            LIMIT $2
+            "#,
+        )
+        .bind(i64::try_from(cursor)?)
+        .bind(i64::try_from(limit)?);
+        let mut events = vec![];
+        for row in self.sqlx_pool.fetch(query).await? {
+            let event_type = if row.get::<i16, _>("event_type") == CHAIN_EVENT_BLOCK_APPLIED {
+                BlockEventType::BlockApplied
+            } else {
+                BlockEventType::BlockReverted
+            };
+            events.push(BlockEvent {
+                id: u64::try_from(row.get::<i64, _>("id"))?.into(),
+                event_type,
+                block_number: u64::try_from(row.get::<i32, _>("block_number"))?.into(),
+                block_hash: bytes_to_h256(row.get("block_hash")),
+                parent_hash: bytes_to_h256(row.get("parent_hash")),
+                created_cells: vec![],
+                consumed_cells: vec![],
+            });
+        }
+        if events.is_empty() {
+            return Ok(events);
+        }
+
+        let placeholders = sqlx_param_placeholders(1..events.len())?;
+        let sql = format!(
+            "SELECT mercury_chain_event_cell.*, mercury_cell.data
+            FROM mercury_chain_event_cell
+            LEFT JOIN mercury_cell
+            ON mercury_cell.tx_hash = mercury_chain_event_cell.tx_hash
+            AND mercury_cell.output_index = mercury_chain_event_cell.output_index
+            WHERE mercury_chain_event_cell.event_id IN ({})
+            ORDER BY mercury_chain_event_cell.event_id, mercury_chain_event_cell.io_type,
+            mercury_chain_event_cell.tx_hash, mercury_chain_event_cell.output_index",
+            placeholders.join(", ")
+        );
+        let mut query = SQLXPool::new_query(&sql);
+        for event in events.iter() {
+            query = query.bind(i64::try_from(event.id.value())?);
+        }
+        let event_index: HashMap<u64, usize> = events
+            .iter()
+            .enumerate()
+            .map(|(index, event)| (event.id.value(), index))
+            .collect();
+        for row in self.sqlx_pool.fetch(query).await? {
+            let type_hash = bytes_to_h256(row.get("type_hash"));
+            let cell = EventCell {
+                out_point: packed::OutPoint::new(
+                    bytes_to_h256(row.get("tx_hash")).pack(),
+                    u32::try_from(row.get::<i32, _>("output_index"))?,
+                )
+                .into(),
+                lock_hash: bytes_to_h256(row.get("lock_hash")),
+                type_hash: if type_hash == H256::default() {
+                    None
+                } else {
+                    Some(type_hash)
+                },
+                capacity: u64::try_from(row.get::<i64, _>("capacity"))?.into(),
+                // the cells created by a reverted block are removed from the cell table
+                data: row
+                    .get::<Option<Vec<u8>>, _>("data")
+                    .map(JsonBytes::from_vec),
+            };
+            let event_id = u64::try_from(row.get::<i64, _>("event_id"))?;
+            if let Some(index) = event_index.get(&event_id) {
+                let event = &mut events[*index];
+                if row.get::<i16, _>("io_type") == i16::from(IO_TYPE_INPUT) {
+                    event.consumed_cells.push(cell);
+                } else {
+                    event.created_cells.push(cell);
+                }
+            }
+        }
+        Ok(events)
+    }
 }
 
 fn build_block_view(
@@ -1357,11 +1878,114 @@ fn to_simple_block(block: AnyRow) -> (H256, BlockNumber, H256, u64) {
     )
 }
 
+/// Append the conditions of the transaction filter to the query of the indexer cells,
+/// whose parameters are numbered after `param_count`. The UDT amount change filter is
+/// given as the transactions it keeps, resolved by `query_udt_amount_change_tx_hashes`.
+/// Return the hashes to bind to the parameters in order.
+fn push_transaction_filter(
+    query_builder: &mut SqlBuilder,
+    lock_hashes: &[H256],
+    block_range: &Option<Range>,
+    filter: &TransactionFilter,
+    udt_amount_change_tx_hashes: &[H256],
+    param_count: usize,
+) -> Result<Vec<H256>> {
+    if (filter.direction.is_some()
+        || filter.capacity_change_range.is_some()
+        || filter.udt_amount_change_range.is_some())
+        && lock_hashes.is_empty()
+    {
+        return Err(DBError::InvalidParameter(
+            "the direction and capacity change filters need lock hashes".to_owned(),
+        )
+        .into());
+    }
+
+    let mut hashes: Vec<H256> = vec![];
+    let mut placeholders = |new_hashes: &[H256]| -> Result<String> {
+        let start = param_count + hashes.len() + 1;
+        hashes.extend_from_slice(new_hashes);
+        Ok(sqlx_param_placeholders(start..param_count + hashes.len())?.join(","))
+    };
+
+    if !filter.counterparty_lock_hashes.is_empty() {
+        query_builder.and_where(format!(
+            "tx_hash IN (SELECT tx_hash FROM mercury_indexer_cell WHERE lock_hash IN ({}))",
+            placeholders(&filter.counterparty_lock_hashes)?
+        ));
+    }
+    if let Some(direction) = filter.direction {
+        let operator = match direction {
+            TransactionDirection::Incoming => "NOT IN",
+            TransactionDirection::Outgoing => "IN",
+        };
+        query_builder.and_where(format!(
+            "tx_hash {} (SELECT tx_hash FROM mercury_indexer_cell WHERE io_type = {} AND lock_hash IN ({}))",
+            operator,
+            IO_TYPE_INPUT,
+            placeholders(lock_hashes)?
+        ));
+    }
+    if let Some(ref range) = filter.capacity_change_range {
+        // the created cells add to the capacity and the consumed cells subtract from it,
+        // and the cells are bounded by the queried blocks
+        let (created_in_range, consumed_in_range) = match block_range {
+            Some(block_range) => (
+                format!(
+                    " AND block_number BETWEEN {} AND {}",
+                    block_range.from.min(i32::MAX as u64),
+                    block_range.to.min(i32::MAX as u64)
+                ),
+                format!(
+                    " AND consumed_block_number BETWEEN {} AND {}",
+                    block_range.from.min(i64::MAX as u64),
+                    block_range.to.min(i64::MAX as u64)
+                ),
+            ),
+            None => (String::new(), String::new()),
+        };
+        query_builder.and_where(format!(
+            "tx_hash IN (SELECT tx_hash FROM (\
+             SELECT tx_hash, capacity AS change FROM mercury_cell WHERE lock_hash IN ({}){} \
+             UNION ALL \
+             SELECT consumed_tx_hash AS tx_hash, -capacity AS change FROM mercury_cell \
+             WHERE consumed_tx_hash IS NOT NULL AND lock_hash IN ({}){}\
+             ) changes GROUP BY tx_hash HAVING ABS(SUM(change)) BETWEEN {} AND {})",
+            placeholders(lock_hashes)?,
+            created_in_range,
+            placeholders(lock_hashes)?,
+            consumed_in_range,
+            range.from.min(i64::MAX as u64),
+            range.to.min(i64::MAX as u64),
+        ));
+    }
+    if filter.udt_amount_change_range.is_some() {
+        if udt_amount_change_tx_hashes.is_empty() {
+            query_builder.and_where("1 = 0");
+        } else {
+            query_builder.and_where(format!(
+                "tx_hash IN ({})",
+                placeholders(udt_amount_change_tx_hashes)?
+            ));
+        }
+    }
+    if let Some(ref range) = filter.time_range {
+        query_builder.and_where(format!(
+            "block_number IN (SELECT block_number FROM mercury_block WHERE block_timestamp BETWEEN {} AND {})",
+            range.from.min(i64::MAX as u64),
+            range.to.min(i64::MAX as u64),
+        ));
+    }
+
+    Ok(hashes)
+}
+
+/// The placeholders from `$start` to `$end` of the params, both ends included.
 pub(crate) fn sqlx_param_placeholders(range: std::ops::Range<usize>) -> Result<Vec<String>> {
     if range.start == 0 {
         return Err(DBError::InvalidParameter("no valid parameter".to_owned()).into());
     }
-    Ok((1..=range.end)
+    Ok((range.start..=range.end)
         .map(|i| format!("${}", i))
         .collect::<Vec<String>>())
 }
@@ -1453,6 +2077,51 @@ fn build_detailed_cell(row: AnyRow) -> Result<DetailedCell> {
     Ok(cell)
 }
 
+fn build_submitted_transaction(row: AnyRow) -> Result<SubmittedTransaction> {
+    let block_number = row
+        .get::<Option<i32>, _>("block_number")
+        .map(u64::try_from)
+        .transpose()?;
+    Ok(SubmittedTransaction {
+        tx_hash: bytes_to_h256(row.get("tx_hash")),
+        status: decode_transaction_status(row.get::<i16, _>("status")),
+        submitted_at: u64::try_from(row.get::<i64, _>("submitted_at"))?.into(),
+        updated_at: u64::try_from(row.get::<i64, _>("updated_at"))?.into(),
+        block_number: block_number.map(Into::into),
+        block_hash: row
+            .get::<Option<Vec<u8>>, _>("block_hash")
+            .map(|hash| bytes_to_h256(&hash)),
+        reason: row.get("reason"),
+    })
+}
+
+fn build_address_label(row: AnyRow) -> Result<AddressLabel> {
+    let item = row.get::<String, _>("item");
+    Ok(AddressLabel {
+        item: if row.get::<i16, _>("item_type") == 0 {
+            JsonItem::Identity(item)
+        } else {
+            JsonItem::Address(item)
+        },
+        label: row.get("label"),
+        metadata: row
+            .get::<Option<String>, _>("metadata")
+            .map(|metadata| serde_json::from_str(&metadata))
+            .transpose()?,
+        updated_at: u64::try_from(row.get::<i64, _>("updated_at"))?.into(),
+    })
+}
+
+fn decode_transaction_status(status: i16) -> TransactionStatus {
+    match status {
+        0 => TransactionStatus::Pending,
+        1 => TransactionStatus::Proposed,
+        2 => TransactionStatus::Committed,
+        3 => TransactionStatus::Rejected,
+        _ => TransactionStatus::Unknown,
+    }
+}
+
 fn build_indexer_transaction(row: AnyRow) -> Result<Transaction> {
     Ok(Transaction {
         block_number: u64::try_from(row.get::<i32, _>("block_number"))?.into(),
@@ -1,6 +1,9 @@
+use crate::error::DBError;
+use crate::relational::fetch::sqlx_param_placeholders;
 use crate::relational::{generate_id, RelationalStorage};
 
 use common::Result;
+use core_rpc_types::{AddressLabel, JsonItem, TransactionStatus};
 use db_sqlx::SQLXPool;
 
 use ckb_types::core::{BlockView, EpochNumberWithFraction, TransactionView};
@@ -8,7 +11,7 @@ use ckb_types::{prelude::*, H160, H256};
 use seq_macro::seq;
 use sql_builder::SqlBuilder;
 use sqlx::{Any, Row, Transaction};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 // Note that every database has a practical limit on the number of bind parameters you can add to a single query.
 // This varies by database.
@@ -21,6 +24,10 @@ pub const BATCH_SIZE_THRESHOLD: usize = 1_000;
 pub const BLAKE_160_HSAH_LEN: usize = 20;
 pub const IO_TYPE_INPUT: u8 = 0;
 pub const IO_TYPE_OUTPUT: u8 = 1;
+pub const WEBHOOK_DELIVERY_PENDING: i16 = 0;
+pub const WEBHOOK_DELIVERY_FAILED: i16 = 1;
+pub const CHAIN_EVENT_BLOCK_APPLIED: i16 = 0;
+pub const CHAIN_EVENT_BLOCK_REVERTED: i16 = 1;
 
 impl RelationalStorage {
     pub(crate) async fn insert_block_table(
@@ -103,6 +110,416 @@ impl RelationalStorage {
 
         Ok(addresses.into_iter().map(|(hash, _)| hash).collect())
     }
+
+    pub(crate) async fn insert_submitted_transaction_table(
+        &self,
+        tx_hash: H256,
+        status: TransactionStatus,
+        reason: Option<String>,
+        submitted_at: u64,
+    ) -> Result<()> {
+        let submitted_at = i64::try_from(submitted_at)?;
+
+        // only a rejected or unknown transaction is tracked again
+        let mut tx = self.sqlx_pool.transaction().await?;
+        SQLXPool::new_query(
+            r#"INSERT INTO mercury_submitted_transaction(
+            tx_hash, status, submitted_at, updated_at, reason)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (tx_hash) DO UPDATE
+            SET status = excluded.status, reason = excluded.reason,
+            updated_at = excluded.updated_at
+            WHERE mercury_submitted_transaction.status IN ($6, $7)"#,
+        )
+        .bind(tx_hash.as_bytes())
+        .bind(encode_transaction_status(&status))
+        .bind(submitted_at)
+        .bind(submitted_at)
+        .bind(reason)
+        .bind(encode_transaction_status(&TransactionStatus::Rejected))
+        .bind(encode_transaction_status(&TransactionStatus::Unknown))
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await.map_err(Into::into)
+    }
+
+    pub(crate) async fn update_submitted_transaction_table(
+        &self,
+        tx_hashes: Vec<H256>,
+        status: TransactionStatus,
+        reason: Option<String>,
+        updated_at: u64,
+    ) -> Result<()> {
+        let updated_at = i64::try_from(updated_at)?;
+        let mut tx = self.sqlx_pool.transaction().await?;
+        for chunk in tx_hashes.chunks(BATCH_SIZE_THRESHOLD) {
+            let placeholders = (4..chunk.len() + 4)
+                .map(|i| format!("${}", i))
+                .collect::<Vec<String>>();
+            let sql = format!(
+                "UPDATE mercury_submitted_transaction
+                SET status = $1, reason = $2, updated_at = $3
+                WHERE tx_hash IN ({})",
+                placeholders.join(", ")
+            );
+
+            let mut query = SQLXPool::new_query(&sql)
+                .bind(encode_transaction_status(&status))
+                .bind(reason.clone())
+                .bind(updated_at);
+            for tx_hash in chunk.iter() {
+                query = query.bind(tx_hash.as_bytes());
+            }
+            query.execute(&mut *tx).await?;
+        }
+        tx.commit().await.map_err(Into::into)
+    }
+
+    pub(crate) async fn upsert_address_label_table(
+        &self,
+        labels: Vec<AddressLabel>,
+        tx: &mut Transaction<'_, Any>,
+    ) -> Result<()> {
+        for label in labels.iter() {
+            let (item_type, item) = encode_label_item(&label.item)?;
+            SQLXPool::new_query(
+                r#"INSERT INTO mercury_address_label(item_type, item, label, metadata, updated_at)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (item) DO UPDATE
+                SET label = excluded.label, metadata = excluded.metadata,
+                updated_at = excluded.updated_at"#,
+            )
+            .bind(item_type)
+            .bind(item)
+            .bind(&label.label)
+            .bind(label.metadata.as_ref().map(|metadata| metadata.to_string()))
+            .bind(i64::try_from(u64::from(label.updated_at))?)
+            .execute(&mut *tx)
+            .await?;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn delete_address_label_table(
+        &self,
+        items: Vec<JsonItem>,
+        tx: &mut Transaction<'_, Any>,
+    ) -> Result<()> {
+        for chunk in items.chunks(BATCH_SIZE_THRESHOLD) {
+            let sql = format!(
+                "DELETE FROM mercury_address_label WHERE item IN ({})",
+                sqlx_param_placeholders(1..chunk.len())?.join(", ")
+            );
+            let mut query = SQLXPool::new_query(&sql);
+            for item in chunk.iter() {
+                query = query.bind(encode_label_item(item)?.1);
+            }
+            query.execute(&mut *tx).await?;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn insert_webhook_table(
+        &self,
+        url: String,
+        items: Vec<JsonItem>,
+        secret: String,
+        created_at: u64,
+    ) -> Result<u64> {
+        let mut tx = self.sqlx_pool.transaction().await?;
+        let id: i64 = SQLXPool::new_query(
+            r#"INSERT INTO mercury_webhook(url, items, secret, created_at)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id"#,
+        )
+        .bind(url)
+        .bind(serde_json::to_string(&items)?)
+        .bind(secret)
+        .bind(i64::try_from(created_at)?)
+        .fetch_one(&mut *tx)
+        .await?
+        .get("id");
+        tx.commit().await?;
+        Ok(u64::try_from(id)?)
+    }
+
+    pub(crate) async fn delete_webhook_table(&self, id: u64) -> Result<bool> {
+        let id = i64::try_from(id)?;
+        let mut tx = self.sqlx_pool.transaction().await?;
+        SQLXPool::new_query("DELETE FROM mercury_webhook_delivery WHERE webhook_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        let deleted = SQLXPool::new_query("DELETE FROM mercury_webhook WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(deleted.rows_affected() > 0)
+    }
+
+    pub(crate) async fn delete_webhook_delivery_table(&self, id: u64) -> Result<()> {
+        let mut tx = self.sqlx_pool.transaction().await?;
+        SQLXPool::new_query("DELETE FROM mercury_webhook_delivery WHERE id = $1")
+            .bind(i64::try_from(id)?)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await.map_err(Into::into)
+    }
+
+    pub(crate) async fn update_webhook_delivery_table(
+        &self,
+        id: u64,
+        attempts: u32,
+        next_attempt_at: Option<u64>,
+        error: String,
+    ) -> Result<()> {
+        let status = if next_attempt_at.is_some() {
+            WEBHOOK_DELIVERY_PENDING
+        } else {
+            WEBHOOK_DELIVERY_FAILED
+        };
+        let mut tx = self.sqlx_pool.transaction().await?;
+        SQLXPool::new_query(
+            r#"UPDATE mercury_webhook_delivery
+            SET status = $1, attempts = $2, next_attempt_at = COALESCE($3, next_attempt_at), last_error = $4
+            WHERE id = $5"#,
+        )
+        .bind(status)
+        .bind(i32::try_from(attempts)?)
+        .bind(next_attempt_at.map(i64::try_from).transpose()?)
+        .bind(error)
+        .bind(i64::try_from(id)?)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await.map_err(Into::into)
+    }
+}
+
+/// Labels are keyed by the address or the identity string, which never collide.
+pub(crate) fn encode_label_item(item: &JsonItem) -> Result<(i16, &str)> {
+    match item {
+        JsonItem::Identity(identity) => Ok((0, identity)),
+        JsonItem::Address(address) => Ok((1, address)),
+        JsonItem::OutPoint(_) => {
+            Err(DBError::InvalidParameter("an out point can not be labeled".to_string()).into())
+        }
+    }
+}
+
+pub(crate) fn encode_transaction_status(status: &TransactionStatus) -> i16 {
+    match status {
+        TransactionStatus::Pending => 0,
+        TransactionStatus::Proposed => 1,
+        TransactionStatus::Committed => 2,
+        TransactionStatus::Rejected => 3,
+        TransactionStatus::Unknown => 4,
+    }
+}
+
+/// Mark the tracked transactions committed in the block.
+pub(crate) async fn update_committed_submitted_transactions(
+    block_view: &BlockView,
+    tx: &mut Transaction<'_, Any>,
+) -> Result<()> {
+    let tx_hashes = block_view.tx_hashes();
+    for chunk in tx_hashes.chunks(BATCH_SIZE_THRESHOLD) {
+        let placeholders = (5..chunk.len() + 5)
+            .map(|i| format!("${}", i))
+            .collect::<Vec<String>>();
+        let sql = format!(
+            "UPDATE mercury_submitted_transaction
+            SET status = $1, block_number = $2, block_hash = $3, updated_at = $4, reason = NULL
+            WHERE tx_hash IN ({})",
+            placeholders.join(", ")
+        );
+
+        let mut query = SQLXPool::new_query(&sql)
+            .bind(encode_transaction_status(&TransactionStatus::Committed))
+            .bind(i32::try_from(block_view.number())?)
+            .bind(block_view.hash().raw_data().to_vec())
+            .bind(i64::try_from(block_view.timestamp())?);
+        for tx_hash in chunk.iter() {
+            query = query.bind(tx_hash.raw_data().to_vec());
+        }
+        query.execute(&mut *tx).await?;
+    }
+    Ok(())
+}
+
+/// Queue the events of the webhooks, each of which is the webhook id and the payload. The
+/// ids are generated by the database in the order of the insertions, which is the order
+/// the events of a webhook are delivered in.
+pub(crate) async fn bulk_insert_webhook_delivery_table(
+    deliveries: Vec<(u64, String)>,
+    created_at: u64,
+    tx: &mut Transaction<'_, Any>,
+) -> Result<()> {
+    let created_at = i64::try_from(created_at)?;
+    let mut delivery_rows = vec![];
+    for (webhook_id, payload) in deliveries.into_iter() {
+        delivery_rows.push((
+            i64::try_from(webhook_id)?,
+            payload,
+            WEBHOOK_DELIVERY_PENDING,
+            0i32,
+            created_at,
+            created_at,
+        ));
+    }
+
+    for start in (0..delivery_rows.len()).step_by(BATCH_SIZE_THRESHOLD) {
+        let end = (start + BATCH_SIZE_THRESHOLD).min(delivery_rows.len());
+
+        // build query str
+        let mut builder = SqlBuilder::insert_into("mercury_webhook_delivery");
+        builder.field(
+            r#"webhook_id,
+            payload,
+            status,
+            attempts,
+            next_attempt_at,
+            created_at"#,
+        );
+        push_values_placeholders(&mut builder, 6, end - start);
+        let sql = builder.sql()?.trim_end_matches(';').to_string();
+
+        // bind
+        let mut query = SQLXPool::new_query(&sql);
+        for row in delivery_rows[start..end].iter() {
+            seq!(i in 0..6 {
+                query = query.bind(&row.i);
+            });
+        }
+
+        // execute
+        query.execute(&mut *tx).await?;
+    }
+    Ok(())
+}
+
+/// Record a chain event of the block, with snapshots of the cells created and consumed by
+/// its transactions. It must run before the cells of a reverted block are removed.
+pub(crate) async fn insert_chain_event_table(
+    event_type: i16,
+    block_number: u64,
+    block_hash: &[u8],
+    parent_hash: &[u8],
+    tx_hashes: &[Vec<u8>],
+    tx: &mut Transaction<'_, Any>,
+) -> Result<()> {
+    // the id is generated by the database in the order of the insertions, which is the
+    // order the events are consumed in
+    let event_id: i64 = SQLXPool::new_query(
+        r#"INSERT INTO mercury_chain_event(event_type, block_number, block_hash, parent_hash)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id"#,
+    )
+    .bind(event_type)
+    .bind(i32::try_from(block_number)?)
+    .bind(block_hash)
+    .bind(parent_hash)
+    .fetch_one(&mut *tx)
+    .await?
+    .get("id");
+
+    for (io_type, tx_hash_column) in [
+        (IO_TYPE_OUTPUT, "tx_hash"),
+        (IO_TYPE_INPUT, "consumed_tx_hash"),
+    ] {
+        for start in (0..tx_hashes.len()).step_by(BATCH_SIZE_THRESHOLD) {
+            let end = (start + BATCH_SIZE_THRESHOLD).min(tx_hashes.len());
+            let placeholders = sqlx_param_placeholders(3..end - start + 2)?;
+            let sql = format!(
+                "INSERT INTO mercury_chain_event_cell(event_id, io_type, tx_hash, output_index, lock_hash, type_hash, capacity)
+                SELECT CAST($1 AS bigint), CAST($2 AS smallint), tx_hash, output_index, lock_hash, type_hash, capacity
+                FROM mercury_cell
+                WHERE {} IN ({})",
+                tx_hash_column,
+                placeholders.join(", ")
+            );
+            let mut query = SQLXPool::new_query(&sql)
+                .bind(event_id)
+                .bind(i16::from(io_type));
+            for tx_hash in tx_hashes[start..end].iter() {
+                query = query.bind(tx_hash);
+            }
+            query.execute(&mut *tx).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Record a block applied event for each block synchronized in parallel after the last
+/// event, in the order of the block numbers, so that the events follow the chain without
+/// a gap. It must be called after the consumed cells are updated. Each batch of blocks is
+/// committed separately, and an interrupted call resumes from the last recorded event.
+pub async fn insert_synced_chain_events(pool: &SQLXPool) -> Result<()> {
+    loop {
+        let mut tx = pool.transaction().await?;
+        let last_event = sqlx::query(
+            "SELECT event_type, block_number FROM mercury_chain_event ORDER BY id DESC LIMIT 1",
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        let from = match last_event {
+            // the reverted block is the next one to apply
+            Some(row) if row.get::<i16, _>("event_type") == CHAIN_EVENT_BLOCK_REVERTED => {
+                row.get::<i32, _>("block_number")
+            }
+            Some(row) => row.get::<i32, _>("block_number") + 1,
+            None => 0,
+        };
+
+        let blocks = SQLXPool::new_query(
+            r#"SELECT block_hash, block_number, parent_hash FROM mercury_block
+            WHERE block_number >= $1
+            ORDER BY block_number
+            LIMIT $2"#,
+        )
+        .bind(from)
+        .bind(i64::try_from(BATCH_SIZE_THRESHOLD)?)
+        .fetch_all(&mut *tx)
+        .await?;
+        let to = match blocks.last() {
+            Some(row) => row.get::<i32, _>("block_number"),
+            None => return Ok(()),
+        };
+
+        let mut tx_hashes: HashMap<i32, Vec<Vec<u8>>> = HashMap::new();
+        for row in SQLXPool::new_query(
+            r#"SELECT tx_hash, block_number FROM mercury_transaction
+            WHERE block_number >= $1 AND block_number <= $2
+            ORDER BY block_number, tx_index"#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&mut *tx)
+        .await?
+        {
+            tx_hashes
+                .entry(row.get("block_number"))
+                .or_default()
+                .push(row.get("tx_hash"));
+        }
+
+        for block in blocks.iter() {
+            let block_number: i32 = block.get("block_number");
+            let block_hash: Vec<u8> = block.get("block_hash");
+            let parent_hash: Vec<u8> = block.get("parent_hash");
+            insert_chain_event_table(
+                CHAIN_EVENT_BLOCK_APPLIED,
+                u64::try_from(block_number)?,
+                &block_hash,
+                &parent_hash,
+                &tx_hashes.remove(&block_number).unwrap_or_default(),
+                &mut tx,
+            )
+            .await?;
+        }
+        tx.commit().await?;
+    }
 }
 
 pub fn push_values_placeholders(
@@ -9,26 +9,37 @@ mod tests;
 use crate::relational::{
     fetch::bytes_to_h256, fetch::to_pagination_response, snowflake::Snowflake,
 };
-use crate::{error::DBError, Storage};
+use crate::{error::DBError, Extension, Storage};
 pub use insert::{
     bulk_insert_blocks, bulk_insert_output_cells, bulk_insert_transactions,
-    push_values_placeholders, BATCH_SIZE_THRESHOLD, BLAKE_160_HSAH_LEN, IO_TYPE_INPUT,
-    IO_TYPE_OUTPUT,
+    insert_synced_chain_events, push_values_placeholders, BATCH_SIZE_THRESHOLD, BLAKE_160_HSAH_LEN,
+    IO_TYPE_INPUT, IO_TYPE_OUTPUT,
+};
+use insert::{
+    bulk_insert_webhook_delivery_table, insert_chain_event_table,
+    update_committed_submitted_transactions, CHAIN_EVENT_BLOCK_APPLIED, CHAIN_EVENT_BLOCK_REVERTED,
 };
 use remove::remove_block_table;
 
 use common::{
     async_trait, DetailedCell, Order, PaginationRequest, PaginationResponse, Range, Result,
 };
-use core_rpc_types::indexer::Transaction;
+use core_rpc_types::{
+    indexer::Transaction, AddressLabel, BlockEvent, JsonItem, SubmittedTransaction,
+    TransactionStatus, Webhook,
+};
 use db_sqlx::{build_next_cursor, SQLXPool};
-use protocol::db::{DBDriver, DBInfo, SimpleBlock, SimpleTransaction, TransactionWrapper};
+use protocol::db::{
+    DBDriver, DBInfo, SimpleBlock, SimpleTransaction, TransactionFilter, TransactionWrapper,
+    WebhookDelivery,
+};
 
 use ckb_types::core::{BlockNumber, BlockView, HeaderView};
 use ckb_types::{bytes::Bytes, packed, prelude::*, H160, H256};
 use sqlx::Row;
 
 use std::collections::HashSet;
+use std::sync::Arc;
 
 lazy_static::lazy_static! {
     pub static ref SNOWFLAKE: Snowflake = Snowflake::default();
@@ -37,22 +48,87 @@ lazy_static::lazy_static! {
 #[derive(Clone, Debug)]
 pub struct RelationalStorage {
     pub sqlx_pool: SQLXPool,
+    extensions: Vec<Arc<dyn Extension>>,
 }
 
 #[async_trait]
 impl Storage for RelationalStorage {
     async fn append_block(&self, block: BlockView) -> Result<()> {
+        self.append_block_with_webhook_deliveries(block, vec![], 0)
+            .await
+    }
+
+    async fn append_block_with_webhook_deliveries(
+        &self,
+        block: BlockView,
+        deliveries: Vec<(u64, String)>,
+        created_at: u64,
+    ) -> Result<()> {
         let mut tx = self.sqlx_pool.transaction().await?;
         self.insert_block_table(&block, &mut tx).await?;
         self.insert_transaction_table(&block, &mut tx).await?;
+        update_committed_submitted_transactions(&block, &mut tx).await?;
+        let tx_hashes: Vec<Vec<u8>> = block
+            .tx_hashes()
+            .iter()
+            .map(|tx_hash| tx_hash.raw_data().to_vec())
+            .collect();
+        insert_chain_event_table(
+            CHAIN_EVENT_BLOCK_APPLIED,
+            block.number(),
+            &block.hash().raw_data(),
+            &block.parent_hash().raw_data(),
+            &tx_hashes,
+            &mut tx,
+        )
+        .await?;
+        for extension in self.extensions.iter() {
+            extension.append_block(&block, &mut tx).await?;
+        }
+        bulk_insert_webhook_delivery_table(deliveries, created_at, &mut tx).await?;
         tx.commit().await.map_err(Into::into)
     }
 
     async fn rollback_block(&self, block_number: BlockNumber, block_hash: H256) -> Result<()> {
+        self.rollback_block_with_webhook_deliveries(block_number, block_hash, vec![], 0)
+            .await
+    }
+
+    async fn rollback_block_with_webhook_deliveries(
+        &self,
+        block_number: BlockNumber,
+        block_hash: H256,
+        deliveries: Vec<(u64, String)>,
+        created_at: u64,
+    ) -> Result<()> {
+        let block = self
+            .get_simple_block_by_block_hash(block_hash.clone())
+            .await?;
+        let tx_hashes: Vec<Vec<u8>> = block
+            .transactions
+            .iter()
+            .map(|tx_hash| tx_hash.as_bytes().to_vec())
+            .collect();
+
         let mut tx = self.sqlx_pool.transaction().await?;
+        for extension in self.extensions.iter() {
+            extension
+                .rollback_block(block_number, &block_hash, &mut tx)
+                .await?;
+        }
+        insert_chain_event_table(
+            CHAIN_EVENT_BLOCK_REVERTED,
+            block_number,
+            block_hash.as_bytes(),
+            block.parent_hash.as_bytes(),
+            &tx_hashes,
+            &mut tx,
+        )
+        .await?;
         self.remove_tx_and_cell(block_number, block_hash.clone(), &mut tx)
             .await?;
         remove_block_table(block_number, block_hash, &mut tx).await?;
+        bulk_insert_webhook_delivery_table(deliveries, created_at, &mut tx).await?;
         tx.commit().await.map_err(Into::into)
     }
 
@@ -75,6 +151,16 @@ impl Storage for RelationalStorage {
         .await
     }
 
+    async fn get_cells_by_out_points(
+        &self,
+        out_points: Vec<packed::OutPoint>,
+    ) -> Result<Vec<DetailedCell>> {
+        if out_points.is_empty() {
+            return Ok(vec![]);
+        }
+        self.query_cells_by_out_points(&out_points).await
+    }
+
     async fn get_live_cells(
         &self,
         out_point: Option<packed::OutPoint>,
@@ -220,6 +306,7 @@ impl Storage for RelationalStorage {
         type_hashes: Vec<H256>,
         block_range: Option<Range>,
         limit_cellbase: bool,
+        filter: TransactionFilter,
         pagination: PaginationRequest,
     ) -> Result<PaginationResponse<TransactionWrapper>> {
         if block_range.is_none() && lock_hashes.is_empty() && type_hashes.is_empty() {
@@ -229,12 +316,26 @@ impl Storage for RelationalStorage {
             .into());
         }
 
+        let udt_amount_change_tx_hashes = match filter.udt_amount_change_range {
+            Some((ref type_hash, ref amount_range)) => {
+                self.query_udt_amount_change_tx_hashes(
+                    &lock_hashes,
+                    type_hash,
+                    &block_range,
+                    amount_range,
+                )
+                .await?
+            }
+            None => vec![],
+        };
         let tx_hashes = self
             .query_transaction_hashes_by_scripts(
                 &lock_hashes,
                 &type_hashes,
                 &block_range,
                 limit_cellbase,
+                &filter,
+                &udt_amount_change_tx_hashes,
                 &pagination,
             )
             .await?;
@@ -246,6 +347,8 @@ impl Storage for RelationalStorage {
                     &type_hashes,
                     &block_range,
                     limit_cellbase,
+                    &filter,
+                    &udt_amount_change_tx_hashes,
                 )
                 .await?;
             Some(count)
@@ -403,6 +506,118 @@ impl Storage for RelationalStorage {
         self.bulk_insert_registered_address_table(addresses).await
     }
 
+    async fn insert_submitted_transaction(
+        &self,
+        tx_hash: H256,
+        status: TransactionStatus,
+        reason: Option<String>,
+        submitted_at: u64,
+    ) -> Result<()> {
+        self.insert_submitted_transaction_table(tx_hash, status, reason, submitted_at)
+            .await
+    }
+
+    async fn update_submitted_transactions(
+        &self,
+        tx_hashes: Vec<H256>,
+        status: TransactionStatus,
+        reason: Option<String>,
+        updated_at: u64,
+    ) -> Result<()> {
+        if tx_hashes.is_empty() {
+            return Ok(());
+        }
+        self.update_submitted_transaction_table(tx_hashes, status, reason, updated_at)
+            .await
+    }
+
+    async fn get_submitted_transactions(
+        &self,
+        tx_hashes: Vec<H256>,
+        statuses: Vec<TransactionStatus>,
+        pagination: PaginationRequest,
+    ) -> Result<PaginationResponse<SubmittedTransaction>> {
+        self.query_submitted_transactions(tx_hashes, statuses, pagination)
+            .await
+    }
+
+    async fn set_address_labels(
+        &self,
+        labels: Vec<AddressLabel>,
+        removed_items: Vec<JsonItem>,
+    ) -> Result<()> {
+        if labels.is_empty() && removed_items.is_empty() {
+            return Ok(());
+        }
+        let mut tx = self.sqlx_pool.transaction().await?;
+        self.upsert_address_label_table(labels, &mut tx).await?;
+        self.delete_address_label_table(removed_items, &mut tx)
+            .await?;
+        tx.commit().await.map_err(Into::into)
+    }
+
+    async fn get_address_labels(&self, items: Vec<JsonItem>) -> Result<Vec<AddressLabel>> {
+        if items.is_empty() {
+            return Ok(vec![]);
+        }
+        self.query_address_labels(items).await
+    }
+
+    async fn search_address_labels(
+        &self,
+        keyword: String,
+        pagination: PaginationRequest,
+    ) -> Result<PaginationResponse<AddressLabel>> {
+        self.query_address_labels_by_keyword(keyword, pagination)
+            .await
+    }
+
+    async fn register_webhook(
+        &self,
+        url: String,
+        items: Vec<JsonItem>,
+        secret: String,
+        created_at: u64,
+    ) -> Result<u64> {
+        self.insert_webhook_table(url, items, secret, created_at)
+            .await
+    }
+
+    async fn get_webhooks(&self) -> Result<Vec<Webhook>> {
+        self.query_webhooks().await
+    }
+
+    async fn remove_webhook(&self, id: u64) -> Result<bool> {
+        self.delete_webhook_table(id).await
+    }
+
+    async fn get_pending_webhook_deliveries(
+        &self,
+        now: u64,
+        limit: u64,
+    ) -> Result<Vec<WebhookDelivery>> {
+        self.query_pending_webhook_deliveries(now, limit).await
+    }
+
+    async fn complete_webhook_delivery(&self, id: u64) -> Result<()> {
+        self.delete_webhook_delivery_table(id).await
+    }
+
+    async fn fail_webhook_delivery(
+        &self,
+        id: u64,
+        attempts: u32,
+        next_attempt_at: Option<u64>,
+        error: String,
+    ) -> Result<()> {
+        self.update_webhook_delivery_table(id, attempts, next_attempt_at, error)
+            .await
+    }
+
+    async fn get_chain_events(&self, cursor: u64, limit: u64) -> Result<Vec<BlockEvent>> {
+        self.query_chain_events(cursor, limit).await
+    }
+
     fn get_db_info(&self) -> Result<DBInfo> {
         let info = SNOWFLAKE.get_info();
 
@@ -436,6 +651,14 @@ impl Storage for RelationalStorage {
         }
     }
 
+    async fn get_simple_blocks(&self, block_range: Range, limit: u64) -> Result<Vec<SimpleBlock>> {
+        self.get_simple_blocks_by_range(block_range, limit).await
+    }
+
+    async fn get_block_number_by_timestamp(&self, timestamp: u64) -> Result<Option<BlockNumber>> {
+        self.query_block_number_by_timestamp(timestamp).await
+    }
+
     async fn get_indexer_transactions(
         &self,
         lock_hashes: Vec<H256>,
@@ -481,7 +704,10 @@ impl RelationalStorage {
             max_lifetime,
             idle_timeout,
         );
-        RelationalStorage { sqlx_pool }
+        RelationalStorage {
+            sqlx_pool,
+            extensions: vec![],
+        }
     }
 
     pub async fn connect(
@@ -499,6 +725,21 @@ impl RelationalStorage {
         Ok(())
     }
 
+    pub fn register_extensions(&mut self, extensions: Vec<Arc<dyn Extension>>) {
+        self.extensions = extensions;
+    }
+
+    /// Create the tables of the registered extensions, the database must be connected.
+    pub async fn create_extension_tables(&self) -> Result<()> {
+        let mut tx = self.sqlx_pool.transaction().await?;
+        for extension in self.extensions.iter() {
+            for schema in extension.table_schemas() {
+                sqlx::query(&schema).execute(&mut *tx).await?;
+            }
+        }
+        tx.commit().await.map_err(Into::into)
+    }
+
     pub fn get_pool(&self) -> SQLXPool {
         self.sqlx_pool.clone()
     }
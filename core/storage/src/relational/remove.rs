@@ -1,9 +1,11 @@
 use crate::relational::fetch::sqlx_param_placeholders;
+use crate::relational::insert::encode_transaction_status;
 use crate::relational::RelationalStorage;
 
 use ckb_types::core::BlockNumber;
 use ckb_types::H256;
 use common::Result;
+use core_rpc_types::TransactionStatus;
 use sql_builder::SqlBuilder;
 use sqlx::{Any, Transaction};
 
@@ -60,6 +62,17 @@ pub(crate) async fn remove_block_table(
     .execute(&mut *tx)
     .await?;
 
+    // the tracked transactions committed in the block return to the tx pool
+    sqlx::query(
+        r#"UPDATE mercury_submitted_transaction
+            SET status = $1, block_number = NULL, block_hash = NULL
+            WHERE block_hash = $2"#,
+    )
+    .bind(encode_transaction_status(&TransactionStatus::Pending))
+    .bind(block_hash.as_bytes())
+    .execute(&mut *tx)
+    .await?;
+
     Ok(())
 }
 
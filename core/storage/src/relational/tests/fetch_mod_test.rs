@@ -196,3 +196,129 @@ async fn test_get_scripts_by_partial_arg() {
     assert_eq!(1, ret.len());
     assert_eq!(script_hash, ret[0].calc_script_hash().unpack())
 }
+
+/// The scripts and the transactions of a block, in which the lock hashes and the script
+/// args are also the values of the other filters.
+struct CrossFilterBlock {
+    self_typed: packed::Script,
+    lock: packed::Script,
+    type_script: packed::Script,
+    self_typed_tx_hash: H256,
+    tx_hash: H256,
+}
+
+async fn append_cross_filter_block(storage: &RelationalStorage) -> CrossFilterBlock {
+    use ckb_types::bytes::Bytes;
+    use ckb_types::core::{BlockBuilder, HeaderBuilder, TransactionBuilder};
+
+    let code_hash = h256!("0x9bd7e06f3ecf4be0f2fcd2188b23f1b9fcc88e5d4b65a8637b17723bbda3cce8");
+    let new_script = |code_hash: &H256, args: &[u8]| {
+        packed::Script::new_builder()
+            .code_hash(code_hash.pack())
+            .hash_type(ScriptHashType::Data.into())
+            .args(Bytes::from(args.to_vec()).pack())
+            .build()
+    };
+    // the args of the self typed script are the code hash of the others
+    let self_typed = new_script(&code_hash, code_hash.as_bytes());
+    let lock = new_script(&code_hash, &[1u8; 20]);
+    let type_script = new_script(&h256!("0x1"), &[2u8; 20]);
+    let new_tx = |lock: &packed::Script, type_script: &packed::Script| {
+        TransactionBuilder::default()
+            .output(
+                packed::CellOutputBuilder::default()
+                    .capacity(100_000_000_000u64.pack())
+                    .lock(lock.clone())
+                    .type_(Some(type_script.clone()).pack())
+                    .build(),
+            )
+            .output_data(Default::default())
+            .build()
+    };
+    let self_typed_tx = new_tx(&self_typed, &self_typed);
+    let tx = new_tx(&lock, &type_script);
+    let block = BlockBuilder::default()
+        .header(HeaderBuilder::default().number(10u64.pack()).build())
+        .transaction(self_typed_tx.clone())
+        .transaction(tx.clone())
+        .build();
+    storage.append_block(block).await.unwrap();
+
+    CrossFilterBlock {
+        self_typed,
+        lock,
+        type_script,
+        self_typed_tx_hash: self_typed_tx.hash().unpack(),
+        tx_hash: tx.hash().unpack(),
+    }
+}
+
+#[tokio::test]
+async fn test_query_by_lock_and_type_hashes() {
+    let storage = connect_and_insert_blocks().await;
+    let block = append_cross_filter_block(&storage).await;
+    // the type hash of the self typed cell is one of the lock hashes, which is not
+    // matched by the type hashes
+    let lock_hashes: Vec<H256> = vec![
+        block.self_typed.calc_script_hash().unpack(),
+        block.lock.calc_script_hash().unpack(),
+    ];
+    let type_hashes: Vec<H256> = vec![block.type_script.calc_script_hash().unpack()];
+
+    let cells = storage
+        .get_live_cells(
+            None,
+            lock_hashes.clone(),
+            type_hashes.clone(),
+            None,
+            None,
+            None,
+            PaginationRequest::default(),
+        )
+        .await
+        .unwrap()
+        .response;
+    assert_eq!(1, cells.len());
+    assert_eq!(block.lock, cells[0].cell_output.lock());
+
+    let cells = storage
+        .get_cells(
+            None,
+            lock_hashes.clone(),
+            type_hashes.clone(),
+            None,
+            PaginationRequest::default(),
+        )
+        .await
+        .unwrap()
+        .response;
+    assert_eq!(1, cells.len());
+    assert_eq!(block.lock, cells[0].cell_output.lock());
+
+    let txs = storage
+        .get_indexer_transactions(lock_hashes, type_hashes, None, PaginationRequest::default())
+        .await
+        .unwrap()
+        .response;
+    assert_eq!(1, txs.len());
+    assert_eq!(block.tx_hash, txs[0].tx_hash);
+    assert_ne!(block.self_typed_tx_hash, txs[0].tx_hash);
+}
+
+#[tokio::test]
+async fn test_get_scripts_by_code_hash_and_args() {
+    let storage = connect_and_insert_blocks().await;
+    let block = append_cross_filter_block(&storage).await;
+
+    // the args of the self typed script are the code hash, which is not matched by the args
+    let ret = storage
+        .get_scripts(
+            vec![],
+            vec![block.lock.code_hash().unpack()],
+            None,
+            vec![block.lock.args().raw_data()],
+        )
+        .await
+        .unwrap();
+    assert_eq!(vec![block.lock], ret);
+}
@@ -35,6 +35,43 @@ async fn test_get_simple_block() {
     assert_eq!(tx_hashes, block_info.transactions);
 }
 
+#[tokio::test]
+async fn test_get_simple_blocks() {
+    let pool = connect_and_insert_blocks().await;
+    let blocks = pool.get_simple_blocks(Range::new(2, 100), 5).await.unwrap();
+    assert_eq!(blocks.len(), 5);
+    for (block, block_number) in blocks.into_iter().zip(2..) {
+        let block_info = pool
+            .get_simple_block(None, Some(block_number))
+            .await
+            .unwrap();
+        assert_eq!(block.block_number, block_number);
+        assert_eq!(block.block_hash, block_info.block_hash);
+        assert_eq!(block.transactions, block_info.transactions);
+    }
+
+    let blocks = pool.get_simple_blocks(Range::new(8, 100), 5).await.unwrap();
+    assert_eq!(blocks.len(), 2);
+}
+
+#[tokio::test]
+async fn test_get_block_number_by_timestamp() {
+    let pool = connect_and_insert_blocks().await;
+    let block_info = pool.get_simple_block(None, Some(5)).await.unwrap();
+    let block_number = pool
+        .get_block_number_by_timestamp(block_info.timestamp)
+        .await
+        .unwrap();
+    assert_eq!(block_number, Some(5));
+
+    let genesis = pool.get_simple_block(None, Some(0)).await.unwrap();
+    let block_number = pool
+        .get_block_number_by_timestamp(genesis.timestamp - 1)
+        .await
+        .unwrap();
+    assert_eq!(block_number, None);
+}
+
 #[tokio::test]
 async fn test_get_block_of_genesis() {
     let pool = connect_and_insert_blocks().await;
@@ -7,14 +7,14 @@ mod other_test;
 mod single_sql_test;
 
 use crate::relational::fetch::bytes_to_h256;
-use crate::relational::{DBDriver, PaginationRequest};
+use crate::relational::{DBDriver, PaginationRequest, BATCH_SIZE_THRESHOLD};
 use crate::{relational::RelationalStorage, Storage};
 
 use ckb_jsonrpc_types::BlockView as JsonBlockView;
 use ckb_types::core::ScriptHashType;
 use ckb_types::{core::BlockView, h160, h256, packed, prelude::*, H160, H256};
 use common::{Order, Range};
-use core_rpc_types::IOType;
+use core_rpc_types::{AddressLabel, BlockEventType, IOType, JsonItem, TransactionStatus};
 
 use std::str::FromStr;
 
@@ -101,6 +101,85 @@ async fn test_register_addresses() {
     assert_eq!(res, Some(address));
 }
 
+#[tokio::test]
+async fn test_address_labels() {
+    let pool = connect_sqlite().await;
+    let tx = pool.sqlx_pool.transaction().await.unwrap();
+    xsql_test::create_tables(tx).await.unwrap();
+
+    let address = JsonItem::Address(String::from(
+        "ckb1qyqt8xaupvm8837nv3gtc9x0ekkj64vud3jqfwyw5v",
+    ));
+    let identity = JsonItem::Identity(String::from("0x00b39bbc0b3673c7d36450bc14cfcdad2d559c6c64"));
+    let hot_wallet = AddressLabel {
+        item: address.clone(),
+        label: String::from("Hot Wallet"),
+        metadata: Some(serde_json::json!({"owner": "ops"})),
+        updated_at: 1u64.into(),
+    };
+    let customer = AddressLabel {
+        item: identity.clone(),
+        label: String::from("customer_123"),
+        metadata: None,
+        updated_at: 2u64.into(),
+    };
+    pool.set_address_labels(vec![hot_wallet.clone(), customer.clone()], vec![])
+        .await
+        .unwrap();
+    let res = pool
+        .get_address_labels(vec![address.clone(), identity.clone()])
+        .await
+        .unwrap();
+    assert_eq!(res.len(), 2);
+    assert!(res.contains(&hot_wallet));
+    assert!(res.contains(&customer));
+
+    // the wildcards in the keyword are matched literally
+    let pagination = PaginationRequest::new(None, Order::Asc, None, None, true);
+    let res = pool
+        .search_address_labels(String::from("WALLET"), pagination.clone())
+        .await
+        .unwrap();
+    assert_eq!(res.response, vec![hot_wallet]);
+    let res = pool
+        .search_address_labels(String::from("r_1"), pagination.clone())
+        .await
+        .unwrap();
+    assert_eq!(res.response, vec![customer.clone()]);
+    let res = pool
+        .search_address_labels(String::from("t_w"), pagination)
+        .await
+        .unwrap();
+    assert!(res.response.is_empty());
+
+    let cold = AddressLabel {
+        item: address.clone(),
+        label: String::from("cold"),
+        metadata: None,
+        updated_at: 3u64.into(),
+    };
+    pool.set_address_labels(vec![cold.clone()], vec![identity.clone()])
+        .await
+        .unwrap();
+    let res = pool
+        .get_address_labels(vec![address.clone(), identity.clone()])
+        .await
+        .unwrap();
+    assert_eq!(res, vec![cold.clone()]);
+
+    // the labels are set and removed at once
+    let out_point = JsonItem::OutPoint(packed::OutPoint::default().into());
+    let res = pool
+        .set_address_labels(vec![customer], vec![address.clone(), out_point])
+        .await;
+    assert!(res.is_err());
+    let res = pool
+        .get_address_labels(vec![address, identity])
+        .await
+        .unwrap();
+    assert_eq!(res, vec![cold]);
+}
+
 #[tokio::test]
 async fn test_get_db_info() {
     let pool = connect_sqlite().await;
@@ -270,3 +349,236 @@ async fn test_rollback_block() {
             .unwrap()
     );
 }
+
+#[tokio::test]
+async fn test_chain_events() {
+    let storage = connect_and_create_tables().await;
+    let block: BlockView = read_block_view(0, String::from(BLOCK_DIR)).into();
+    let block_hash: H256 = block.hash().unpack();
+    storage.append_block(block.clone()).await.unwrap();
+    let events = storage.get_chain_events(0, 10).await.unwrap();
+    assert!(events[0]
+        .created_cells
+        .iter()
+        .all(|cell| cell.data.is_some()));
+    storage.rollback_block(0, block_hash.clone()).await.unwrap();
+
+    // the data of the cells removed by the rollback is gone
+    let events = storage.get_chain_events(0, 10).await.unwrap();
+    assert!(events[0]
+        .created_cells
+        .iter()
+        .all(|cell| cell.data.is_none()));
+    assert_eq!(2, events.len());
+    assert_eq!(BlockEventType::BlockApplied, events[0].event_type);
+    assert_eq!(BlockEventType::BlockReverted, events[1].event_type);
+    for event in events.iter() {
+        assert_eq!(block_hash, event.block_hash);
+        assert_eq!(0u64, event.block_number.value());
+        assert_eq!(12, event.created_cells.len());
+    }
+    assert_eq!(events[0].created_cells, events[1].created_cells);
+    assert_eq!(events[0].consumed_cells, events[1].consumed_cells);
+
+    let events_after_cursor = storage
+        .get_chain_events(events[0].id.value(), 10)
+        .await
+        .unwrap();
+    assert_eq!(1, events_after_cursor.len());
+    assert_eq!(events[1].id, events_after_cursor[0].id);
+
+    // the block applied again is recorded after the reverted one
+    storage.append_block(block).await.unwrap();
+    let events_after_cursor = storage
+        .get_chain_events(events[1].id.value(), 10)
+        .await
+        .unwrap();
+    assert_eq!(1, events_after_cursor.len());
+    assert_eq!(
+        BlockEventType::BlockApplied,
+        events_after_cursor[0].event_type
+    );
+    assert!(events_after_cursor[0].id.value() > events[1].id.value());
+}
+
+async fn pending_payloads(storage: &RelationalStorage, now: u64) -> Vec<(u64, String)> {
+    storage
+        .get_pending_webhook_deliveries(now, 10)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|delivery| (delivery.id, delivery.payload))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_webhook_deliveries() {
+    let storage = connect_and_create_tables().await;
+    let items = vec![JsonItem::Address(String::from(
+        "ckb1qyqt8xaupvm8837nv3gtc9x0ekkj64vud3jqfwyw5v",
+    ))];
+    let busy = storage
+        .register_webhook(
+            "http://127.0.0.1/busy".to_string(),
+            items.clone(),
+            "secret".to_string(),
+            0,
+        )
+        .await
+        .unwrap();
+    let idle = storage
+        .register_webhook(
+            "http://127.0.0.1/idle".to_string(),
+            items,
+            "secret".to_string(),
+            0,
+        )
+        .await
+        .unwrap();
+    assert!(idle > busy);
+
+    let block: BlockView = read_block_view(0, String::from(BLOCK_DIR)).into();
+    let deliveries = vec![
+        (busy, "first".to_string()),
+        (busy, "second".to_string()),
+        (idle, "third".to_string()),
+    ];
+    storage
+        .append_block_with_webhook_deliveries(block, deliveries, 0)
+        .await
+        .unwrap();
+
+    // only the head of the queue of each webhook is due
+    let heads = pending_payloads(&storage, 0).await;
+    assert_eq!(2, heads.len());
+    assert_eq!("first", heads[0].1);
+    assert_eq!("third", heads[1].1);
+
+    // the head retried later holds back its own queue only
+    storage
+        .fail_webhook_delivery(heads[0].0, 1, Some(100), "error".to_string())
+        .await
+        .unwrap();
+    assert_eq!(vec![heads[1].clone()], pending_payloads(&storage, 0).await);
+    storage.complete_webhook_delivery(heads[1].0).await.unwrap();
+    assert!(pending_payloads(&storage, 0).await.is_empty());
+    storage.complete_webhook_delivery(heads[0].0).await.unwrap();
+    let heads = pending_payloads(&storage, 100).await;
+    assert_eq!(1, heads.len());
+    assert_eq!("second", heads[0].1);
+}
+
+#[tokio::test]
+async fn test_get_cells_by_out_points() {
+    let storage = connect_and_insert_blocks().await;
+    let block: BlockView = read_block_view(0, String::from(BLOCK_DIR)).into();
+    let out_points = vec![
+        packed::OutPoint::new(block.tx_hashes()[1].clone(), 1),
+        packed::OutPoint::new(H256([1u8; 32]).pack(), 0),
+        packed::OutPoint::new(block.tx_hashes()[0].clone(), 0),
+    ];
+    let cells = storage
+        .get_cells_by_out_points(out_points.clone())
+        .await
+        .unwrap();
+    assert_eq!(2, cells.len());
+    for cell in cells.iter() {
+        assert!(out_points.contains(&cell.out_point));
+    }
+}
+
+#[tokio::test]
+async fn test_submitted_transactions() {
+    let storage = connect_and_create_tables().await;
+    let block: BlockView = read_block_view(0, String::from(BLOCK_DIR)).into();
+    let block_hash: H256 = block.hash().unpack();
+    let committed_hash: H256 = block.tx_hashes()[1].unpack();
+    let rejected_hash = H256([1u8; 32]);
+    let status_of = |tx_hash: H256| {
+        let storage = storage.clone();
+        async move {
+            storage
+                .get_submitted_transactions(vec![tx_hash], vec![], PaginationRequest::default())
+                .await
+                .unwrap()
+                .response
+                .pop()
+                .unwrap()
+        }
+    };
+
+    // a pending transaction is not rejected by sending it again
+    storage
+        .insert_submitted_transaction(committed_hash.clone(), TransactionStatus::Pending, None, 1)
+        .await
+        .unwrap();
+    storage
+        .insert_submitted_transaction(
+            committed_hash.clone(),
+            TransactionStatus::Rejected,
+            Some("duplicated".to_string()),
+            2,
+        )
+        .await
+        .unwrap();
+    let tx = status_of(committed_hash.clone()).await;
+    assert_eq!(TransactionStatus::Pending, tx.status);
+    assert_eq!(None, tx.reason);
+    assert_eq!(1u64, tx.updated_at.value());
+
+    // a rejected transaction is tracked again
+    storage
+        .insert_submitted_transaction(
+            rejected_hash.clone(),
+            TransactionStatus::Rejected,
+            Some("dead input".to_string()),
+            1,
+        )
+        .await
+        .unwrap();
+    storage
+        .insert_submitted_transaction(rejected_hash.clone(), TransactionStatus::Pending, None, 2)
+        .await
+        .unwrap();
+    let tx = status_of(rejected_hash).await;
+    assert_eq!(TransactionStatus::Pending, tx.status);
+    assert_eq!(None, tx.reason);
+    assert_eq!(2u64, tx.updated_at.value());
+
+    // the hashes out of the first batch are updated
+    let mut tx_hashes = (0..BATCH_SIZE_THRESHOLD)
+        .map(|i| H256::from_slice(&[&[2u8; 24][..], &i.to_le_bytes()[..]].concat()).unwrap())
+        .collect::<Vec<_>>();
+    tx_hashes.push(committed_hash.clone());
+    storage
+        .update_submitted_transactions(tx_hashes, TransactionStatus::Proposed, None, 3)
+        .await
+        .unwrap();
+    assert_eq!(
+        TransactionStatus::Proposed,
+        status_of(committed_hash.clone()).await.status
+    );
+
+    storage.append_block(block).await.unwrap();
+    let tx = status_of(committed_hash.clone()).await;
+    assert_eq!(TransactionStatus::Committed, tx.status);
+    assert_eq!(Some(block_hash.clone()), tx.block_hash);
+    storage
+        .insert_submitted_transaction(
+            committed_hash.clone(),
+            TransactionStatus::Rejected,
+            Some("dead input".to_string()),
+            4,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        TransactionStatus::Committed,
+        status_of(committed_hash.clone()).await.status
+    );
+
+    storage.rollback_block(0, block_hash).await.unwrap();
+    let tx = status_of(committed_hash).await;
+    assert_eq!(TransactionStatus::Pending, tx.status);
+    assert_eq!(None, tx.block_hash);
+}
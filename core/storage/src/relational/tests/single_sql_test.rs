@@ -1,6 +1,7 @@
 use super::*;
 
 use ckb_types::H256;
+use protocol::db::{TransactionDirection, TransactionFilter};
 use std::str::FromStr;
 
 #[tokio::test]
@@ -8,19 +9,33 @@ async fn test_fetch_distinct_tx_hashes_count_by_range_() {
     let pool = connect_and_insert_blocks().await;
 
     let res = pool
-        .query_distinct_tx_hashes_count(&[], &[], &Some(Range::new(0, 10)), false)
+        .query_distinct_tx_hashes_count(
+            &[],
+            &[],
+            &Some(Range::new(0, 10)),
+            false,
+            &TransactionFilter::default(),
+            &[],
+        )
         .await
         .unwrap();
     assert_eq!(2, res);
 
     let res = pool
-        .query_distinct_tx_hashes_count(&[], &[], &Some(Range::new(1, 10)), false)
+        .query_distinct_tx_hashes_count(
+            &[],
+            &[],
+            &Some(Range::new(1, 10)),
+            false,
+            &TransactionFilter::default(),
+            &[],
+        )
         .await
         .unwrap();
     assert_eq!(0, res);
 
     let res = pool
-        .query_distinct_tx_hashes_count(&[], &[], &None, false)
+        .query_distinct_tx_hashes_count(&[], &[], &None, false, &TransactionFilter::default(), &[])
         .await;
     assert!(res.is_err());
 }
@@ -33,20 +48,87 @@ async fn test_fetch_distinct_tx_hashes_count_by_lock_hash() {
         H256::from_str("ba93972fbe398074f4e0bc538d7e36e61a8b140585b52deb4d2890e8d9d320f0").unwrap();
 
     let res = pool
-        .query_distinct_tx_hashes_count(&[lock_hash.clone()], &[], &Some(Range::new(0, 10)), false)
+        .query_distinct_tx_hashes_count(
+            &[lock_hash.clone()],
+            &[],
+            &Some(Range::new(0, 10)),
+            false,
+            &TransactionFilter::default(),
+            &[],
+        )
         .await
         .unwrap();
     assert_eq!(1, res);
 
     let res = pool
-        .query_distinct_tx_hashes_count(&[lock_hash.clone()], &[], &Some(Range::new(1, 10)), false)
+        .query_distinct_tx_hashes_count(
+            &[lock_hash.clone()],
+            &[],
+            &Some(Range::new(1, 10)),
+            false,
+            &TransactionFilter::default(),
+            &[],
+        )
         .await
         .unwrap();
     assert_eq!(0, res);
 
     let res = pool
-        .query_distinct_tx_hashes_count(&[lock_hash], &[], &None, false)
+        .query_distinct_tx_hashes_count(
+            &[lock_hash],
+            &[],
+            &None,
+            false,
+            &TransactionFilter::default(),
+            &[],
+        )
         .await
         .unwrap();
     assert_eq!(1, res);
 }
+
+#[tokio::test]
+async fn test_fetch_distinct_tx_hashes_count_by_filter() {
+    let pool = connect_and_insert_blocks().await;
+
+    let lock_hash =
+        H256::from_str("ba93972fbe398074f4e0bc538d7e36e61a8b140585b52deb4d2890e8d9d320f0").unwrap();
+    let count = |filter: TransactionFilter| {
+        let pool = &pool;
+        let lock_hash = lock_hash.clone();
+        async move {
+            pool.query_distinct_tx_hashes_count(&[lock_hash], &[], &None, false, &filter, &[])
+                .await
+                .unwrap()
+        }
+    };
+
+    // the genesis transaction only creates the cells of the lock
+    let incoming = TransactionFilter {
+        direction: Some(TransactionDirection::Incoming),
+        ..Default::default()
+    };
+    assert_eq!(1, count(incoming).await);
+    let outgoing = TransactionFilter {
+        direction: Some(TransactionDirection::Outgoing),
+        ..Default::default()
+    };
+    assert_eq!(0, count(outgoing).await);
+
+    let time_range = TransactionFilter {
+        time_range: Some(Range::new(0, u64::MAX)),
+        ..Default::default()
+    };
+    assert_eq!(1, count(time_range).await);
+    let time_range = TransactionFilter {
+        time_range: Some(Range::new(0, 0)),
+        ..Default::default()
+    };
+    assert_eq!(0, count(time_range).await);
+
+    let capacity_change_range = TransactionFilter {
+        capacity_change_range: Some(Range::new(0, u64::MAX)),
+        ..Default::default()
+    };
+    assert_eq!(1, count(capacity_change_range).await);
+}
@@ -8,6 +8,7 @@ use crate::task::{Task, TaskType};
 
 use common::{async_trait, Result};
 use core_rpc_types::{SyncProgress, SyncState};
+use core_storage::{relational::insert_synced_chain_events, Extension};
 use db_sqlx::SQLXPool;
 
 use ckb_types::core::{BlockNumber, BlockView};
@@ -34,6 +35,7 @@ pub struct Synchronization<T> {
     max_task_number: usize,
     chain_tip: u64,
     sync_state: Arc<RwLock<SyncState>>,
+    extensions: Vec<Arc<dyn Extension>>,
 
     adapter: Arc<T>,
 }
@@ -45,12 +47,14 @@ impl<T: SyncAdapter> Synchronization<T> {
         max_task_number: usize,
         chain_tip: u64,
         sync_state: Arc<RwLock<SyncState>>,
+        extensions: Vec<Arc<dyn Extension>>,
     ) -> Self {
         Synchronization {
             sqlx_pool,
             max_task_number,
             chain_tip,
             sync_state,
+            extensions,
             adapter,
         }
     }
@@ -99,6 +103,10 @@ impl<T: SyncAdapter> Synchronization<T> {
         sql::remove_in_update(&mut tx).await?;
 
         tx.commit().await.expect("insert into");
+
+        log::info!("[sync] insert into chain event table");
+        insert_synced_chain_events(&self.sqlx_pool).await?;
+
         sleep(Duration::from_secs(10)).await;
         Ok(())
     }
@@ -116,6 +124,7 @@ impl<T: SyncAdapter> Synchronization<T> {
                 self.sqlx_pool.clone(),
                 Arc::clone(&self.adapter),
                 TaskType::SyncIndexerCell,
+                self.extensions.clone(),
             );
 
             if task.check_done().await? {
@@ -158,6 +167,7 @@ impl<T: SyncAdapter> Synchronization<T> {
                 self.sqlx_pool.clone(),
                 Arc::clone(&self.adapter),
                 TaskType::SyncMetadata,
+                self.extensions.clone(),
             );
 
             if task.check_done().await.expect("task is done") {
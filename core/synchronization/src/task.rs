@@ -5,6 +5,7 @@ use core_storage::relational::{
     bulk_insert_blocks, bulk_insert_output_cells, bulk_insert_transactions, generate_id,
     push_values_placeholders, BATCH_SIZE_THRESHOLD, IO_TYPE_INPUT, IO_TYPE_OUTPUT,
 };
+use core_storage::Extension;
 use db_sqlx::SQLXPool;
 
 use ckb_types::{
@@ -42,18 +43,27 @@ pub struct Task<T> {
     pool: SQLXPool,
     type_: TaskType,
     state_cursor: Option<u64>,
+    extensions: Vec<Arc<dyn Extension>>,
 
     adapter: Arc<T>,
 }
 
 impl<T: SyncAdapter> Task<T> {
-    pub fn new(id: u64, tip: u64, pool: SQLXPool, adapter: Arc<T>, type_: TaskType) -> Task<T> {
+    pub fn new(
+        id: u64,
+        tip: u64,
+        pool: SQLXPool,
+        adapter: Arc<T>,
+        type_: TaskType,
+        extensions: Vec<Arc<dyn Extension>>,
+    ) -> Task<T> {
         Task {
             id,
             tip,
             pool,
             type_,
             state_cursor: None,
+            extensions,
             adapter,
         }
     }
@@ -115,7 +125,7 @@ impl<T: SyncAdapter> Task<T> {
             let end = (start + PULL_BLOCK_BATCH_SIZE).min(last + 1);
             let sub_task = (start..end).collect();
             let blocks = self.poll_call(Self::pull_blocks, sub_task).await;
-            sync_blocks(blocks, self.pool.clone()).await?;
+            sync_blocks(blocks, self.pool.clone(), &self.extensions).await?;
         }
 
         free_one_task();
@@ -181,7 +191,11 @@ impl<T: SyncAdapter> Task<T> {
     }
 }
 
-async fn sync_blocks(blocks: Vec<BlockView>, pool: SQLXPool) -> Result<()> {
+async fn sync_blocks(
+    blocks: Vec<BlockView>,
+    pool: SQLXPool,
+    extensions: &[Arc<dyn Extension>],
+) -> Result<()> {
     let mut tx = pool.transaction().await?;
 
     bulk_insert_blocks(&blocks, &mut tx).await?;
@@ -204,6 +218,10 @@ async fn sync_blocks(blocks: Vec<BlockView>, pool: SQLXPool) -> Result<()> {
         bulk_insert_output_cells(block_number, &block_hash, epoch, &tx_views, false, &mut tx)
             .await?;
         bulk_insert_consume_info(block_number, &block_hash, &tx_views, &mut tx).await?;
+
+        for extension in extensions.iter() {
+            extension.append_block(block, &mut tx).await?;
+        }
     }
 
     tx.commit().await.map_err(Into::into)
@@ -2,32 +2,68 @@ use super::*;
 
 use crate::Synchronization;
 
-use core_rpc_types::SyncState;
-use core_storage::Storage;
+use common::utils::ScriptInfo;
+use core_rpc_types::{BlockEventType, SyncState};
+use core_storage::{Extension, Storage};
 
 use ckb_types::prelude::Unpack;
 use ckb_types::H256;
 use parking_lot::RwLock;
+use sqlx::{Any, Transaction};
 
 use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
+#[derive(Debug, Default)]
+struct AppendedBlockCounter(AtomicU64);
+
+#[async_trait]
+impl Extension for AppendedBlockCounter {
+    fn name(&self) -> &str {
+        "appended_block_counter"
+    }
+
+    fn script_infos(&self) -> Vec<ScriptInfo> {
+        vec![]
+    }
+
+    async fn append_block(&self, _block: &BlockView, _tx: &mut Transaction<'_, Any>) -> Result<()> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn rollback_block(
+        &self,
+        _block_number: BlockNumber,
+        _block_hash: &H256,
+        _tx: &mut Transaction<'_, Any>,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
 #[tokio::test]
 async fn test_sync() {
     let res = connect_and_create_tables().await;
     assert!(res.is_ok());
 
     let storage = res.unwrap();
+    let counter = Arc::new(AppendedBlockCounter::default());
     let sync_handler = Synchronization::new(
         storage.get_pool(),
         Arc::new(CkbRpcTestClient),
         4,
         9,
         Arc::new(RwLock::new(SyncState::ReadOnly)),
+        vec![Arc::clone(&counter) as Arc<dyn Extension>],
     );
     sync_handler.do_sync().await.unwrap();
     sync_handler.build_indexer_cell_table().await.unwrap();
 
+    // the extensions handle the blocks synchronized in parallel
+    assert_eq!(10, counter.0.load(Ordering::SeqCst));
+
     let pool = storage.get_pool();
     assert_eq!(10, pool.fetch_count("mercury_block").await.unwrap());
     assert_eq!(11, pool.fetch_count("mercury_transaction").await.unwrap());
@@ -52,6 +88,24 @@ async fn test_sync() {
     assert_eq!(10, pool.fetch_count("mercury_sync_status").await.unwrap());
     assert_eq!(0, pool.fetch_count("mercury_in_update").await.unwrap());
 
+    // the blocks synchronized in parallel are recorded as chain events in order
+    let events = storage.get_chain_events(0, 100).await.unwrap();
+    assert_eq!(10, events.len());
+    for (number, event) in events.iter().enumerate() {
+        assert_eq!(BlockEventType::BlockApplied, event.event_type);
+        assert_eq!(number as u64, event.block_number.value());
+    }
+    assert!(events
+        .windows(2)
+        .all(|pair| pair[0].id.value() < pair[1].id.value()));
+    assert!(events
+        .windows(2)
+        .all(|pair| pair[0].block_hash == pair[1].parent_hash));
+    let created_count: usize = events.iter().map(|event| event.created_cells.len()).sum();
+    let consumed_count: usize = events.iter().map(|event| event.consumed_cells.len()).sum();
+    assert_eq!(12, created_count);
+    assert_eq!(1, consumed_count);
+
     // check build block view
     let block_hash =
         H256::from_str("10639e0895502b5688a6be8cf69460d76541bfa4821629d86d62ba0aae3f9606").unwrap();
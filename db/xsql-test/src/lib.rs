@@ -13,6 +13,12 @@ pub async fn delete_all_data(mut tx: Transaction<'_, Any>) -> Result<()> {
     delete_script_table_data(&mut tx).await?;
     delete_canonical_chain_table_data(&mut tx).await?;
     delete_registered_address_table_data(&mut tx).await?;
+    delete_submitted_transaction_table_data(&mut tx).await?;
+    delete_address_label_table_data(&mut tx).await?;
+    delete_webhook_table_data(&mut tx).await?;
+    delete_webhook_delivery_table_data(&mut tx).await?;
+    delete_chain_event_table_data(&mut tx).await?;
+    delete_chain_event_cell_table_data(&mut tx).await?;
     delete_sync_status_table_data(&mut tx).await?;
     delete_in_update_table_data(&mut tx).await?;
     tx.commit().await?;
@@ -28,6 +34,12 @@ pub async fn create_tables(mut tx: Transaction<'_, Any>) -> Result<()> {
     create_script_table(&mut tx).await?;
     create_canonical_chain_table(&mut tx).await?;
     create_registered_address_table(&mut tx).await?;
+    create_submitted_transaction_table(&mut tx).await?;
+    create_address_label_table(&mut tx).await?;
+    create_webhook_table(&mut tx).await?;
+    create_webhook_delivery_table(&mut tx).await?;
+    create_chain_event_table(&mut tx).await?;
+    create_chain_event_cell_table(&mut tx).await?;
     create_sync_status_table(&mut tx).await?;
     create_in_update_table(&mut tx).await?;
     tx.commit().await?;
@@ -64,6 +64,48 @@ pub async fn delete_registered_address_table_data(tx: &mut Transaction<'_, Any>)
     Ok(())
 }
 
+pub async fn delete_submitted_transaction_table_data(tx: &mut Transaction<'_, Any>) -> Result<()> {
+    sqlx::query("DELETE FROM mercury_submitted_transaction")
+        .execute(&mut *tx)
+        .await?;
+    Ok(())
+}
+
+pub async fn delete_address_label_table_data(tx: &mut Transaction<'_, Any>) -> Result<()> {
+    sqlx::query("DELETE FROM mercury_address_label")
+        .execute(&mut *tx)
+        .await?;
+    Ok(())
+}
+
+pub async fn delete_webhook_table_data(tx: &mut Transaction<'_, Any>) -> Result<()> {
+    sqlx::query("DELETE FROM mercury_webhook")
+        .execute(&mut *tx)
+        .await?;
+    Ok(())
+}
+
+pub async fn delete_webhook_delivery_table_data(tx: &mut Transaction<'_, Any>) -> Result<()> {
+    sqlx::query("DELETE FROM mercury_webhook_delivery")
+        .execute(&mut *tx)
+        .await?;
+    Ok(())
+}
+
+pub async fn delete_chain_event_table_data(tx: &mut Transaction<'_, Any>) -> Result<()> {
+    sqlx::query("DELETE FROM mercury_chain_event")
+        .execute(&mut *tx)
+        .await?;
+    Ok(())
+}
+
+pub async fn delete_chain_event_cell_table_data(tx: &mut Transaction<'_, Any>) -> Result<()> {
+    sqlx::query("DELETE FROM mercury_chain_event_cell")
+        .execute(&mut *tx)
+        .await?;
+    Ok(())
+}
+
 pub async fn delete_sync_status_table_data(tx: &mut Transaction<'_, Any>) -> Result<()> {
     sqlx::query("DELETE FROM mercury_sync_status")
         .execute(&mut *tx)
@@ -275,6 +317,106 @@ pub async fn create_registered_address_table(tx: &mut Transaction<'_, Any>) -> R
     Ok(())
 }
 
+pub async fn create_submitted_transaction_table(tx: &mut Transaction<'_, Any>) -> Result<()> {
+    sqlx::query(
+        "CREATE TABLE mercury_submitted_transaction(
+        id integer PRIMARY KEY AUTOINCREMENT,
+        tx_hash blob NOT NULL UNIQUE,
+        status smallint NOT NULL,
+        submitted_at bigint NOT NULL,
+        updated_at bigint NOT NULL,
+        block_number int,
+        block_hash blob,
+        reason varchar
+    )",
+    )
+    .execute(&mut *tx)
+    .await?;
+    Ok(())
+}
+
+pub async fn create_address_label_table(tx: &mut Transaction<'_, Any>) -> Result<()> {
+    sqlx::query(
+        "CREATE TABLE mercury_address_label(
+        id integer PRIMARY KEY AUTOINCREMENT,
+        item_type smallint NOT NULL,
+        item varchar NOT NULL UNIQUE,
+        label varchar NOT NULL,
+        metadata varchar,
+        updated_at bigint NOT NULL
+    )",
+    )
+    .execute(&mut *tx)
+    .await?;
+    Ok(())
+}
+
+pub async fn create_webhook_table(tx: &mut Transaction<'_, Any>) -> Result<()> {
+    sqlx::query(
+        "CREATE TABLE mercury_webhook(
+        id integer PRIMARY KEY AUTOINCREMENT,
+        url varchar NOT NULL,
+        items varchar NOT NULL,
+        secret varchar NOT NULL,
+        created_at bigint NOT NULL
+    )",
+    )
+    .execute(&mut *tx)
+    .await?;
+    Ok(())
+}
+
+pub async fn create_webhook_delivery_table(tx: &mut Transaction<'_, Any>) -> Result<()> {
+    sqlx::query(
+        "CREATE TABLE mercury_webhook_delivery(
+        id integer PRIMARY KEY AUTOINCREMENT,
+        webhook_id bigint NOT NULL,
+        payload varchar NOT NULL,
+        status smallint NOT NULL,
+        attempts int NOT NULL,
+        next_attempt_at bigint NOT NULL,
+        last_error varchar,
+        created_at bigint NOT NULL
+    )",
+    )
+    .execute(&mut *tx)
+    .await?;
+    Ok(())
+}
+
+pub async fn create_chain_event_table(tx: &mut Transaction<'_, Any>) -> Result<()> {
+    sqlx::query(
+        "CREATE TABLE mercury_chain_event(
+        id integer PRIMARY KEY AUTOINCREMENT,
+        event_type smallint NOT NULL,
+        block_number int NOT NULL,
+        block_hash blob NOT NULL,
+        parent_hash blob NOT NULL
+    )",
+    )
+    .execute(&mut *tx)
+    .await?;
+    Ok(())
+}
+
+pub async fn create_chain_event_cell_table(tx: &mut Transaction<'_, Any>) -> Result<()> {
+    sqlx::query(
+        "CREATE TABLE mercury_chain_event_cell(
+        event_id bigint NOT NULL,
+        io_type smallint NOT NULL,
+        tx_hash blob NOT NULL,
+        output_index int NOT NULL,
+        lock_hash blob NOT NULL,
+        type_hash blob NOT NULL,
+        capacity bigint NOT NULL,
+        PRIMARY KEY(event_id, io_type, tx_hash, output_index)
+    )",
+    )
+    .execute(&mut *tx)
+    .await?;
+    Ok(())
+}
+
 pub async fn create_sync_status_table(tx: &mut Transaction<'_, Any>) -> Result<()> {
     sqlx::query(
         "CREATE TABLE mercury_sync_status(
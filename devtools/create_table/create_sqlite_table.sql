@@ -126,6 +126,64 @@ CREATE TABLE mercury_registered_address(
     address varchar NOT NULL
 );
 
+CREATE TABLE mercury_submitted_transaction(
+    id integer PRIMARY KEY AUTOINCREMENT,
+    tx_hash blob NOT NULL UNIQUE,
+    status smallint NOT NULL,
+    submitted_at bigint NOT NULL,
+    updated_at bigint NOT NULL,
+    block_number int,
+    block_hash blob,
+    reason varchar
+);
+
+CREATE TABLE mercury_address_label(
+    id integer PRIMARY KEY AUTOINCREMENT,
+    item_type smallint NOT NULL,
+    item varchar NOT NULL UNIQUE,
+    label varchar NOT NULL,
+    metadata varchar,
+    updated_at bigint NOT NULL
+);
+
+CREATE TABLE mercury_webhook(
+    id integer PRIMARY KEY AUTOINCREMENT,
+    url varchar NOT NULL,
+    items varchar NOT NULL,
+    secret varchar NOT NULL,
+    created_at bigint NOT NULL
+);
+
+CREATE TABLE mercury_webhook_delivery(
+    id integer PRIMARY KEY AUTOINCREMENT,
+    webhook_id bigint NOT NULL,
+    payload varchar NOT NULL,
+    status smallint NOT NULL,
+    attempts int NOT NULL,
+    next_attempt_at bigint NOT NULL,
+    last_error varchar,
+    created_at bigint NOT NULL
+);
+
+CREATE TABLE mercury_chain_event(
+    id integer PRIMARY KEY AUTOINCREMENT,
+    event_type smallint NOT NULL,
+    block_number int NOT NULL,
+    block_hash blob NOT NULL,
+    parent_hash blob NOT NULL
+);
+
+CREATE TABLE mercury_chain_event_cell(
+    event_id bigint NOT NULL,
+    io_type smallint NOT NULL,
+    tx_hash blob NOT NULL,
+    output_index int NOT NULL,
+    lock_hash blob NOT NULL,
+    type_hash blob NOT NULL,
+    capacity bigint NOT NULL,
+    PRIMARY KEY(event_id, io_type, tx_hash, output_index)
+);
+
 CREATE TABLE mercury_consume_info(
     tx_hash blob NOT NULL,
     output_index int NOT NULL,
@@ -137,3 +195,6 @@ CREATE TABLE mercury_consume_info(
     since blob NOT NULL,
     PRIMARY KEY(tx_hash, output_index)
 );
+
+CREATE INDEX "index_block_table_block_timestamp" ON "mercury_block" ("block_timestamp");
+CREATE INDEX "index_transaction_table_block_number" ON "mercury_transaction" ("block_number");
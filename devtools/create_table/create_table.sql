@@ -133,7 +133,66 @@ CREATE TABLE mercury_in_update(
     is_in bool NOT NULL PRIMARY KEY
 );
 
+CREATE TABLE mercury_submitted_transaction(
+    id bigserial PRIMARY KEY,
+    tx_hash bytea NOT NULL,
+    status smallint NOT NULL,
+    submitted_at bigint NOT NULL,
+    updated_at bigint NOT NULL,
+    block_number int,
+    block_hash bytea,
+    reason varchar
+);
+
+CREATE TABLE mercury_address_label(
+    id bigserial PRIMARY KEY,
+    item_type smallint NOT NULL,
+    item varchar NOT NULL,
+    label varchar NOT NULL,
+    metadata varchar,
+    updated_at bigint NOT NULL
+);
+
+CREATE TABLE mercury_webhook(
+    id bigserial PRIMARY KEY,
+    url varchar NOT NULL,
+    items varchar NOT NULL,
+    secret varchar NOT NULL,
+    created_at bigint NOT NULL
+);
+
+CREATE TABLE mercury_webhook_delivery(
+    id bigserial PRIMARY KEY,
+    webhook_id bigint NOT NULL,
+    payload varchar NOT NULL,
+    status smallint NOT NULL,
+    attempts int NOT NULL,
+    next_attempt_at bigint NOT NULL,
+    last_error varchar,
+    created_at bigint NOT NULL
+);
+
+CREATE TABLE mercury_chain_event(
+    id bigserial PRIMARY KEY,
+    event_type smallint NOT NULL,
+    block_number int NOT NULL,
+    block_hash bytea NOT NULL,
+    parent_hash bytea NOT NULL
+);
+
+CREATE TABLE mercury_chain_event_cell(
+    event_id bigint NOT NULL,
+    io_type smallint NOT NULL,
+    tx_hash bytea NOT NULL,
+    output_index int NOT NULL,
+    lock_hash bytea NOT NULL,
+    type_hash bytea NOT NULL,
+    capacity bigint NOT NULL,
+    PRIMARY KEY(event_id, io_type, tx_hash, output_index)
+);
+
 CREATE INDEX "index_block_table_block_number" ON "mercury_block" ("block_number");
+CREATE INDEX "index_block_table_block_timestamp" ON "mercury_block" ("block_timestamp");
 
 CREATE INDEX "index_live_cell_table_block_hash" ON "mercury_live_cell" ("block_hash");
 CREATE INDEX "index_live_cell_table_block_number" ON "mercury_live_cell" ("block_number");
@@ -163,6 +222,19 @@ CREATE INDEX "index_cell_table_consumed_block_number" ON "public"."mercury_cell"
 
 CREATE INDEX "index_transaction_table_tx_hash" ON "mercury_transaction" USING btree ("tx_hash" "pg_catalog"."bytea_ops" ASC NULLS LAST);
 CREATE INDEX "index_transaction_table_block_hash" ON "mercury_transaction" USING btree ("block_hash");
+CREATE INDEX "index_transaction_table_block_number" ON "mercury_transaction" ("block_number");
+
+CREATE UNIQUE INDEX "index_submitted_transaction_table_tx_hash" ON "mercury_submitted_transaction" ("tx_hash");
+CREATE INDEX "index_submitted_transaction_table_block_hash" ON "mercury_submitted_transaction" ("block_hash");
+
+CREATE UNIQUE INDEX "index_address_label_table_item" ON "mercury_address_label" ("item");
+CREATE INDEX "index_address_label_table_label" ON "mercury_address_label" ("label");
+
+CREATE INDEX "index_webhook_delivery_table_webhook_id" ON "mercury_webhook_delivery" ("webhook_id");
+CREATE INDEX "index_webhook_delivery_table_status" ON "mercury_webhook_delivery" ("status");
+
+CREATE INDEX "index_chain_event_cell_table_lock_hash" ON "mercury_chain_event_cell" ("lock_hash");
+CREATE INDEX "index_chain_event_cell_table_type_hash" ON "mercury_chain_event_cell" ("type_hash");
 
 CREATE INDEX "index_indexer_cell_table_lock_hash" ON "public"."mercury_indexer_cell" ("lock_hash");
 CREATE INDEX "index_indexer_cell_table_tx_hash" ON "mercury_indexer_cell" USING btree (
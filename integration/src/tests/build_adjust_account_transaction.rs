@@ -40,6 +40,8 @@ fn test_adjust_account() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let response = mercury_client.get_balance(payload.clone()).unwrap();
     assert_eq!(response.balances.len(), 1);
@@ -82,6 +84,8 @@ fn test_adjust_account_pw_lock() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let response = mercury_client.get_balance(payload.clone()).unwrap();
     assert_eq!(response.balances.len(), 1);
@@ -131,6 +135,7 @@ fn test_adjust_account_from_multi() {
         account_number: Some(5u32.into()),
         extra_ckb: None,
         fee_rate: None,
+        verify: None,
     };
     let mercury_client = MercuryRpcClient::new(MERCURY_URI.to_string());
     let tx = mercury_client
@@ -148,6 +153,8 @@ fn test_adjust_account_from_multi() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let response = mercury_client.get_balance(payload.clone()).unwrap();
     assert_eq!(response.balances.len(), 1);
@@ -164,6 +171,7 @@ fn test_adjust_account_from_multi() {
         account_number: Some(1u32.into()),
         extra_ckb: None,
         fee_rate: None,
+        verify: None,
     };
     let mercury_client = MercuryRpcClient::new(MERCURY_URI.to_string());
     let tx = mercury_client
@@ -190,6 +198,9 @@ fn test_adjust_account_from_multi() {
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let tx = mercury_client
         .build_transfer_transaction(transfer_payload)
@@ -208,6 +219,7 @@ fn test_adjust_account_from_multi() {
         account_number: Some(0u32.into()),
         extra_ckb: None,
         fee_rate: None,
+        verify: None,
     };
     let mercury_client = MercuryRpcClient::new(MERCURY_URI.to_string());
     let tx = mercury_client.build_adjust_account_transaction(adjust_account_payload);
@@ -31,6 +31,7 @@ fn test_dao_by_address() {
         to: None,
         amount: 200_0000_0000.into(),
         fee_rate: None,
+        verify: None,
     };
     let tx = mercury_client
         .build_dao_deposit_transaction(payload)
@@ -46,6 +47,8 @@ fn test_dao_by_address() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(balance_payload.clone()).unwrap();
     assert_eq!(balance.balances.len(), 1);
@@ -55,7 +58,10 @@ fn test_dao_by_address() {
     // withdraw
     let withdraw_payload = DaoWithdrawPayload {
         from: vec![JsonItem::Address(address.to_string())],
+        out_points: None,
+        amount: None,
         fee_rate: None,
+        verify: None,
     };
     let tx = mercury_client.build_dao_withdraw_transaction(withdraw_payload.clone());
     assert!(tx.is_err());
@@ -64,7 +70,10 @@ fn test_dao_by_address() {
     let claim_payload = DaoClaimPayload {
         from: vec![JsonItem::Address(address.to_string())],
         to: None,
+        out_points: None,
+        amount: None,
         fee_rate: None,
+        verify: None,
     };
     let tx = mercury_client.build_dao_claim_transaction(claim_payload.clone());
     assert!(tx.is_err());
@@ -129,6 +138,7 @@ fn test_dao_pool_money() {
         to: None,
         amount: 200_0000_0000.into(),
         fee_rate: None,
+        verify: None,
     };
     let tx = mercury_client
         .build_dao_deposit_transaction(payload)
@@ -139,7 +149,10 @@ fn test_dao_pool_money() {
     // withdraw
     let withdraw_payload = DaoWithdrawPayload {
         from: vec![JsonItem::Address(address.to_string())],
+        out_points: None,
+        amount: None,
         fee_rate: None,
+        verify: None,
     };
     let tx = mercury_client.build_dao_withdraw_transaction(withdraw_payload.clone());
     assert!(tx.is_err());
@@ -148,7 +161,10 @@ fn test_dao_pool_money() {
     let claim_payload = DaoClaimPayload {
         from: vec![JsonItem::Address(address.to_string())],
         to: None,
+        out_points: None,
+        amount: None,
         fee_rate: None,
+        verify: None,
     };
     let tx = mercury_client.build_dao_claim_transaction(claim_payload);
     assert!(tx.is_err());
@@ -175,6 +191,9 @@ fn test_dao_pool_money() {
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let tx = mercury_client.build_transfer_transaction(transfer_payload.clone());
     assert!(tx.is_err());
@@ -196,6 +215,8 @@ fn test_dao_pool_money() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(balance_payload).unwrap();
     assert_eq!(balance.balances.len(), 1);
@@ -225,6 +246,7 @@ fn test_dao_by_out_point() {
         to: None,
         amount: 200_0000_0000.into(),
         fee_rate: None,
+        verify: None,
     };
     let tx = mercury_client
         .build_dao_deposit_transaction(payload)
@@ -242,6 +264,7 @@ fn test_dao_by_out_point() {
         to: None,
         amount: 200_0000_0000.into(),
         fee_rate: None,
+        verify: None,
     };
     let tx = mercury_client
         .build_dao_deposit_transaction(payload)
@@ -261,6 +284,8 @@ fn test_dao_by_out_point() {
         asset_infos: asset_infos.clone(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client
         .get_balance(balance_payload_1.clone())
@@ -275,6 +300,8 @@ fn test_dao_by_out_point() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client
         .get_balance(balance_payload_2.clone())
@@ -290,7 +317,10 @@ fn test_dao_by_out_point() {
             JsonItem::OutPoint(out_point_deposit_2),
             JsonItem::Address(address_2.to_string()),
         ],
+        out_points: None,
+        amount: None,
         fee_rate: None,
+        verify: None,
     };
     let tx = mercury_client.build_dao_withdraw_transaction(withdraw_payload.clone());
     assert!(tx.is_err());
@@ -302,7 +332,10 @@ fn test_dao_by_out_point() {
             JsonItem::Address(address_2.to_string()),
         ],
         to: None,
+        out_points: None,
+        amount: None,
         fee_rate: None,
+        verify: None,
     };
     let tx = mercury_client.build_dao_claim_transaction(claim_payload.clone());
     assert!(tx.is_err());
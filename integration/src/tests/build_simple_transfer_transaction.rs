@@ -37,6 +37,9 @@ fn test_simple_transfer_ckb() {
         }],
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let mercury_client = MercuryRpcClient::new(MERCURY_URI.to_string());
     let tx = mercury_client
@@ -55,6 +58,8 @@ fn test_simple_transfer_ckb() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let to_balance = mercury_client.get_balance(payload).unwrap();
 
@@ -70,6 +75,8 @@ fn test_simple_transfer_ckb() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let from_balance = mercury_client.get_balance(payload).unwrap();
     let from_left_capacity = from_balance.balances[0].free.into();
@@ -116,6 +123,9 @@ fn test_simple_transfer_udt_to_provide_capacity() {
         }],
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let mercury_client = MercuryRpcClient::new(MERCURY_URI.to_string());
     let tx = mercury_client
@@ -134,6 +144,8 @@ fn test_simple_transfer_udt_to_provide_capacity() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let to_balance = mercury_client.get_balance(payload).unwrap();
     let balance_ckb_secp = to_balance
@@ -188,6 +200,9 @@ fn test_simple_transfer_udt_from_provide_capacity() {
         }],
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let mercury_client = MercuryRpcClient::new(MERCURY_URI.to_string());
     let tx = mercury_client
@@ -206,6 +221,8 @@ fn test_simple_transfer_udt_from_provide_capacity() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let to_balance = mercury_client.get_balance(payload).unwrap();
     assert_eq!(to_balance.balances.len(), 1);
@@ -218,6 +235,8 @@ fn test_simple_transfer_udt_from_provide_capacity() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let from_balance = mercury_client.get_balance(payload).unwrap();
     let ckb_balance = &from_balance.balances[0];
@@ -42,6 +42,8 @@ fn test_issue_udt_from_provide_capacity() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let to_balance = mercury_client.get_balance(payload_to).unwrap();
     let udt_balance = &to_balance.balances[0];
@@ -90,6 +92,7 @@ fn test_issue_udt_from_multi_item() {
         output_capacity_provider: Some(OutputCapacityProvider::From),
         fee_rate: None,
         since: None,
+        verify: None,
     };
 
     let mercury_client = MercuryRpcClient::new(MERCURY_URI.to_string());
@@ -113,6 +116,8 @@ fn test_issue_udt_from_multi_item() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let to_balance = mercury_client.get_balance(payload_to.clone()).unwrap();
     let udt_balance = &to_balance.balances[0];
@@ -141,6 +146,7 @@ fn test_issue_udt_from_multi_item() {
         output_capacity_provider: None,
         fee_rate: None,
         since: None,
+        verify: None,
     };
 
     // build tx
@@ -35,6 +35,9 @@ fn test_transfer_ckb_from_provide_capacity() {
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
 
     // build tx
@@ -53,6 +56,8 @@ fn test_transfer_ckb_from_provide_capacity() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let to_balance = mercury_client.get_balance(payload).unwrap();
 
@@ -68,6 +73,8 @@ fn test_transfer_ckb_from_provide_capacity() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let from_balance = mercury_client.get_balance(payload).unwrap();
     let from_left_capacity = from_balance.balances[0].free.into();
@@ -119,6 +126,9 @@ fn test_transfer_ckb_to_provide_capacity() {
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let mercury_client = MercuryRpcClient::new(MERCURY_URI.to_string());
     let tx = mercury_client.build_transfer_transaction(payload).unwrap();
@@ -135,6 +145,8 @@ fn test_transfer_ckb_to_provide_capacity() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let from_balance = mercury_client.get_balance(payload).unwrap();
     let from_left_capacity = from_balance.balances[0].free.into();
@@ -186,6 +198,8 @@ fn test_change_to_new_output() {
         asset_infos: asset_infos.clone(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
     assert!(107u128 < balance.balances[0].free.into());
@@ -195,6 +209,8 @@ fn test_change_to_new_output() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
     assert!(142u128 < balance.balances[0].occupied.into());
@@ -215,6 +231,9 @@ fn test_change_to_new_output() {
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let mercury_client = MercuryRpcClient::new(MERCURY_URI.to_string());
     let tx = mercury_client.build_transfer_transaction(payload).unwrap();
@@ -233,6 +252,8 @@ fn test_change_to_new_output() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
     let ckb_balance = &balance.balances[0];
@@ -265,6 +286,9 @@ fn test_transfer_ckb_from_provide_capacity_out_point() {
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
 
     // build tx
@@ -286,6 +310,9 @@ fn test_transfer_ckb_from_provide_capacity_out_point() {
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
 
     // build tx
@@ -303,6 +330,8 @@ fn test_transfer_ckb_from_provide_capacity_out_point() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
 
@@ -335,6 +364,9 @@ fn test_transfer_ckb_from_provide_capacity_to_acp_address() {
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
 
     // build tx
@@ -352,6 +384,8 @@ fn test_transfer_ckb_from_provide_capacity_to_acp_address() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
 
@@ -384,6 +418,9 @@ fn test_transfer_ckb_from_provide_capacity_pay_fee_to() {
         pay_fee: Some(PayFee::To),
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let mercury_client = MercuryRpcClient::new(MERCURY_URI.to_string());
     let tx = mercury_client.build_transfer_transaction(payload).unwrap();
@@ -400,6 +437,8 @@ fn test_transfer_ckb_from_provide_capacity_pay_fee_to() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let from_balance = mercury_client.get_balance(payload).unwrap();
     let from_left_capacity = from_balance.balances[0].free.into();
@@ -419,6 +458,8 @@ fn test_transfer_ckb_from_provide_capacity_pay_fee_to() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
     let to_capacity = balance.balances[0].free.into();
@@ -467,6 +508,9 @@ fn test_transfer_ckb_to_provide_capacity_to_pay_fee() {
         pay_fee: Some(PayFee::To),
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let mercury_client = MercuryRpcClient::new(MERCURY_URI.to_string());
     let tx = mercury_client.build_transfer_transaction(payload).unwrap();
@@ -483,6 +527,8 @@ fn test_transfer_ckb_to_provide_capacity_to_pay_fee() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
     let from_capacity = balance.balances[0].free.into();
@@ -499,6 +545,8 @@ fn test_transfer_ckb_to_provide_capacity_to_pay_fee() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
     let to_free_capacity: u128 = balance.balances[0].free.into();
@@ -557,6 +605,8 @@ fn test_change_to_new_acp() {
         asset_infos: asset_infos.clone(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
     assert!(107_0000_0000u128 < balance.balances[0].free.into());
@@ -566,6 +616,8 @@ fn test_change_to_new_acp() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
     assert_eq!(142_0000_0000u128, balance.balances[0].occupied.into());
@@ -588,6 +640,9 @@ fn test_change_to_new_acp() {
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let mercury_client = MercuryRpcClient::new(MERCURY_URI.to_string());
     let tx = mercury_client.build_transfer_transaction(payload).unwrap();
@@ -606,6 +661,8 @@ fn test_change_to_new_acp() {
         asset_infos: asset_infos.clone(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
     let ckb_balance = &balance.balances[0];
@@ -618,6 +675,8 @@ fn test_change_to_new_acp() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
     let ckb_balance = &balance.balances[0];
@@ -632,6 +691,8 @@ fn test_change_to_new_acp() {
         asset_infos: asset_infos.clone(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
     let ckb_balance = &balance.balances[0];
@@ -674,6 +735,9 @@ fn test_change_need_pool() {
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let tx = mercury_client.build_transfer_transaction(payload).unwrap();
     let tx = sign_transaction(tx, &pks).unwrap();
@@ -693,6 +757,8 @@ fn test_change_need_pool() {
         asset_infos: asset_infos.clone(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
     let ckb_balance = &balance.balances[0];
@@ -759,6 +825,9 @@ fn test_change_to_output_acp() {
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let tx = mercury_client.build_transfer_transaction(payload).unwrap();
     let tx = sign_transaction(tx, &pks).unwrap();
@@ -776,6 +845,8 @@ fn test_change_to_output_acp() {
         asset_infos: asset_infos.clone(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
     assert_eq!(balance.balances.len(), 0);
@@ -786,6 +857,8 @@ fn test_change_to_output_acp() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
     let ckb_balance = &balance.balances[0];
@@ -802,6 +875,8 @@ fn test_change_to_output_acp() {
         asset_infos: asset_infos.clone(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
     let ckb_balance = &balance.balances[0];
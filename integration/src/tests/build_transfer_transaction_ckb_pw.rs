@@ -31,6 +31,9 @@ fn test_transfer_ckb_from_provide_capacity_pw() {
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
 
     // build tx
@@ -49,6 +52,8 @@ fn test_transfer_ckb_from_provide_capacity_pw() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let to_balance = mercury_client.get_balance(payload).unwrap();
 
@@ -64,6 +69,8 @@ fn test_transfer_ckb_from_provide_capacity_pw() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let from_balance = mercury_client.get_balance(payload).unwrap();
     let from_left_capacity = from_balance.balances[0].free.into();
@@ -97,6 +104,9 @@ fn test_transfer_ckb_from_provide_capacity_pw_pay_fee_to() {
         pay_fee: Some(PayFee::To),
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
 
     // build tx
@@ -115,6 +125,8 @@ fn test_transfer_ckb_from_provide_capacity_pw_pay_fee_to() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let to_balance = mercury_client.get_balance(payload).unwrap();
 
@@ -131,6 +143,8 @@ fn test_transfer_ckb_from_provide_capacity_pw_pay_fee_to() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let from_balance = mercury_client.get_balance(payload).unwrap();
     let from_left_capacity = from_balance.balances[0].free.into();
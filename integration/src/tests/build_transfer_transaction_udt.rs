@@ -67,6 +67,9 @@ fn test_transfer_udt_to_provide_capacity_from_identity_has_in_lock_cheque() {
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let tx = mercury_client.build_transfer_transaction(payload);
     assert!(tx.is_err());
@@ -87,6 +90,9 @@ fn test_transfer_udt_to_provide_capacity_from_identity_has_in_lock_cheque() {
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let tx = mercury_client.build_transfer_transaction(payload).unwrap();
     let tx = sign_transaction(tx, &[receiver_address_pk]).unwrap();
@@ -98,6 +104,8 @@ fn test_transfer_udt_to_provide_capacity_from_identity_has_in_lock_cheque() {
         asset_infos: HashSet::new(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let from_balance = mercury_client.get_balance(payload).unwrap();
     let from_left_capacity = from_balance.balances[0].free.into();
@@ -152,6 +160,9 @@ fn test_transfer_udt_to_provide_capacity_from_sender_cheque() {
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let mercury_client = MercuryRpcClient::new(MERCURY_URI.to_string());
     let tx = mercury_client.build_transfer_transaction(payload).unwrap();
@@ -164,6 +175,8 @@ fn test_transfer_udt_to_provide_capacity_from_sender_cheque() {
         asset_infos: HashSet::new(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let from_balance = mercury_client.get_balance(payload).unwrap();
     let from_left_capacity = from_balance.balances[0].free.into();
@@ -219,6 +232,9 @@ fn test_transfer_udt_to_provide_capacity_from_receiver_cheque() {
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let mercury_client = MercuryRpcClient::new(MERCURY_URI.to_string());
     let tx = mercury_client.build_transfer_transaction(payload).unwrap();
@@ -231,6 +247,8 @@ fn test_transfer_udt_to_provide_capacity_from_receiver_cheque() {
         asset_infos: HashSet::new(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let from_balance = mercury_client.get_balance(payload).unwrap();
     let from_left_capacity = from_balance.balances[0].free.into();
@@ -285,6 +303,9 @@ fn test_transfer_udt_to_provide_capacity_from_receiver_cheque_change_udt() {
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let tx = mercury_client.build_transfer_transaction(payload).unwrap();
     let tx = sign_transaction(tx, &[receiver_address_pk]).unwrap();
@@ -296,6 +317,8 @@ fn test_transfer_udt_to_provide_capacity_from_receiver_cheque_change_udt() {
         asset_infos: HashSet::new(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let from_balance = mercury_client.get_balance(payload).unwrap();
     let (ckb_balance, udt_balance) = (&from_balance.balances[0], &from_balance.balances[1]);
@@ -310,6 +333,8 @@ fn test_transfer_udt_to_provide_capacity_from_receiver_cheque_change_udt() {
         asset_infos: HashSet::new(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
     assert_eq!(balance.balances.len(), 1);
@@ -371,6 +396,9 @@ fn test_transfer_udt_to_provide_capacity_from_receiver_has_cheque_change_udt_to_
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let tx = mercury_client.build_transfer_transaction(payload).unwrap();
     let tx = sign_transaction(tx, &[receiver_address_pk]).unwrap();
@@ -382,6 +410,8 @@ fn test_transfer_udt_to_provide_capacity_from_receiver_has_cheque_change_udt_to_
         asset_infos: HashSet::new(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let from_balance = mercury_client.get_balance(payload).unwrap();
     let (ckb_balance, udt_balance) = (&from_balance.balances[0], &from_balance.balances[1]);
@@ -396,6 +426,8 @@ fn test_transfer_udt_to_provide_capacity_from_receiver_has_cheque_change_udt_to_
         asset_infos: HashSet::new(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
     assert_eq!(balance.balances.len(), 1);
@@ -460,6 +492,9 @@ fn test_transfer_udt_to_provide_capacity_from_out_point_cheque_part_claim() {
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let tx = mercury_client.build_transfer_transaction(payload).unwrap();
     let tx = sign_transaction(tx, &[receiver_address_pk]).unwrap();
@@ -471,6 +506,8 @@ fn test_transfer_udt_to_provide_capacity_from_out_point_cheque_part_claim() {
         asset_infos: HashSet::new(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
     let capacity = balance.balances[0].free.into();
@@ -484,6 +521,8 @@ fn test_transfer_udt_to_provide_capacity_from_out_point_cheque_part_claim() {
         asset_infos: HashSet::new(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
     let (ckb_balance, udt_balance) = (&balance.balances[0], &balance.balances[1]);
@@ -542,6 +581,9 @@ fn test_transfer_udt_to_provide_capacity_from_cheque_address_part_claim() {
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let tx = mercury_client.build_transfer_transaction(payload).unwrap();
     let tx = sign_transaction(tx, &[receiver_address_pk]).unwrap();
@@ -553,6 +595,8 @@ fn test_transfer_udt_to_provide_capacity_from_cheque_address_part_claim() {
         asset_infos: HashSet::new(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
     let capacity = balance.balances[0].free.into();
@@ -566,6 +610,8 @@ fn test_transfer_udt_to_provide_capacity_from_cheque_address_part_claim() {
         asset_infos: HashSet::new(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
     let (ckb_balance, udt_balance) = (&balance.balances[0], &balance.balances[1]);
@@ -603,6 +649,9 @@ fn test_transfer_udt_from_provide_capacity_acp() {
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let mercury_client = MercuryRpcClient::new(MERCURY_URI.to_string());
     let tx = mercury_client.build_transfer_transaction(payload).unwrap();
@@ -617,6 +666,8 @@ fn test_transfer_udt_from_provide_capacity_acp() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let to_balance = mercury_client.get_balance(payload).unwrap();
     let (ckb_balance, udt_balance) = (&to_balance.balances[0], &to_balance.balances[1]);
@@ -676,6 +727,9 @@ fn test_transfer_udt_to_provide_capacity_from_sender_has_cheque_part_withdraw()
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let tx = mercury_client.build_transfer_transaction(payload).unwrap();
     let tx = sign_transaction_for_cheque_of_sender(tx, &sender_address_pk, vec![1]).unwrap();
@@ -687,6 +741,8 @@ fn test_transfer_udt_to_provide_capacity_from_sender_has_cheque_part_withdraw()
         asset_infos: HashSet::new(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload).unwrap();
     let (ckb_balance, udt_balance) = (&balance.balances[0], &balance.balances[1]);
@@ -734,6 +790,9 @@ fn test_transfer_udt_pay_fee_to() {
         pay_fee: Some(PayFee::To),
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let mercury_client = MercuryRpcClient::new(MERCURY_URI.to_string());
     let tx = mercury_client.build_transfer_transaction(payload);
@@ -757,6 +816,9 @@ fn test_transfer_udt_pay_fee_to() {
         pay_fee: Some(PayFee::To),
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let tx = mercury_client.build_transfer_transaction(payload);
 
@@ -777,6 +839,9 @@ fn test_transfer_udt_pay_fee_to() {
         pay_fee: Some(PayFee::To),
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let tx = mercury_client.build_transfer_transaction(payload);
 
@@ -32,6 +32,8 @@ fn test_get_balance_of_genesis_built_in_address_1() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let mercury_client = MercuryRpcClient::new(MERCURY_URI.to_string());
     let response = mercury_client.get_balance(payload).unwrap();
@@ -56,6 +58,8 @@ fn test_get_balance_of_udt_1_holder_address() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let mercury_client = MercuryRpcClient::new(MERCURY_URI.to_string());
     let balance = mercury_client.get_balance(payload).unwrap();
@@ -102,6 +106,8 @@ fn test_get_balance_of_item_has_cheque() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let receiver_balance = mercury_client
         .get_balance(payload_receiver.clone())
@@ -118,6 +124,8 @@ fn test_get_balance_of_item_has_cheque() {
         asset_infos,
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let to_balance = mercury_client.get_balance(payload).unwrap();
     assert_eq!(to_balance.balances.len(), 0);
@@ -128,6 +136,8 @@ fn test_get_balance_of_item_has_cheque() {
         asset_infos: HashSet::new(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let to_balance = mercury_client.get_balance(payload).unwrap();
     assert_eq!(to_balance.balances.len(), 1);
@@ -139,6 +149,8 @@ fn test_get_balance_of_item_has_cheque() {
         asset_infos: HashSet::new(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let sender_balance = mercury_client.get_balance(payload_sender.clone()).unwrap();
     let sender_balance_ckb_secp = sender_balance
@@ -179,6 +191,8 @@ fn test_get_balance_of_item_has_cheque() {
         asset_infos: HashSet::new(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload_out_point).unwrap();
     let (ckb_balance, udt_balance) = (&balance.balances[0], &balance.balances[1]);
@@ -193,6 +207,8 @@ fn test_get_balance_of_item_has_cheque() {
         asset_infos: HashSet::new(),
         extra: None,
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(payload_out_point).unwrap();
     let (ckb_balance, udt_balance) = (&balance.balances[0], &balance.balances[1]);
@@ -239,6 +255,7 @@ fn test_dao_get_balance() {
         to: None,
         amount: 200_0000_0000.into(),
         fee_rate: None,
+        verify: None,
     };
     let tx = mercury_client
         .build_dao_deposit_transaction(payload)
@@ -254,6 +271,8 @@ fn test_dao_get_balance() {
         asset_infos,
         extra: Some(ExtraType::Dao),
         tip_block_number: None,
+        detailed: None,
+        min_confirmations: None,
     };
     let balance = mercury_client.get_balance(balance_payload.clone()).unwrap();
     assert_eq!(balance.balances.len(), 1);
@@ -265,7 +284,10 @@ fn test_dao_get_balance() {
     // withdraw
     let withdraw_payload = DaoWithdrawPayload {
         from: vec![JsonItem::Address(address.to_string())],
+        out_points: None,
+        amount: None,
         fee_rate: None,
+        verify: None,
     };
     let tx = mercury_client.build_dao_withdraw_transaction(withdraw_payload.clone());
     assert!(tx.is_err());
@@ -274,7 +296,10 @@ fn test_dao_get_balance() {
     let claim_payload = DaoClaimPayload {
         from: vec![JsonItem::Address(address.to_string())],
         to: None,
+        out_points: None,
+        amount: None,
         fee_rate: None,
+        verify: None,
     };
     let tx = mercury_client.build_dao_claim_transaction(claim_payload.clone());
     assert!(tx.is_err());
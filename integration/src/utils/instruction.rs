@@ -205,6 +205,9 @@ pub(crate) fn prepare_secp_address_with_ckb_capacity(
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let mercury_client = MercuryRpcClient::new(MERCURY_URI.to_string());
     let tx = mercury_client.build_transfer_transaction(payload)?;
@@ -249,6 +252,7 @@ pub(crate) fn issue_udt_with_cheque(
         output_capacity_provider: Some(OutputCapacityProvider::From),
         fee_rate: None,
         since: None,
+        verify: None,
     };
 
     // build tx
@@ -276,6 +280,7 @@ pub(crate) fn issue_udt_with_acp(
         output_capacity_provider: Some(OutputCapacityProvider::From),
         fee_rate: None,
         since: None,
+        verify: None,
     };
 
     // build tx
@@ -301,6 +306,7 @@ pub(crate) fn prepare_account(
         account_number: account_number.map(Into::into),
         extra_ckb: None,
         fee_rate: None,
+        verify: None,
     };
     let mercury_client = MercuryRpcClient::new(MERCURY_URI.to_string());
     let tx = mercury_client.build_adjust_account_transaction(payload)?;
@@ -324,6 +330,9 @@ pub(crate) fn prepare_pw_address_with_capacity(capacity: u64) -> Result<(Address
         pay_fee: None,
         fee_rate: None,
         since: None,
+        multisig_configs: None,
+        coin_selection: None,
+        verify: None,
     };
     let mercury_client = MercuryRpcClient::new(MERCURY_URI.to_string());
     let tx = mercury_client.build_transfer_transaction(payload)?;
@@ -1,7 +1,7 @@
 use ckb_jsonrpc_types::TransactionWithStatus;
 use ckb_types::core::{BlockNumber, RationalU256, TransactionView};
 use ckb_types::H256;
-use common::DetailedCell;
+use common::{DetailedCell, Range};
 use serde::{Deserialize, Serialize};
 
 pub type IteratorItem = (Box<[u8]>, Box<[u8]>);
@@ -72,6 +72,50 @@ pub struct SimpleBlock {
     pub transactions: Vec<H256>,
 }
 
+/// The filters of the transactions queried by scripts, which are applied to the
+/// transactions involving the queried lock scripts.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransactionFilter {
+    /// Keep the transactions which also involve one of the lock scripts.
+    pub counterparty_lock_hashes: Vec<H256>,
+    pub direction: Option<TransactionDirection>,
+    /// Keep the transactions whose absolute net change of the capacity of the
+    /// queried lock scripts is in the range.
+    pub capacity_change_range: Option<Range>,
+    /// Keep the transactions whose absolute net change of the amount of the UDT of the
+    /// type hash, held by the queried lock scripts, is in the range.
+    pub udt_amount_change_range: Option<(H256, Range)>,
+    /// Keep the transactions whose block timestamp is in the range.
+    pub time_range: Option<Range>,
+}
+
+impl TransactionFilter {
+    pub fn is_empty(&self) -> bool {
+        self == &TransactionFilter::default()
+    }
+}
+
+/// An outgoing transaction consumes at least one cell of the queried lock scripts,
+/// while an incoming transaction consumes none.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransactionDirection {
+    Incoming,
+    Outgoing,
+}
+
+/// An event queued for a webhook, with the url and the secret of the webhook. The
+/// timestamp is in milliseconds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebhookDelivery {
+    pub id: u64,
+    pub webhook_id: u64,
+    pub url: String,
+    pub secret: String,
+    pub payload: String,
+    pub attempts: u32,
+    pub next_attempt_at: u64,
+}
+
 #[derive(Clone, Debug)]
 pub struct TransactionWrapper {
     pub transaction_with_status: TransactionWithStatus,